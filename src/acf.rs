@@ -0,0 +1,132 @@
+use num::{Float, FromPrimitive};
+use std::collections::VecDeque;
+use std::ops::{AddAssign, SubAssign};
+
+use crate::covariance::Covariance;
+use crate::stats::{Bivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running autocorrelation function up to `max_lag`.
+/// The last `max_lag` values are buffered and each new value `x_t` updates the covariance of the
+/// pairs `(x_{t-k}, x_t)` of every lag `k`. The autocorrelation at lag `k` is that covariance
+/// divided by the variance of the whole stream, both with `ddof = 0`. It converges to the
+/// estimator of `statsmodels.tsa.stattools.acf`, the gap being of order `k / n`.
+///
+/// The lag `k` needs `k + 1` values to get its first pair: until then, and while the stream is
+/// constant, its autocorrelation is `NaN`. `get` returns the autocorrelation at lag 1.
+/// # Arguments
+/// * `max_lag` - Largest lag, must be at least 1.
+/// # Examples
+/// ```
+/// use watermill::acf::ACF;
+/// use watermill::stats::Univariate;
+/// let mut running_acf: ACF<f64> = ACF::new(2).unwrap();
+/// for x in [1., -1., 1., -1., 1., -1.].iter(){
+///     running_acf.update(*x);
+/// }
+/// let acf = running_acf.acf();
+/// // The lag 1 pairs have the means 0.2 and -0.2 rather than the overall mean 0.
+/// assert!((acf[1] + 0.96).abs() < 1e-12);
+/// assert!((acf[2] - 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on autocorrelation](https://en.wikipedia.org/wiki/Autocorrelation)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ACF<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub max_lag: usize,
+    /// Covariance of the pairs `(x_{t-k}, x_t)`, at index `k - 1`.
+    pub covariances: Vec<Covariance<F>>,
+    pub variance: Variance<F>,
+    /// Last `max_lag` values, the most recent at the back.
+    window: VecDeque<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ACF<F> {
+    pub fn new(max_lag: usize) -> Result<Self, &'static str> {
+        if max_lag == 0 {
+            return Err("max_lag should be at least 1");
+        }
+        Ok(Self {
+            max_lag,
+            covariances: vec![Covariance::new(0); max_lag],
+            variance: Variance::new(0),
+            window: VecDeque::with_capacity(max_lag),
+        })
+    }
+    /// Autocorrelations from lag 0 to `max_lag`, lag 0 being 1 once the stream is not constant.
+    pub fn acf(&self) -> Vec<F> {
+        (0..=self.max_lag).map(|lag| self.at(lag)).collect()
+    }
+    /// Autocorrelation at a single lag, `NaN` if `lag > max_lag`.
+    pub fn at(&self, lag: usize) -> F {
+        let variance = self.variance.get();
+        if lag > self.max_lag || variance == F::from_f64(0.).unwrap() {
+            return F::nan();
+        }
+        if lag == 0 {
+            return F::from_f64(1.).unwrap();
+        }
+        let covariance = &self.covariances[lag - 1];
+        if covariance.n_samples() == Some(0) {
+            return F::nan();
+        }
+        covariance.get() / variance
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for ACF<F> {
+    fn update(&mut self, x: F) {
+        // The value `k` steps back is at `len - k`.
+        let len = self.window.len();
+        for (lag, covariance) in self.covariances.iter_mut().enumerate().take(len) {
+            covariance.update(self.window[len - 1 - lag], x);
+        }
+        self.variance.update(x);
+        if self.window.len() == self.max_lag {
+            self.window.pop_front();
+        }
+        self.window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.at(1)
+    }
+    fn reset(&mut self) {
+        self.covariances = vec![Covariance::new(0); self.max_lag];
+        self.variance = Variance::new(0);
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn close_to_statsmodels() {
+        use crate::acf::ACF;
+        use crate::stats::Univariate;
+        let mut running_acf: ACF<f64> = ACF::new(3).unwrap();
+        assert!(running_acf.get().is_nan());
+        for t in 0..500 {
+            running_acf.update((0.5 * t as f64).sin() + 0.1 * (t % 7) as f64);
+            if t < 3 {
+                assert!(running_acf.at(t + 1).is_nan());
+            }
+        }
+        // `statsmodels.tsa.stattools.acf(data, nlags=3)`
+        let expected = [
+            1.0,
+            0.8286814381786767,
+            0.47757935452114103,
+            0.024470975613677767,
+        ];
+        for (acf, expected) in running_acf.acf().iter().zip(expected.iter()) {
+            assert!((acf - expected).abs() < 5e-3);
+        }
+        assert!(running_acf.at(4).is_nan());
+        running_acf.reset();
+        assert!(running_acf.acf()[1].is_nan());
+        assert!(ACF::<f64>::new(0).is_err());
+    }
+}
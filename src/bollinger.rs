@@ -0,0 +1,132 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use crate::variance::{RollingVariance, Variance};
+use serde::{Deserialize, Serialize};
+/// Bollinger bands, `mean ± k * std` over a rolling window.
+/// `new` uses the population standard deviation (`ddof = 0`), as in the original definition,
+/// `with_ddof` changes it, e.g. to `1` to match pandas' default rolling standard deviation.
+/// `get` returns the middle band, i.e. the rolling mean.
+/// # Arguments
+/// * `window_size` - Size of the rolling window, usually `20`.
+/// * `k` - Width of the bands, in standard deviations, usually `2`.
+/// * `ddof` - Delta Degrees of Freedom of the standard deviation. Defaults to `0`.
+/// # Examples
+/// ```
+/// use watermill::bollinger::BollingerBands;
+/// use watermill::stats::Univariate;
+/// let mut bands: BollingerBands<f64> = BollingerBands::new(4, 2.).unwrap();
+/// for x in [1., 3., 1., 3.].iter(){
+///     bands.update(*x);
+/// }
+/// assert_eq!(bands.middle(), 2.);
+/// assert_eq!(bands.upper(), 4.);
+/// assert_eq!(bands.lower(), 0.);
+/// assert_eq!(bands.percent_b(3.), 0.75);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on Bollinger bands](https://en.wikipedia.org/wiki/Bollinger_Bands)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BollingerBands<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Variance of the window, its mean is the middle band.
+    pub variance: RollingVariance<F>,
+    pub k: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> BollingerBands<F> {
+    pub fn new(window_size: usize, k: F) -> Result<Self, &'static str> {
+        Self::with_ddof(window_size, k, 0)
+    }
+    pub fn with_ddof(window_size: usize, k: F, ddof: u32) -> Result<Self, &'static str> {
+        if k < F::from_f64(0.).unwrap() {
+            return Err("k should be positive");
+        }
+        Ok(Self {
+            variance: RollingVariance::new(Variance::new(ddof), window_size)?,
+            k,
+        })
+    }
+    /// Rolling mean.
+    pub fn middle(&self) -> F {
+        self.variance.to_roll.mean.get()
+    }
+    /// `middle() + k * std`.
+    pub fn upper(&self) -> F {
+        self.middle() + self.k * self.std()
+    }
+    /// `middle() - k * std`.
+    pub fn lower(&self) -> F {
+        self.middle() - self.k * self.std()
+    }
+    /// Position of `x` relative to the bands: `0` on the lower band, `1` on the upper band and
+    /// outside of `[0, 1]` beyond them. `NaN` or infinite when the bands are collapsed.
+    pub fn percent_b(&self, x: F) -> F {
+        let lower = self.lower();
+        (x - lower) / (self.upper() - lower)
+    }
+    fn std(&self) -> F {
+        // Reverting values can leave a tiny negative variance.
+        self.variance.get().max(F::from_f64(0.).unwrap()).sqrt()
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.variance.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.variance.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.variance.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for BollingerBands<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.middle()
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn breakout_above_the_upper_band() {
+        use crate::bollinger::BollingerBands;
+        use crate::stats::Univariate;
+        let mut bands: BollingerBands<f64> = BollingerBands::new(20, 2.).unwrap();
+        let data: Vec<f64> = (0..40).map(|i| 100. + (i % 3) as f64).collect();
+        for x in data.iter() {
+            bands.update(*x);
+        }
+        assert!((bands.middle() - 101.).abs() < 0.1);
+        assert!(bands.upper() > 102. && bands.upper() < 103.);
+        bands.update(110.);
+        let window = &[&data[21..], &[110.]].concat();
+        let mean = window.iter().sum::<f64>() / 20.;
+        let std = (window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 20.).sqrt();
+        assert!((bands.get() - mean).abs() < 1e-9);
+        assert!((bands.upper() - (mean + 2. * std)).abs() < 1e-9);
+        assert!((bands.lower() - (mean - 2. * std)).abs() < 1e-9);
+        assert!(110. > bands.upper());
+        assert!(bands.percent_b(110.) > 1.);
+        assert!(BollingerBands::<f64>::new(20, -1.).is_err());
+        // With `ddof = 1` the bands use the sample standard deviation.
+        let mut sample_bands: BollingerBands<f64> = BollingerBands::with_ddof(20, 2., 1).unwrap();
+        for x in window.iter() {
+            sample_bands.update(*x);
+        }
+        let sample_std = std * (20_f64 / 19.).sqrt();
+        assert!((sample_bands.upper() - (mean + 2. * sample_std)).abs() < 1e-9);
+    }
+}
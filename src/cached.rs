@@ -0,0 +1,157 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Caches the value of a statistic for read-heavy workloads.
+/// `freeze` computes the value of the wrapped statistic once, `get` then returns the cached value
+/// until the next `update`, `revert` or `reset` invalidates it. Without a frozen value `get`
+/// delegates to the wrapped statistic.
+/// # Arguments
+/// * `inner` - The statistic whose value is cached.
+/// # Examples
+/// ```
+/// use watermill::cached::Cached;
+/// use watermill::quantile::RollingQuantile;
+/// use watermill::stats::Univariate;
+/// let mut median: Cached<f64, RollingQuantile<f64>> =
+///     Cached::new(RollingQuantile::new(0.5, 5).unwrap());
+/// for x in [3., 1., 2.].iter(){
+///     median.update(*x);
+/// }
+/// median.freeze();
+/// for _ in 0..1000{
+///     assert_eq!(median.get(), 2.);
+/// }
+/// median.update(10.);
+/// assert!(!median.is_frozen());
+/// assert_eq!(median.get(), 2.5);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Cached<F, U> {
+    inner: U,
+    cache: Option<F>,
+}
+
+impl<F, U> Cached<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F>,
+{
+    pub fn new(inner: U) -> Self {
+        Self { inner, cache: None }
+    }
+    /// Computes and caches the current value of the wrapped statistic.
+    pub fn freeze(&mut self) {
+        self.cache = Some(self.inner.get());
+    }
+    /// Drops the cached value, `get` delegates to the wrapped statistic again.
+    pub fn thaw(&mut self) {
+        self.cache = None;
+    }
+    /// Returns `true` while a cached value is returned by `get`.
+    pub fn is_frozen(&self) -> bool {
+        self.cache.is_some()
+    }
+    /// The wrapped statistic.
+    pub fn inner(&self) -> &U {
+        &self.inner
+    }
+    /// Mutable access to the wrapped statistic, drops the cached value since it may change.
+    pub fn inner_mut(&mut self) -> &mut U {
+        self.cache = None;
+        &mut self.inner
+    }
+    /// Unwraps the statistic, the cached value is dropped.
+    pub fn into_inner(self) -> U {
+        self.inner
+    }
+}
+
+impl<F, U> Univariate<F> for Cached<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F>,
+{
+    fn update(&mut self, x: F) {
+        self.inner.update(x);
+        self.cache = None;
+    }
+    fn update_opt(&mut self, x: Option<F>) {
+        self.inner.update_opt(x);
+        self.cache = None;
+    }
+    fn get(&self) -> F {
+        match self.cache {
+            Some(value) => value,
+            None => self.inner.get(),
+        }
+    }
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.cache = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.inner.n_samples()
+    }
+}
+
+impl<F, U> Revertable<F> for Cached<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Revertable<F>,
+{
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.cache = None;
+        self.inner.revert(x)
+    }
+}
+
+impl<F, U> RollableUnivariate<F> for Cached<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn frozen_reads_until_the_next_change() {
+        use crate::cached::Cached;
+        use crate::quantile::Quantile;
+        use crate::stats::{Revertable, Univariate};
+        use crate::sum::Sum;
+        let mut running_quantile: Cached<f64, Quantile<f64>> =
+            Cached::new(Quantile::new(0.5).unwrap());
+        for i in 0..100 {
+            running_quantile.update(((i * 7) % 13) as f64);
+        }
+        let value = running_quantile.inner().get();
+        running_quantile.freeze();
+        assert!(running_quantile.is_frozen());
+        for _ in 0..10 {
+            assert_eq!(running_quantile.get(), value);
+        }
+        running_quantile.update_opt(None);
+        assert!(!running_quantile.is_frozen());
+        running_quantile.freeze();
+        running_quantile.thaw();
+        assert!(!running_quantile.is_frozen());
+        let mut running_sum: Cached<f64, Sum<f64>> = Cached::new(Sum::new());
+        running_sum.update(2.);
+        running_sum.freeze();
+        running_sum.revert(2.).unwrap();
+        assert_eq!(running_sum.get(), 0.);
+        running_sum.update(5.);
+        running_sum.freeze();
+        running_sum.reset();
+        assert_eq!(running_sum.get(), 0.);
+        running_sum.update(1.);
+        running_sum.freeze();
+        running_sum.inner_mut().update(3.);
+        assert!(!running_sum.is_frozen());
+        assert_eq!(running_sum.get(), 4.);
+        assert_eq!(running_sum.into_inner().get(), 4.);
+    }
+}
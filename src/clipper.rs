@@ -0,0 +1,126 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::quantile::RollingQuantile;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Rolling percentile clipper, a robust preprocessing step which clamps values to the range
+/// between two rolling quantiles.
+/// `clip` clamps a value to the current bounds without touching the window, `update` adds a value
+/// to the window and `get` returns the most recent value clamped to the current bounds. Values go
+/// through untouched while the window is empty.
+/// # Arguments
+/// * `q_low` - Quantile of the lower bound.
+/// * `q_high` - Quantile of the upper bound, must be greater than `q_low`.
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::clipper::RollingClipper;
+/// use watermill::stats::Univariate;
+/// let mut clipper: RollingClipper<f64> = RollingClipper::new(0.1, 0.9, 11).unwrap();
+/// for i in 0..=10{
+///     clipper.update(i as f64);
+/// }
+/// assert_eq!(clipper.clip(100.), 9.);
+/// assert_eq!(clipper.clip(-100.), 1.);
+/// assert_eq!(clipper.clip(4.5), 4.5);
+/// ```
+#[derive(Serialize, Deserialize)]
+pub struct RollingClipper<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    low: RollingQuantile<F>,
+    high: RollingQuantile<F>,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingClipper<F> {
+    pub fn new(q_low: F, q_high: F, window_size: usize) -> Result<Self, &'static str> {
+        if q_low < F::from_f64(0.).unwrap() || q_high > F::from_f64(1.).unwrap() || q_low >= q_high
+        {
+            return Err("q_low and q_high should satisfy 0 <= q_low < q_high <= 1");
+        }
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            low: RollingQuantile::new(q_low, window_size)?,
+            high: RollingQuantile::new(q_high, window_size)?,
+            last: None,
+        })
+    }
+    /// Current lower bound.
+    pub fn lower(&self) -> F {
+        self.low.get()
+    }
+    /// Current upper bound.
+    pub fn upper(&self) -> F {
+        self.high.get()
+    }
+    /// Clamps `x` to the current bounds, the window is left untouched.
+    pub fn clip(&self, x: F) -> F {
+        if self.low.is_empty() {
+            return x;
+        }
+        x.max(self.lower()).min(self.upper())
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.low.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.low.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.low.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingClipper<F> {
+    fn update(&mut self, x: F) {
+        self.low.update(x);
+        self.high.update(x);
+        self.last = Some(x);
+    }
+    fn get(&self) -> F {
+        match self.last {
+            Some(x) => self.clip(x),
+            None => F::nan(),
+        }
+    }
+    fn reset(&mut self) {
+        self.low.reset();
+        self.high.reset();
+        self.last = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.low.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn extremes_are_clamped_to_the_rolling_bounds() {
+        use crate::clipper::RollingClipper;
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut clipper: RollingClipper<f64> = RollingClipper::new(0.05, 0.95, 50).unwrap();
+        let mut low: RollingQuantile<f64> = RollingQuantile::new(0.05, 50).unwrap();
+        let mut high: RollingQuantile<f64> = RollingQuantile::new(0.95, 50).unwrap();
+        assert_eq!(clipper.clip(1e9), 1e9);
+        for i in 0..500 {
+            let x = ((i * 13) % 50) as f64;
+            clipper.update(x);
+            low.update(x);
+            high.update(x);
+        }
+        assert_eq!(clipper.clip(1e9), high.get());
+        assert_eq!(clipper.clip(-1e9), low.get());
+        assert_eq!(clipper.clip(25.), 25.);
+        clipper.update(1e9);
+        assert_eq!(clipper.get(), clipper.upper());
+        assert!(clipper.upper() < 1e9);
+        assert!(RollingClipper::<f64>::new(0.9, 0.1, 50).is_err());
+    }
+}
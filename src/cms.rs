@@ -0,0 +1,178 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// SplitMix64 mixing function, portable and cheap: it hashes the values of `CountMinSketch` and
+/// draws the random numbers of `Reservoir`.
+pub(crate) fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Bits of a float widened to `f64`, `0.` and `-0.` share the same key. Distinct values have
+/// distinct keys.
+fn key<F: Float>(x: F) -> u64 {
+    if x == F::zero() {
+        return 0;
+    }
+    x.to_f64().unwrap().to_bits()
+}
+
+/// Count-min sketch, the approximate number of occurrences of each value of a stream of
+/// categories in a fixed amount of memory.
+/// The sketch holds `depth` rows of `width` counters, each row has its own hash function and a
+/// value increments one counter per row. The estimate of a value is the smallest of its counters,
+/// it never underestimates the true count, and overestimates it by at most `2 * n / width` with
+/// probability `1 - (1/2)^depth` where `n` is the number of values seen.
+/// `get` returns the estimate of the most recent value.
+///
+/// The hash functions only depend on `seed`: two sketches built with the same seed and fed the same
+/// values have the same state, down to their serialized form. Sketches are `Send` and `Sync`.
+/// # Arguments
+/// * `width` - Number of counters per row, the larger the more accurate.
+/// * `depth` - Number of rows, the larger the more reliable.
+/// * `seed` - Seed of the hash functions.
+/// # Examples
+/// ```
+/// use watermill::cms::CountMinSketch;
+/// use watermill::stats::Univariate;
+/// let mut cms: CountMinSketch<f64> = CountMinSketch::new(64, 4, 42).unwrap();
+/// for x in [1., 2., 1., 3., 1.].iter(){
+///     cms.update(*x);
+/// }
+/// assert_eq!(cms.estimate(1.), 3);
+/// assert_eq!(cms.get(), 3.);
+/// ```
+/// # References
+/// [^1]: [Cormode, G. and Muthukrishnan, S., 2005. An improved data stream summary: the count-min sketch and its applications. Journal of Algorithms, 55(1), pp.58-75.](https://doi.org/10.1016/j.jalgor.2003.12.001)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CountMinSketch<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    width: usize,
+    depth: usize,
+    seed: u64,
+    /// Counters, row after row.
+    counts: Vec<usize>,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> CountMinSketch<F> {
+    pub fn new(width: usize, depth: usize, seed: u64) -> Result<Self, &'static str> {
+        if width == 0 || depth == 0 {
+            return Err("width and depth should be greater than 0");
+        }
+        Ok(Self {
+            width,
+            depth,
+            seed,
+            counts: vec![0; width * depth],
+            last: None,
+        })
+    }
+    fn index(&self, row: usize, x: F) -> usize {
+        let row_seed = splitmix64(self.seed.wrapping_add(row as u64));
+        let column = (splitmix64(key(x) ^ row_seed) % self.width as u64) as usize;
+        row * self.width + column
+    }
+    /// Estimated number of occurrences of `x`, never lower than the true count.
+    pub fn estimate(&self, x: F) -> usize {
+        (0..self.depth)
+            .map(|row| self.counts[self.index(row, x)])
+            .min()
+            .unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for CountMinSketch<F> {
+    fn update(&mut self, x: F) {
+        for row in 0..self.depth {
+            let i = self.index(row, x);
+            self.counts[i] += 1;
+        }
+        self.last = Some(x);
+    }
+    fn get(&self) -> F {
+        match self.last {
+            Some(x) => F::from_usize(self.estimate(x)).unwrap(),
+            None => F::from_f64(0.).unwrap(),
+        }
+    }
+    fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.last = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.counts[..self.width].iter().sum())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn heavy_hitter_in_high_cardinality_stream() {
+        use crate::cms::CountMinSketch;
+        use crate::stats::Univariate;
+        let mut cms: CountMinSketch<f64> = CountMinSketch::new(1000, 5, 0).unwrap();
+        let capacity = cms.counts.capacity();
+        let n = 100_000;
+        let mut heavy_count = 0;
+        for i in 0..n {
+            let x = if i % 10 == 0 {
+                heavy_count += 1;
+                -1.
+            } else {
+                // 90 000 values among 30 000 distinct ones.
+                (i % 30_000) as f64 + 0.5
+            };
+            cms.update(x);
+        }
+        assert_eq!(cms.counts.capacity(), capacity);
+        let estimate = cms.estimate(-1.);
+        assert!(estimate >= heavy_count);
+        assert!(estimate - heavy_count <= 2 * n / 1000);
+        assert_eq!(cms.get(), cms.estimate(9_999.5) as f64);
+        assert_eq!(cms.estimate(0.), cms.estimate(-0.));
+    }
+    #[test]
+    fn same_seed_same_state() {
+        use crate::cms::CountMinSketch;
+        use crate::stats::Univariate;
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CountMinSketch<f64>>();
+        let mut sketches: Vec<CountMinSketch<f64>> = (0..2)
+            .map(|_| CountMinSketch::new(50, 3, 1234).unwrap())
+            .collect();
+        for sketch in sketches.iter_mut() {
+            for i in 0..1000 {
+                sketch.update(((i * 7) % 97) as f64);
+            }
+        }
+        let serialized: Vec<String> = sketches
+            .iter()
+            .map(|sketch| serde_json::to_string(sketch).unwrap())
+            .collect();
+        assert_eq!(serialized[0].as_bytes(), serialized[1].as_bytes());
+        let mut other_seed: CountMinSketch<f64> = CountMinSketch::new(50, 3, 4321).unwrap();
+        for i in 0..1000 {
+            other_seed.update(((i * 7) % 97) as f64);
+        }
+        assert_ne!(serde_json::to_string(&other_seed).unwrap(), serialized[0]);
+    }
+    #[test]
+    fn opposite_and_close_values_do_not_collide() {
+        use crate::cms::CountMinSketch;
+        use crate::stats::Univariate;
+        let mut cms: CountMinSketch<f64> = CountMinSketch::new(64, 4, 7).unwrap();
+        for _ in 0..10 {
+            cms.update(1.);
+        }
+        assert_eq!(cms.estimate(1.), 10);
+        assert_eq!(cms.estimate(-1.), 0);
+        assert_eq!(cms.estimate(2.125), 0);
+        cms.update(0.);
+        assert_eq!(cms.estimate(-0.), 1);
+    }
+}
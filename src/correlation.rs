@@ -0,0 +1,283 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::covariance::Covariance;
+use crate::rolling::RollingBivariate;
+use crate::sorted_window::SortedWindow;
+use crate::stats::{Bivariate, Revertable, RevertableBivariate, RollableBivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running Pearson correlation.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom used for the covariance and the variances.
+/// # Examples
+/// ```
+/// use watermill::correlation::PearsonCorrelation;
+/// use watermill::stats::Bivariate;
+/// let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+/// let x: Vec<f64> = vec![1., 2., 3., 4.];
+/// let y: Vec<f64> = vec![2., 4., 6., 8.];
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     running_corr.update(*xi, *yi);
+/// }
+/// assert!((running_corr.get() - 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Pearson correlation coefficient](https://www.wikiwand.com/en/Pearson_correlation_coefficient)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PearsonCorrelation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub cov: Covariance<F>,
+    pub var_x: Variance<F>,
+    pub var_y: Variance<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PearsonCorrelation<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            cov: Covariance::new(ddof),
+            var_x: Variance::new(ddof),
+            var_y: Variance::new(ddof),
+        }
+    }
+}
+
+impl<F> Default for PearsonCorrelation<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for PearsonCorrelation<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.cov.update(x, y);
+        self.var_x.update(x);
+        self.var_y.update(y);
+    }
+    fn get(&self) -> F {
+        let var_x = self.var_x.get();
+        let var_y = self.var_y.get();
+        if var_x > F::from_f64(0.).unwrap() && var_y > F::from_f64(0.).unwrap() {
+            return self.cov.get() / (var_x * var_y).sqrt();
+        }
+        F::from_f64(0.).unwrap()
+    }
+    fn reset(&mut self) {
+        self.cov.reset();
+        self.var_x.reset();
+        self.var_y.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.cov.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F>
+    for PearsonCorrelation<F>
+{
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        self.cov.revert(x, y)?;
+        self.var_x.revert(x)?;
+        self.var_y.revert(y)?;
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableBivariate<F>
+    for PearsonCorrelation<F>
+{
+}
+
+/// Rolling Pearson correlation over the last `window_size` pairs, each evicted pair is reverted
+/// from the covariance and the variances.
+/// # Examples
+/// ```
+/// use watermill::correlation::{PearsonCorrelation, RollingCorrelation};
+/// use watermill::stats::Bivariate;
+/// let mut rolling_corr: RollingCorrelation<f64> =
+///     RollingCorrelation::new(PearsonCorrelation::default(), 3).unwrap();
+/// // The first pair is anti-correlated but leaves the window.
+/// for (x, y) in [(0., 10.), (1., 2.), (2., 4.), (3., 6.)].iter(){
+///     rolling_corr.update(*x, *y);
+/// }
+/// assert!((rolling_corr.get() - 1.).abs() < 1e-12);
+/// ```
+pub type RollingCorrelation<F> = RollingBivariate<F, PearsonCorrelation<F>>;
+
+/// Rolling Spearman rank correlation.
+/// Values of `x` and `y` are kept in sorted windows, `get` ranks every pair of the window and
+/// returns the Pearson correlation of the ranks. Tied values get the average of their ranks, and
+/// the correlation is 0 while all the values of `x` or of `y` are equal.
+/// Each call to `get` costs `O(w log w)`, `w` being the size of the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::correlation::RollingSpearman;
+/// use watermill::stats::Bivariate;
+/// let mut rolling_spearman: RollingSpearman<f64> = RollingSpearman::new(4).unwrap();
+/// // The first pair breaks the monotone relationship but leaves the window.
+/// for (x, y) in [(10., 1.), (1., 1.), (2., 8.), (3., 27.), (4., 64.)]{
+///     rolling_spearman.update(x, y);
+/// }
+/// assert!((rolling_spearman.get() - 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on Spearman's rank correlation coefficient](https://en.wikipedia.org/wiki/Spearman%27s_rank_correlation_coefficient)
+#[derive(Serialize, Deserialize)]
+pub struct RollingSpearman<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_x: SortedWindow<F>,
+    sorted_y: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingSpearman<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_x: SortedWindow::new(window_size),
+            sorted_y: SortedWindow::new(window_size),
+        })
+    }
+    /// Number of pairs currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_x.len()
+    }
+    /// Returns `true` if no pair has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_x.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` pairs.
+    pub fn is_full(&self) -> bool {
+        self.sorted_x.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RollingSpearman<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.sorted_x.push_back(x);
+        self.sorted_y.push_back(y);
+    }
+    fn get(&self) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        // Average ranks always sum to n (n + 1) / 2.
+        let mean_rank = F::from_usize(self.len() + 1).unwrap() / F::from_f64(2.).unwrap();
+        let (mut cov, mut var_x, mut var_y) = (zero, zero, zero);
+        for (x, y) in self
+            .sorted_x
+            .unsorted_window
+            .iter()
+            .zip(self.sorted_y.unsorted_window.iter())
+        {
+            let dx = self.sorted_x.rank(*x) - mean_rank;
+            let dy = self.sorted_y.rank(*y) - mean_rank;
+            cov += dx * dy;
+            var_x += dx * dx;
+            var_y += dy * dy;
+        }
+        if var_x > zero && var_y > zero {
+            return cov / (var_x * var_y).sqrt();
+        }
+        zero
+    }
+    fn reset(&mut self) {
+        self.sorted_x.clear();
+        self.sorted_y.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn spearman(x: &[f64], y: &[f64]) -> f64 {
+        let rank = |values: &[f64], v: f64| {
+            let below = values.iter().filter(|w| **w < v).count() as f64;
+            let equal = values.iter().filter(|w| **w == v).count() as f64;
+            below + (equal + 1.) / 2.
+        };
+        let rx: Vec<f64> = x.iter().map(|v| rank(x, *v)).collect();
+        let ry: Vec<f64> = y.iter().map(|v| rank(y, *v)).collect();
+        let n = x.len() as f64;
+        let (mx, my) = (rx.iter().sum::<f64>() / n, ry.iter().sum::<f64>() / n);
+        let cov: f64 = rx
+            .iter()
+            .zip(ry.iter())
+            .map(|(a, b)| (a - mx) * (b - my))
+            .sum();
+        let vx: f64 = rx.iter().map(|a| (a - mx) * (a - mx)).sum();
+        let vy: f64 = ry.iter().map(|b| (b - my) * (b - my)).sum();
+        if vx > 0. && vy > 0. {
+            cov / (vx * vy).sqrt()
+        } else {
+            0.
+        }
+    }
+
+    #[test]
+    fn rolling_spearman_matches_brute_force() {
+        use crate::correlation::RollingSpearman;
+        use crate::stats::Bivariate;
+        let x: Vec<f64> = (0..40).map(|i| ((i * 7) % 10) as f64).collect();
+        let monotone: Vec<f64> = x.iter().map(|v| v.powi(3) - 2.).collect();
+        let non_monotone: Vec<f64> = x.iter().map(|v| ((v - 4.5) * (v - 4.5)).round()).collect();
+        for y in [monotone, non_monotone] {
+            let window_size = 6;
+            let mut rolling_spearman: RollingSpearman<f64> =
+                RollingSpearman::new(window_size).unwrap();
+            for i in 0..x.len() {
+                rolling_spearman.update(x[i], y[i]);
+                let start = (i + 1).saturating_sub(window_size);
+                let expected = spearman(&x[start..=i], &y[start..=i]);
+                assert!((rolling_spearman.get() - expected).abs() < 1e-12);
+            }
+        }
+        assert!(RollingSpearman::<f64>::new(0).is_err());
+        let mut rolling_spearman: RollingSpearman<f64> = RollingSpearman::new(3).unwrap();
+        for (xi, yi) in [(1., 3.), (2., 2.), (3., 1.)] {
+            rolling_spearman.update(xi, yi);
+        }
+        assert!((rolling_spearman.get() + 1.).abs() < 1e-12);
+    }
+    #[test]
+    fn pearson_revert_and_rolling() {
+        use crate::correlation::{PearsonCorrelation, RollingCorrelation};
+        use crate::stats::{Bivariate, RevertableBivariate};
+        let pearson = |x: &[f64], y: &[f64]| {
+            let n = x.len() as f64;
+            let (mx, my) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+            let cov: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+            let vx: f64 = x.iter().map(|a| (a - mx).powi(2)).sum();
+            let vy: f64 = y.iter().map(|b| (b - my).powi(2)).sum();
+            cov / (vx * vy).sqrt()
+        };
+        let x: Vec<f64> = (0..60).map(|i| ((i * 37) % 23) as f64).collect();
+        let y: Vec<f64> = (0..60)
+            .map(|i| ((i * 11) % 17) as f64 + 0.5 * x[i])
+            .collect();
+        let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+        for (xi, yi) in x.iter().zip(y.iter()).take(5) {
+            running_corr.update(*xi, *yi);
+        }
+        for (xi, yi) in x.iter().zip(y.iter()).take(5) {
+            running_corr.revert(*xi, *yi).unwrap();
+        }
+        // Without any pair the correlation is undefined and reported as 0.
+        assert_eq!(running_corr.n_samples(), Some(0));
+        assert_eq!(running_corr.get(), 0.);
+        let window_size = 10;
+        let mut rolling_corr: RollingCorrelation<f64> =
+            RollingCorrelation::new(PearsonCorrelation::default(), window_size).unwrap();
+        for i in 0..x.len() {
+            rolling_corr.update(x[i], y[i]);
+            if i >= window_size {
+                let start = i + 1 - window_size;
+                let expected = pearson(&x[start..=i], &y[start..=i]);
+                assert!((rolling_corr.get() - expected).abs() < 1e-9);
+            }
+        }
+    }
+}
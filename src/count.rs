@@ -1,9 +1,12 @@
-use num::{Float, FromPrimitive};
+use num::{Float, FromPrimitive, Num, NumCast, PrimInt, ToPrimitive, Unsigned};
 use std::ops::{AddAssign, SubAssign};
 
 use crate::stats::{Revertable, RollableUnivariate, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running count.
+/// The count is stored in `F`: with `f64` it stops increasing at `2^53` (`2^24` with `f32`), the
+/// largest integer after which consecutive integers are no longer representable. Use `ExactCount`
+/// for longer streams.
 /// # Examples
 /// ```
 /// use watermill::stats::{Univariate, Revertable};
@@ -21,37 +24,400 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_count.get(), 0.);
 ///```
+/// `Count` does not require a floating point type, integer streams can be counted directly:
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::count::Count;
+/// let mut running_count: Count<u64> = Count::new();
+/// for i in 1..10{
+///     running_count.update(i);
+/// }
+/// assert_eq!(running_count.get(), 9);
+///```
+/// Reverting an empty count is an error, see `SaturatingCount` for a count which stays at 0.
 #[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
-pub struct Count<F: Float + FromPrimitive + AddAssign + SubAssign> {
+pub struct Count<F: Num + Copy + AddAssign + SubAssign> {
     pub count: F,
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Count<F> {
+impl<F: Num + Copy + AddAssign + SubAssign> Count<F> {
     pub fn new() -> Self {
-        Self {
-            count: F::from_f64(0.0).unwrap(),
-        }
+        Self { count: F::zero() }
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Count<F> {
+macro_rules! impl_const_new {
+    ($($t:ty),*) => {
+        $(
+            impl Count<$t> {
+                /// Same as `Count::new` but usable in `const` and `static` contexts.
+                pub const fn new_const() -> Self {
+                    Self { count: 0.0 }
+                }
+            }
+        )*
+    };
+}
+impl_const_new!(f32, f64);
+
+impl<F: Num + Copy + AddAssign + SubAssign + ToPrimitive> Univariate<F> for Count<F> {
     #[warn(unused_variables)]
     fn update(&mut self, _x: F) {
-        self.count += F::from_f64(1.).unwrap();
+        self.count += F::one();
     }
     fn get(&self) -> F {
         self.count
     }
+    fn reset(&mut self) {
+        self.count = F::zero();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.count.to_usize().unwrap_or(usize::MAX))
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Revertable<F> for Count<F> {
+    fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
+        if self.count == F::zero() {
+            return Err("Count cannot go below 0");
+        }
+        self.count -= F::one();
+        Ok(())
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign + ToPrimitive> RollableUnivariate<F> for Count<F> {}
+
+/// Running count whose `revert` stays at 0 instead of returning an error, so rolling it never
+/// panics even if a value is evicted more times than it was counted. Otherwise the same as `Count`.
+/// # Examples
+/// ```
+/// use watermill::stats::{Univariate, Revertable};
+/// use watermill::count::SaturatingCount;
+/// let mut running_count: SaturatingCount<f64> = SaturatingCount::new();
+/// running_count.update(1.);
+/// assert!(running_count.revert(1.).is_ok());
+/// assert!(running_count.revert(1.).is_ok());
+/// assert_eq!(running_count.get(), 0.);
+///```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SaturatingCount<F: Num + Copy + AddAssign + SubAssign> {
+    pub count: F,
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> SaturatingCount<F> {
+    pub fn new() -> Self {
+        Self { count: F::zero() }
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign + ToPrimitive> Univariate<F> for SaturatingCount<F> {
+    fn update(&mut self, _x: F) {
+        self.count += F::one();
+    }
+    fn get(&self) -> F {
+        self.count
+    }
+    fn reset(&mut self) {
+        self.count = F::zero();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.count.to_usize().unwrap_or(usize::MAX))
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Revertable<F> for SaturatingCount<F> {
+    fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
+        if self.count != F::zero() {
+            self.count -= F::one();
+        }
+        Ok(())
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign + ToPrimitive> RollableUnivariate<F>
+    for SaturatingCount<F>
+{
+}
+
+/// Running count backed by an unsigned integer, `u64` by default, for streams too long for
+/// `Count<f64>`. The count is incremented exactly and only converted to `F` by `get`, where it
+/// is rounded to the nearest representable value.
+/// # Examples
+/// ```
+/// use watermill::stats::{Univariate, Revertable};
+/// use watermill::count::ExactCount;
+/// let mut running_count: ExactCount<f64> = ExactCount::new();
+/// for i in 1..10{
+///     running_count.update(i as f64);
+/// }
+/// running_count.revert(1.).unwrap();
+/// assert_eq!(running_count.get(), 8.0);
+/// assert_eq!(running_count.count, 8);
+///```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ExactCount<F: Float, C: PrimInt + Unsigned = u64> {
+    pub count: C,
+    #[serde(skip)]
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Float, C: PrimInt + Unsigned> ExactCount<F, C> {
+    pub fn new() -> Self {
+        Self {
+            count: C::zero(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: Float + AddAssign + SubAssign, C: PrimInt + Unsigned> Univariate<F> for ExactCount<F, C> {
+    fn update(&mut self, _x: F) {
+        self.count = self.count + C::one();
+    }
+    fn get(&self) -> F {
+        <F as NumCast>::from(self.count).unwrap()
+    }
+    fn reset(&mut self) {
+        self.count = C::zero();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.count.to_usize().unwrap_or(usize::MAX))
+    }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Count<F> {
+impl<F: Float + AddAssign + SubAssign, C: PrimInt + Unsigned> Revertable<F> for ExactCount<F, C> {
     fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
-        if self.count == F::from_f64(0.).unwrap() {
+        if self.count == C::zero() {
             return Err("Count cannot go below 0");
         }
-        self.count -= F::from_f64(1.).unwrap();
+        self.count = self.count - C::one();
         Ok(())
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Count<F> {}
+impl<F: Float + AddAssign + SubAssign, C: PrimInt + Unsigned> RollableUnivariate<F>
+    for ExactCount<F, C>
+{
+}
+
+/// Natural logarithm of the running count, `ln(n)`, e.g. to normalize an accumulator that grows
+/// logarithmically on heavy-tailed streams. The count is kept exactly, `get` returns `-inf` before
+/// the first update.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::count::LogCount;
+/// let mut log_count: LogCount<f64> = LogCount::new();
+/// assert_eq!(log_count.get(), f64::NEG_INFINITY);
+/// for i in 1..5{
+///     log_count.update(i as f64);
+/// }
+/// assert_eq!(log_count.get(), 4_f64.ln());
+/// assert_eq!(log_count.n, 4);
+///```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct LogCount<F: Float> {
+    pub n: u64,
+    #[serde(skip)]
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Float> LogCount<F> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for LogCount<F> {
+    fn update(&mut self, _x: F) {
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        F::from_u64(self.n).unwrap().ln()
+    }
+    fn reset(&mut self) {
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n as usize)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for LogCount<F> {
+    fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
+        if self.n == 0 {
+            return Err("LogCount cannot revert more values than it has seen");
+        }
+        self.n -= 1;
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for LogCount<F> {}
+
+/// Running harmonic number, the partial sum `H_n = Σ_{i=1}^{n} 1 / i` over the `n` values seen so
+/// far. It grows like `ln(n) + 0.5772…`, the Euler–Mascheroni constant, and is 0 before the first
+/// update.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::count::HarmonicNumber;
+/// let mut harmonic_number: HarmonicNumber<f64> = HarmonicNumber::new();
+/// for (i, expected) in [1., 1.5, 11. / 6., 25. / 12.].iter().enumerate(){
+///     harmonic_number.update(i as f64);
+///     assert!((harmonic_number.get() - expected).abs() < 1e-12);
+/// }
+///```
+/// # References
+/// [^1]: [Wikipedia article on harmonic numbers](https://en.wikipedia.org/wiki/Harmonic_number)
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct HarmonicNumber<F: Float> {
+    pub n: u64,
+    pub sum: F,
+}
+
+impl<F: Float> HarmonicNumber<F> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            sum: F::zero(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for HarmonicNumber<F> {
+    fn update(&mut self, _x: F) {
+        self.n += 1;
+        self.sum += F::from_u64(self.n).unwrap().recip();
+    }
+    fn get(&self) -> F {
+        self.sum
+    }
+    fn reset(&mut self) {
+        self.n = 0;
+        self.sum = F::zero();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n as usize)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for HarmonicNumber<F> {
+    fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
+        if self.n == 0 {
+            return Err("HarmonicNumber cannot revert more values than it has seen");
+        }
+        self.sum -= F::from_u64(self.n).unwrap().recip();
+        self.n -= 1;
+        if self.n == 0 {
+            self.sum = F::zero();
+        }
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for HarmonicNumber<F> {}
+
+#[cfg(test)]
+impl<F: num::Float + AddAssign + SubAssign> crate::stats::ApproxEq<F> for Count<F> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool {
+        crate::stats::close(self.count, other.count, eps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn saturating_revert_stays_at_zero() {
+        use crate::count::{Count, SaturatingCount};
+        use crate::rolling::Rolling;
+        use crate::stats::{Revertable, Univariate};
+        let mut strict: Count<u32> = Count::new();
+        let mut saturating: SaturatingCount<u32> = SaturatingCount::new();
+        for x in [1, 2] {
+            strict.update(x);
+            saturating.update(x);
+        }
+        for _ in 0..2 {
+            strict.revert(0).unwrap();
+            saturating.revert(0).unwrap();
+        }
+        assert!(strict.revert(0).is_err());
+        assert!(saturating.revert(0).is_ok());
+        assert_eq!(saturating.get(), 0);
+        saturating.update(7);
+        assert_eq!(saturating.get(), 1);
+        // Evicting more values than were counted does not panic.
+        let mut running_count: SaturatingCount<f64> = SaturatingCount::new();
+        let mut rolling_count: Rolling<f64> = Rolling::new(&mut running_count, 2).unwrap();
+        rolling_count.update(1.);
+        assert_eq!(rolling_count.pop_oldest(), Some(1.));
+        running_count.revert(1.).unwrap();
+        assert_eq!(running_count.get(), 0.);
+        // Both counts keep the format of `Count`.
+        let serialized = serde_json::to_string(&running_count).unwrap();
+        assert_eq!(serialized, r#"{"count":0.0}"#);
+        let strict: Count<f64> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(strict.count, 0.);
+    }
+    #[test]
+    fn exact_count_beyond_float_precision() {
+        use crate::count::{Count, ExactCount};
+        use crate::stats::Univariate;
+        let limit = 2_u64.pow(53);
+        let mut float_count: Count<f64> = Count::new();
+        float_count.count = limit as f64;
+        let mut exact_count: ExactCount<f64> = ExactCount::new();
+        exact_count.count = limit;
+        let mut wide_count: ExactCount<f64, u128> = ExactCount::new();
+        wide_count.count = u64::MAX as u128;
+        for _ in 0..2 {
+            float_count.update(1.);
+            exact_count.update(1.);
+            wide_count.update(1.);
+        }
+        // `2^53 + 1` rounds back to `2^53`, the float count is stuck.
+        assert_eq!(float_count.get(), limit as f64);
+        assert_eq!(exact_count.count, limit + 2);
+        assert_eq!(exact_count.get(), (limit + 2) as f64);
+        assert_eq!(wide_count.count, u64::MAX as u128 + 2);
+        let serialized = serde_json::to_string(&exact_count).unwrap();
+        let deserialized: ExactCount<f64> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.count, limit + 2);
+    }
+    #[test]
+    fn harmonic_number_tracks_the_log_count() {
+        use crate::count::{HarmonicNumber, LogCount};
+        use crate::rolling::RollingOwned;
+        use crate::stats::{Revertable, Univariate};
+        let mut log_count: LogCount<f64> = LogCount::new();
+        let mut harmonic_number: HarmonicNumber<f64> = HarmonicNumber::new();
+        assert_eq!(harmonic_number.get(), 0.);
+        for i in 0..10_000 {
+            log_count.update(i as f64);
+            harmonic_number.update(i as f64);
+        }
+        let euler_mascheroni = 0.577_215_664_901_532_9;
+        assert!((harmonic_number.get() - log_count.get() - euler_mascheroni).abs() < 1e-4);
+        for _ in 0..9_999 {
+            harmonic_number.revert(0.).unwrap();
+            log_count.revert(0.).unwrap();
+        }
+        assert!((harmonic_number.get() - 1.).abs() < 1e-9);
+        assert_eq!(log_count.get(), 0.);
+        harmonic_number.revert(0.).unwrap();
+        assert_eq!(harmonic_number.get(), 0.);
+        assert!(harmonic_number.revert(0.).is_err());
+        // Over a window the harmonic number stays at `H_w`.
+        let mut rolling_harmonic: RollingOwned<f64, HarmonicNumber<f64>> =
+            RollingOwned::new(HarmonicNumber::new(), 3).unwrap();
+        for i in 0..10 {
+            rolling_harmonic.update(i as f64);
+        }
+        assert!((rolling_harmonic.get() - 11. / 6.).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,119 @@
+use num::{Float, FromPrimitive};
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::{AddAssign, SubAssign},
+};
+
+#[doc(hidden)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Counter<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub(crate) counts: HashMap<NotNan<F>, usize>,
+    total: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Counter<F> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+            total: 0,
+        }
+    }
+    /// Number of distinct values.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.total = 0;
+    }
+    /// Number of values, duplicates included.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+    pub fn count(&self, x: F) -> usize {
+        *self
+            .counts
+            .get(&NotNan::new(x).expect("Value is NaN"))
+            .unwrap_or(&0)
+    }
+    pub fn add(&mut self, x: F) {
+        *self
+            .counts
+            .entry(NotNan::new(x).expect("Value is NaN"))
+            .or_insert(0) += 1;
+        self.total += 1;
+    }
+    pub fn remove(&mut self, x: F) -> Result<(), &'static str> {
+        let key = NotNan::new(x).expect("Value is NaN");
+        match self.counts.get_mut(&key) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.counts.remove(&key);
+            }
+            None => return Err("The value is not in the counter"),
+        }
+        self.total -= 1;
+        Ok(())
+    }
+    /// Most frequent value and its count, ties are broken by keeping the smallest value.
+    pub fn most_common(&self) -> Option<(F, usize)> {
+        self.counts
+            .iter()
+            .max_by(|(x_a, n_a), (x_b, n_b)| n_a.cmp(n_b).then(x_b.cmp(x_a)))
+            .map(|(x, n)| (x.into_inner(), *n))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (F, usize)> + '_ {
+        self.counts.iter().map(|(x, n)| (x.into_inner(), *n))
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingCounter<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub(crate) counter: Counter<F>,
+    pub(crate) window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingCounter<F> {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            counter: Counter::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+    pub fn clear(&mut self) {
+        self.counter.clear();
+        self.window.clear();
+    }
+    /// Adds `value` and returns the oldest value if it was evicted.
+    pub fn push_back(&mut self, value: F) -> Option<F> {
+        let mut evicted = None;
+        if self.window.len() == self.window_size {
+            if let Some(oldest) = self.window.pop_front() {
+                self.counter
+                    .remove(oldest)
+                    .expect("The value is not in the counter");
+                evicted = Some(oldest);
+            }
+        }
+        self.window.push_back(value);
+        self.counter.add(value);
+        evicted
+    }
+}
@@ -0,0 +1,242 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::mean::Mean;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Running covariance matrix of `dim` variables.
+/// Each entry is updated exactly like `Covariance`, so entry `(i, j)` equals the covariance of variables `i` and `j`.
+/// # Arguments
+/// * `dim` - Number of variables.
+/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen observations.
+/// # Examples
+/// ```
+/// use watermill::cov_matrix::CovMatrix;
+/// let mut running_cov: CovMatrix<f64> = CovMatrix::new(2, 1).unwrap();
+/// let data: Vec<[f64; 2]> = vec![[-2.1, 3.], [-1., 1.1], [4.3, 0.12]];
+/// for x in data.iter(){
+///     running_cov.update(x).unwrap();
+/// }
+/// let matrix = running_cov.get_matrix();
+/// assert_eq!(matrix[0][1], -4.286);
+/// assert_eq!(matrix[0][1], matrix[1][0]);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on algorithms for calculating variance](https://www.wikiwand.com/en/Algorithms_for_calculating_variance#/Covariance)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CovMatrix<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub dim: usize,
+    pub ddof: u32,
+    pub means: Vec<Mean<F>>,
+    /// Co-moments stored row by row.
+    c: Vec<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> CovMatrix<F> {
+    pub fn new(dim: usize, ddof: u32) -> Result<Self, &'static str> {
+        if dim == 0 {
+            return Err("dim should be greater than 0");
+        }
+        Ok(Self {
+            dim,
+            ddof,
+            means: vec![Mean::new(); dim],
+            c: vec![F::from_f64(0.).unwrap(); dim * dim],
+        })
+    }
+    pub fn update(&mut self, x: &[F]) -> Result<(), &'static str> {
+        if x.len() != self.dim {
+            return Err("x should have dim values");
+        }
+        let dx: Vec<F> = x
+            .iter()
+            .zip(self.means.iter())
+            .map(|(xi, mean)| *xi - mean.get())
+            .collect();
+        for (mean, xi) in self.means.iter_mut().zip(x.iter()) {
+            mean.update(*xi);
+        }
+        for (row, dxi) in self.c.chunks_mut(self.dim).zip(dx.iter()) {
+            for ((c, xj), mean) in row.iter_mut().zip(x.iter()).zip(self.means.iter()) {
+                *c += *dxi * (*xj - mean.get());
+            }
+        }
+        Ok(())
+    }
+    pub fn get_matrix(&self) -> Vec<Vec<F>> {
+        let n = match self.means.first() {
+            Some(mean) => mean.weight(),
+            None => return Vec::new(),
+        };
+        let divisor = F::from_f64(1.)
+            .unwrap()
+            .max(n - F::from_u32(self.ddof).unwrap());
+        self.c
+            .chunks(self.dim)
+            .map(|row| row.iter().map(|c| *c / divisor).collect())
+            .collect()
+    }
+}
+
+/// Exponentially weighted covariance matrix of `dim` variables, e.g. for the risk of a portfolio.
+/// The first observation initializes the means, then each update decays the means and the
+/// co-moments: with `dx = x - mean` measured before the update, `mean += alpha * dx` and
+/// `c_ij = (1 - alpha) * (c_ij + alpha * dx_i * dx_j)`. The diagonal is the `EWVariance` of each
+/// variable built with `Adjust::Recursive`.
+/// # Arguments
+/// * `dim` - Number of variables, at least 1.
+/// * `alpha` - In `(0, 1]`, the closer `alpha` is to 1 the more the matrix will adapt to recent observations.
+/// # Examples
+/// ```
+/// use watermill::cov_matrix::EWCovMatrix;
+/// let mut ew_cov: EWCovMatrix<f64> = EWCovMatrix::new(2, 0.5).unwrap();
+/// for x in [[1., 2.], [3., -2.]].iter(){
+///     ew_cov.update(x).unwrap();
+/// }
+/// // `dx = (2, -4)`, the covariance is `0.5 * 0.5 * 2 * -4`.
+/// let matrix = ew_cov.get_matrix();
+/// assert_eq!(matrix[0][1], -2.);
+/// assert_eq!(matrix[1][0], -2.);
+/// assert_eq!(matrix[0][0], 1.);
+/// assert_eq!(matrix[1][1], 4.);
+/// ```
+/// # References
+/// [^1]: [Finch, T., 2009. Incremental calculation of weighted mean and variance. University of Cambridge, 4(11-5), pp.41-42.](https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EWCovMatrix<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub dim: usize,
+    pub alpha: F,
+    pub means: Vec<F>,
+    /// Co-moments stored row by row.
+    c: Vec<F>,
+    n: u64,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWCovMatrix<F> {
+    pub fn new(dim: usize, alpha: F) -> Result<Self, &'static str> {
+        if dim == 0 {
+            return Err("dim should be greater than 0");
+        }
+        if !(alpha > F::from_f64(0.).unwrap() && alpha <= F::from_f64(1.).unwrap()) {
+            return Err("alpha should be in (0, 1]");
+        }
+        Ok(Self {
+            dim,
+            alpha,
+            means: vec![F::from_f64(0.).unwrap(); dim],
+            c: vec![F::from_f64(0.).unwrap(); dim * dim],
+            n: 0,
+        })
+    }
+    pub fn update(&mut self, x: &[F]) -> Result<(), &'static str> {
+        if x.len() != self.dim {
+            return Err("x should have dim values");
+        }
+        if self.n == 0 {
+            self.means.copy_from_slice(x);
+            self.n = 1;
+            return Ok(());
+        }
+        let decay = F::from_f64(1.).unwrap() - self.alpha;
+        let dx: Vec<F> = x
+            .iter()
+            .zip(self.means.iter())
+            .map(|(xi, mean)| *xi - *mean)
+            .collect();
+        for (mean, dxi) in self.means.iter_mut().zip(dx.iter()) {
+            *mean += self.alpha * *dxi;
+        }
+        for (row, dxi) in self.c.chunks_mut(self.dim).zip(dx.iter()) {
+            for (c, dxj) in row.iter_mut().zip(dx.iter()) {
+                // Multiplying the deviations first keeps the matrix exactly symmetric.
+                *c = decay * (*c + self.alpha * (*dxi * *dxj));
+            }
+        }
+        self.n += 1;
+        Ok(())
+    }
+    pub fn get_matrix(&self) -> Vec<Vec<F>> {
+        self.c.chunks(self.dim).map(|row| row.to_vec()).collect()
+    }
+    pub fn reset(&mut self) {
+        self.means.fill(F::from_f64(0.).unwrap());
+        self.c.fill(F::from_f64(0.).unwrap());
+        self.n = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn matches_pairwise_covariance() {
+        use crate::cov_matrix::CovMatrix;
+        use crate::covariance::Covariance;
+        use crate::stats::Bivariate;
+        let data: Vec<[f64; 3]> = (0..40)
+            .map(|i| {
+                let t = i as f64;
+                [t.sin(), (0.3 * t).cos() * 2., (t * 7.) % 5.]
+            })
+            .collect();
+        let mut running_cov: CovMatrix<f64> = CovMatrix::new(3, 1).unwrap();
+        let mut pairwise: Vec<Vec<Covariance<f64>>> = vec![vec![Covariance::default(); 3]; 3];
+        for x in data.iter() {
+            running_cov.update(x).unwrap();
+            for (xi, row) in x.iter().zip(pairwise.iter_mut()) {
+                for (xj, cov) in x.iter().zip(row.iter_mut()) {
+                    cov.update(*xi, *xj);
+                }
+            }
+        }
+        let matrix = running_cov.get_matrix();
+        for (matrix_row, pairwise_row) in matrix.iter().zip(pairwise.iter()) {
+            for (entry, cov) in matrix_row.iter().zip(pairwise_row.iter()) {
+                assert!((entry - cov.get()).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_length() {
+        use crate::cov_matrix::CovMatrix;
+        assert!(CovMatrix::<f64>::new(0, 1).is_err());
+        let mut running_cov: CovMatrix<f64> = CovMatrix::new(3, 1).unwrap();
+        assert!(running_cov.update(&[1., 2.]).is_err());
+        assert!(running_cov.update(&[1., 2., 3., 4.]).is_err());
+        assert!(running_cov.update(&[1., 2., 3.]).is_ok());
+    }
+
+    #[test]
+    fn ew_diagonal_matches_ew_variance() {
+        use crate::cov_matrix::EWCovMatrix;
+        use crate::ewmean::Adjust;
+        use crate::ewvariance::EWVariance;
+        use crate::stats::Univariate;
+        let alpha = 0.2;
+        assert!(EWCovMatrix::<f64>::new(0, alpha).is_err());
+        assert!(EWCovMatrix::<f64>::new(3, 0.).is_err());
+        assert!(EWCovMatrix::<f64>::new(3, 1.5).is_err());
+        assert!(EWCovMatrix::<f64>::new(3, f64::NAN).is_err());
+        let mut ew_cov: EWCovMatrix<f64> = EWCovMatrix::new(3, alpha).unwrap();
+        let mut variances: Vec<EWVariance<f64>> =
+            vec![EWVariance::with_adjust(alpha, Adjust::Recursive); 3];
+        assert!(ew_cov.update(&[1., 2.]).is_err());
+        for i in 0..100 {
+            let t = i as f64;
+            // The third variable is exactly minus the first one.
+            let x = [t.sin(), (0.3 * t).cos() * 2. + 10., -t.sin()];
+            ew_cov.update(&x).unwrap();
+            for (variance, xi) in variances.iter_mut().zip(x.iter()) {
+                variance.update(*xi);
+            }
+        }
+        let matrix = ew_cov.get_matrix();
+        for (i, variance) in variances.iter().enumerate() {
+            assert!((matrix[i][i] - variance.get()).abs() < 1e-9);
+        }
+        assert!((matrix[0][2] + matrix[0][0]).abs() < 1e-12);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        ew_cov.reset();
+        assert_eq!(ew_cov.get_matrix()[0][0], 0.);
+    }
+}
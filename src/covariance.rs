@@ -2,13 +2,14 @@ use num::{Float, FromPrimitive};
 use std::ops::{AddAssign, SubAssign};
 
 use crate::mean::Mean;
-use crate::stats::{Bivariate, Univariate};
+use crate::stats::{Bivariate, Revertable, RevertableBivariate, RollableBivariate, Univariate};
+use crate::variance::Variance;
 use serde::{Deserialize, Serialize};
 /// Running Covariance.
 /// # Examples
 /// ```
 /// use watermill::covariance::Covariance;
-/// use watermill::stats::Bivariate;
+/// use watermill::stats::{Bivariate, RevertableBivariate};
 /// let mut running_cov: Covariance<f64> = Covariance::default();
 /// let x: Vec<f64> = vec![-2.1,  -1.,  4.3];
 /// let y: Vec<f64> = vec![3., 1.1, 0.12];
@@ -16,6 +17,10 @@ use serde::{Deserialize, Serialize};
 ///     running_cov.update(*xi,*yi);
 /// }
 /// assert_eq!(running_cov.get(), -4.286);
+///
+/// // You can revert the covariance
+/// running_cov.revert(4.3, 0.12);
+/// assert!((running_cov.get() + 1.045).abs() < 1e-12);
 /// ```
 /// # References
 /// [^1]: [Wikipedia article on algorithms for calculating variance](https://www.wikiwand.com/en/Algorithms_for_calculating_variance#/Covariance)
@@ -56,18 +61,245 @@ where
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Covariance<F> {
+    fn update_cov(&mut self) {
+        self.cov = self.c
+            / (F::from_f64(1.)
+                .unwrap()
+                .max(self.mean_x.weight() - F::from_u32(self.ddof).unwrap()));
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for Covariance<F> {
     fn update(&mut self, x: F, y: F) {
         let dx = x - self.mean_x.get();
         self.mean_x.update(x);
         self.mean_y.update(y);
         self.c += dx * (y - self.mean_y.get());
-        self.cov = self.c
-            / (F::from_f64(1.)
-                .unwrap()
-                .max(self.mean_x.n.get() - F::from_u32(self.ddof).unwrap()));
+        self.update_cov();
     }
     fn get(&self) -> F {
         self.cov
     }
+    fn reset(&mut self) {
+        *self = Self::new(self.ddof);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.mean_x.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for Covariance<F> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        // Mirror of `update`: the means are reverted first so `dx` is computed with the same
+        // mean of x as during the update, and `y` is compared to the mean of y that included it.
+        let mean_y = self.mean_y.get();
+        self.mean_x.revert(x)?;
+        self.mean_y.revert(y)?;
+        if self.mean_x.weight() == F::from_f64(0.).unwrap() {
+            self.c = F::from_f64(0.).unwrap();
+        } else {
+            self.c -= (x - self.mean_x.get()) * (y - mean_y);
+        }
+        self.update_cov();
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableBivariate<F> for Covariance<F> {}
+
+/// Running covariance together with the variances of `x` and `y`, which gives the correlation and
+/// both regression slopes from a single statistic. `get` returns the covariance.
+/// * `beta_xy` is the slope of the regression of `y` on `x`, `cov / var_x`.
+/// * `beta_yx` is the slope of the regression of `x` on `y`, `cov / var_y`.
+///
+/// Like `PearsonCorrelation`, the correlation and the slopes are 0 while a variance is 0.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom used for the covariance and the variances.
+/// # Examples
+/// ```
+/// use watermill::covariance::JointStats;
+/// use watermill::stats::Bivariate;
+/// let mut joint: JointStats<f64> = JointStats::default();
+/// for (x, y) in [(1., 3.), (2., 5.), (3., 7.), (4., 9.)].iter(){
+///     joint.update(*x, *y);
+/// }
+/// assert!((joint.beta_xy() - 2.).abs() < 1e-12);
+/// assert!((joint.beta_yx() - 0.5).abs() < 1e-12);
+/// assert!((joint.correlation() - 1.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct JointStats<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub cov: Covariance<F>,
+    pub var_x: Variance<F>,
+    pub var_y: Variance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> JointStats<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            cov: Covariance::new(ddof),
+            var_x: Variance::new(ddof),
+            var_y: Variance::new(ddof),
+        }
+    }
+    /// Pearson correlation of `x` and `y`.
+    pub fn correlation(&self) -> F {
+        let var_x = self.var_x.get();
+        let var_y = self.var_y.get();
+        if var_x > F::from_f64(0.).unwrap() && var_y > F::from_f64(0.).unwrap() {
+            return self.cov.get() / (var_x * var_y).sqrt();
+        }
+        F::from_f64(0.).unwrap()
+    }
+    /// Slope of the regression of `y` on `x`.
+    pub fn beta_xy(&self) -> F {
+        let var_x = self.var_x.get();
+        if var_x > F::from_f64(0.).unwrap() {
+            return self.cov.get() / var_x;
+        }
+        F::from_f64(0.).unwrap()
+    }
+    /// Slope of the regression of `x` on `y`.
+    pub fn beta_yx(&self) -> F {
+        let var_y = self.var_y.get();
+        if var_y > F::from_f64(0.).unwrap() {
+            return self.cov.get() / var_y;
+        }
+        F::from_f64(0.).unwrap()
+    }
+}
+
+impl<F> Default for JointStats<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for JointStats<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.cov.update(x, y);
+        self.var_x.update(x);
+        self.var_y.update(y);
+    }
+    fn get(&self) -> F {
+        self.cov.get()
+    }
+    fn reset(&mut self) {
+        self.cov.reset();
+        self.var_x.reset();
+        self.var_y.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.cov.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for JointStats<F> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        self.cov.revert(x, y)?;
+        self.var_x.revert(x)?;
+        self.var_y.revert(y)?;
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableBivariate<F> for JointStats<F> {}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn revert_is_stable_over_long_windows() {
+        use crate::covariance::Covariance;
+        use crate::stats::{uniform, Bivariate, RevertableBivariate};
+        use std::collections::VecDeque;
+        let window_size = 100;
+        let mut uniform = {
+            let mut uniform = uniform(7);
+            move || uniform() - 0.5
+        };
+        let mut running_cov: Covariance<f64> = Covariance::default();
+        let mut window: VecDeque<(f64, f64)> = VecDeque::new();
+        for i in 1..=100_000 {
+            let x = 1000. + 50. * (i as f64 * 0.01).sin() + uniform();
+            let y = 0.5 * x + 3. * uniform();
+            if window.len() == window_size {
+                let (old_x, old_y) = window.pop_front().unwrap();
+                running_cov.revert(old_x, old_y).unwrap();
+            }
+            window.push_back((x, y));
+            running_cov.update(x, y);
+            if i % 10_000 == 0 {
+                let mut reference: Covariance<f64> = Covariance::default();
+                for (wx, wy) in window.iter() {
+                    reference.update(*wx, *wy);
+                }
+                let error = (running_cov.get() - reference.get()).abs();
+                assert!(error < 1e-9 * reference.get().abs().max(1.));
+            }
+        }
+        for (x, y) in window.iter() {
+            running_cov.revert(*x, *y).unwrap();
+        }
+        assert_eq!(running_cov.get(), 0.);
+        assert!(running_cov.revert(1., 1.).is_err());
+    }
+
+    #[test]
+    fn reset_mid_stream() {
+        use crate::covariance::Covariance;
+        use crate::stats::Bivariate;
+        let mut running_cov: Covariance<f64> = Covariance::new(0);
+        for i in 0..10 {
+            running_cov.update(i as f64, (i * i) as f64);
+        }
+        assert_eq!(running_cov.n_samples(), Some(10));
+        running_cov.reset();
+        assert_eq!(running_cov.n_samples(), Some(0));
+        assert_eq!(running_cov.ddof, 0);
+        let mut fresh_cov: Covariance<f64> = Covariance::new(0);
+        for (x, y) in [(1., 3.), (-2., 0.5), (4., 4.)] {
+            running_cov.update(x, y);
+            fresh_cov.update(x, y);
+        }
+        assert_eq!(running_cov.get(), fresh_cov.get());
+        assert_eq!(running_cov.n_samples(), Some(3));
+    }
+    #[test]
+    fn joint_stats_betas_multiply_to_the_squared_correlation() {
+        use crate::correlation::PearsonCorrelation;
+        use crate::covariance::JointStats;
+        use crate::linreg::SimpleLinReg;
+        use crate::stats::{Bivariate, RevertableBivariate};
+        let data: Vec<(f64, f64)> = (0..50)
+            .map(|i| {
+                let x = i as f64 * 0.3;
+                (x, 2. - 0.7 * x + ((i * 13) % 7) as f64)
+            })
+            .collect();
+        let mut joint: JointStats<f64> = JointStats::default();
+        let mut corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+        let mut linreg: SimpleLinReg<f64> = SimpleLinReg::default();
+        for (x, y) in data.iter() {
+            joint.update(*x, *y);
+            corr.update(*x, *y);
+            linreg.update(*x, *y);
+        }
+        assert!((joint.correlation() - corr.get()).abs() < 1e-12);
+        assert!((joint.beta_xy() - linreg.get()).abs() < 1e-12);
+        let correlation = joint.correlation();
+        assert!((joint.beta_xy() * joint.beta_yx() - correlation * correlation).abs() < 1e-12);
+        for (x, y) in data[..10].iter() {
+            joint.revert(*x, *y).unwrap();
+        }
+        let mut reference: JointStats<f64> = JointStats::default();
+        for (x, y) in data[10..].iter() {
+            reference.update(*x, *y);
+        }
+        assert!((joint.correlation() - reference.correlation()).abs() < 1e-9);
+        assert!((joint.beta_yx() - reference.beta_yx()).abs() < 1e-9);
+    }
 }
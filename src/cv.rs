@@ -0,0 +1,132 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use crate::variance::{RollingVariance, Variance};
+use serde::{Deserialize, Serialize};
+/// Rolling coefficient of variation, `std / mean` over the window.
+/// The ratio is meaningless when the mean of the window is close to 0, so `get` returns `NaN`
+/// when `|mean| <= sqrt(epsilon) * std`, i.e. when the coefficient would exceed about `1e8` for
+/// `f64`, and for an empty window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `ddof` - Delta Degrees of Freedom of the variance.
+/// # Examples
+/// ```
+/// use watermill::cv::RollingCoefficientOfVariation;
+/// use watermill::stats::Univariate;
+/// let mut rolling_cv: RollingCoefficientOfVariation<f64> =
+///     RollingCoefficientOfVariation::new(3, 1).unwrap();
+/// for x in [100., 1., 2., 3.].iter(){
+///     rolling_cv.update(*x);
+/// }
+/// assert!((rolling_cv.get() - 0.5).abs() < 1e-9);
+/// rolling_cv.update(-5.);
+/// assert!(rolling_cv.get().is_nan());
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the coefficient of variation](https://en.wikipedia.org/wiki/Coefficient_of_variation)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingCoefficientOfVariation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Variance of the window, its mean is the mean of the window.
+    pub variance: RollingVariance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingCoefficientOfVariation<F> {
+    pub fn new(window_size: usize, ddof: u32) -> Result<Self, &'static str> {
+        Ok(Self {
+            variance: RollingVariance::new(Variance::new(ddof), window_size)?,
+        })
+    }
+    /// Coefficient of variation with the population standard deviation, `ddof = 0`.
+    pub fn population(window_size: usize) -> Result<Self, &'static str> {
+        Self::new(window_size, 0)
+    }
+    /// Coefficient of variation with the sample standard deviation, `ddof = 1`.
+    pub fn sample(window_size: usize) -> Result<Self, &'static str> {
+        Self::new(window_size, 1)
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.variance.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.variance.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.variance.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F>
+    for RollingCoefficientOfVariation<F>
+{
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        let mean = self.variance.to_roll.mean.get();
+        let std = self.variance.get().sqrt();
+        if self.variance.is_empty() || mean.abs() <= F::epsilon().sqrt() * std {
+            return F::nan();
+        }
+        std / mean
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn matches_brute_force() {
+        use crate::cv::RollingCoefficientOfVariation;
+        use crate::stats::Univariate;
+        let data: Vec<f64> = (0..200)
+            .map(|i| 50. + ((i * 37) % 23) as f64 * (1. + (i / 50) as f64))
+            .collect();
+        let window_size = 10;
+        let mut rolling_cv: RollingCoefficientOfVariation<f64> =
+            RollingCoefficientOfVariation::new(window_size, 1).unwrap();
+        assert!(rolling_cv.get().is_nan());
+        for (i, x) in data.iter().enumerate() {
+            rolling_cv.update(*x);
+            let window = &data[(i + 1).saturating_sub(window_size)..=i];
+            let n = window.len() as f64;
+            let mean = window.iter().sum::<f64>() / n;
+            let expected = if window.len() < 2 {
+                0.
+            } else {
+                let var = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.);
+                var.sqrt() / mean
+            };
+            assert!((rolling_cv.get() - expected).abs() < 1e-9);
+        }
+        // A window which is symmetric around 0 has no meaningful coefficient.
+        let mut rolling_cv: RollingCoefficientOfVariation<f64> =
+            RollingCoefficientOfVariation::new(4, 0).unwrap();
+        for x in [0.1, -0.3, 0.3, -0.1] {
+            rolling_cv.update(x);
+        }
+        assert!(rolling_cv.get().is_nan());
+    }
+    #[test]
+    fn population_and_sample_divisors() {
+        use crate::cv::RollingCoefficientOfVariation;
+        use crate::stats::Univariate;
+        let mut population: RollingCoefficientOfVariation<f64> =
+            RollingCoefficientOfVariation::population(4).unwrap();
+        let mut sample: RollingCoefficientOfVariation<f64> =
+            RollingCoefficientOfVariation::sample(4).unwrap();
+        for x in [1., 2., 3., 4.] {
+            population.update(x);
+            sample.update(x);
+        }
+        // The mean is 2.5 and the sum of squared deviations 5.
+        assert!((population.get() - (5. / 4_f64).sqrt() / 2.5).abs() < 1e-12);
+        assert!((sample.get() - (5. / 3_f64).sqrt() / 2.5).abs() < 1e-12);
+    }
+}
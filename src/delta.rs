@@ -0,0 +1,253 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Difference between the last value and the one before it, `x_t - x_{t-1}`.
+/// `get` returns 0 after the first value.
+/// # Examples
+/// ```
+/// use watermill::delta::Delta;
+/// use watermill::stats::Univariate;
+/// let mut delta: Delta<f64> = Delta::new();
+/// delta.update(3.);
+/// assert_eq!(delta.get(), 0.);
+/// delta.update(5.);
+/// assert_eq!(delta.get(), 2.);
+/// delta.update(4.);
+/// assert_eq!(delta.get(), -1.);
+/// ```
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Delta<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    previous: Option<F>,
+    delta: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Delta<F> {
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            delta: F::from_f64(0.).unwrap(),
+        }
+    }
+    /// Last value seen.
+    pub fn previous(&self) -> Option<F> {
+        self.previous
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Delta<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Delta<F> {
+    fn update(&mut self, x: F) {
+        if let Some(previous) = self.previous {
+            self.delta = x - previous;
+        }
+        self.previous = Some(x);
+    }
+    fn get(&self) -> F {
+        self.delta
+    }
+    fn reset(&mut self) {
+        self.previous = None;
+        self.delta = F::from_f64(0.).unwrap();
+    }
+}
+
+/// Relative change between the last value and the one before it, `(x_t - x_{t-1}) / x_{t-1}`.
+/// `get` returns 0 after the first value and `NaN` when the previous value is 0.
+/// # Examples
+/// ```
+/// use watermill::delta::PercentChange;
+/// use watermill::stats::Univariate;
+/// let mut percent_change: PercentChange<f64> = PercentChange::new();
+/// percent_change.update(4.);
+/// assert_eq!(percent_change.get(), 0.);
+/// percent_change.update(5.);
+/// assert_eq!(percent_change.get(), 0.25);
+/// percent_change.update(0.);
+/// assert_eq!(percent_change.get(), -1.);
+/// // The change from 0 is undefined.
+/// percent_change.update(2.);
+/// assert!(percent_change.get().is_nan());
+/// ```
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct PercentChange<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    previous: Option<F>,
+    change: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PercentChange<F> {
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            change: F::from_f64(0.).unwrap(),
+        }
+    }
+    /// Last value seen.
+    pub fn previous(&self) -> Option<F> {
+        self.previous
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for PercentChange<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PercentChange<F> {
+    fn update(&mut self, x: F) {
+        if let Some(previous) = self.previous {
+            self.change = if previous == F::from_f64(0.).unwrap() {
+                F::nan()
+            } else {
+                (x - previous) / previous
+            };
+        }
+        self.previous = Some(x);
+    }
+    fn get(&self) -> F {
+        self.change
+    }
+    fn reset(&mut self) {
+        self.previous = None;
+        self.change = F::from_f64(0.).unwrap();
+    }
+}
+
+/// Direction of a monotonic run, see `RunLength`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    /// Equal consecutive values, a plateau.
+    Constant,
+}
+
+/// Length of the current monotonic run, the number of consecutive steps `x_{t-1} -> x_t` going in
+/// the same direction. A step in another direction starts a new run of length 1, equal values
+/// form a run of their own so plateaus can be detected too. `get` returns the run length, 0 until
+/// two values are seen.
+/// # Examples
+/// ```
+/// use watermill::delta::{Direction, RunLength};
+/// use watermill::stats::Univariate;
+/// let mut run_length: RunLength<f64> = RunLength::new();
+/// for (x, length) in [(1., 0), (2., 1), (4., 2), (7., 3), (5., 1), (3., 2)].iter(){
+///     run_length.update(*x);
+///     assert_eq!(run_length.run_length(), *length);
+/// }
+/// assert_eq!(run_length.direction(), Some(Direction::Decreasing));
+/// assert_eq!(run_length.get(), 2.);
+/// ```
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct RunLength<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    previous: Option<F>,
+    direction: Option<Direction>,
+    run_length: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RunLength<F> {
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            direction: None,
+            run_length: 0,
+        }
+    }
+    /// Number of steps in the current run.
+    pub fn run_length(&self) -> usize {
+        self.run_length
+    }
+    /// Direction of the current run, `None` until two values are seen.
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for RunLength<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RunLength<F> {
+    fn update(&mut self, x: F) {
+        if let Some(previous) = self.previous {
+            let direction = if x > previous {
+                Direction::Increasing
+            } else if x < previous {
+                Direction::Decreasing
+            } else {
+                Direction::Constant
+            };
+            if self.direction == Some(direction) {
+                self.run_length += 1;
+            } else {
+                self.direction = Some(direction);
+                self.run_length = 1;
+            }
+        }
+        self.previous = Some(x);
+    }
+    fn get(&self) -> F {
+        F::from_usize(self.run_length).unwrap()
+    }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn reset_forgets_the_previous_value() {
+        use crate::delta::{Delta, PercentChange};
+        use crate::stats::Univariate;
+        let mut delta: Delta<f64> = Delta::new();
+        let mut percent_change: PercentChange<f64> = PercentChange::new();
+        for x in [2., -2., 1.] {
+            delta.update(x);
+            percent_change.update(x);
+        }
+        assert_eq!(delta.get(), 3.);
+        // The change is relative to the signed previous value.
+        assert_eq!(percent_change.get(), -1.5);
+        delta.reset();
+        percent_change.reset();
+        assert_eq!(delta.previous(), None);
+        delta.update(10.);
+        percent_change.update(10.);
+        assert_eq!(delta.get(), 0.);
+        assert_eq!(percent_change.get(), 0.);
+    }
+    #[test]
+    fn plateaus_are_runs_of_their_own() {
+        use crate::delta::{Direction, RunLength};
+        use crate::stats::Univariate;
+        let mut run_length: RunLength<f64> = RunLength::new();
+        assert_eq!(run_length.direction(), None);
+        let expected = [
+            (1., None, 0),
+            (1., Some(Direction::Constant), 1),
+            (1., Some(Direction::Constant), 2),
+            (2., Some(Direction::Increasing), 1),
+            (2., Some(Direction::Constant), 1),
+            (3., Some(Direction::Increasing), 1),
+        ];
+        for (x, direction, length) in expected {
+            run_length.update(x);
+            assert_eq!(run_length.direction(), direction);
+            assert_eq!(run_length.run_length(), length);
+        }
+        run_length.reset();
+        run_length.update(0.);
+        assert_eq!(run_length.get(), 0.);
+        assert_eq!(run_length.direction(), None);
+    }
+}
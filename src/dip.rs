@@ -0,0 +1,319 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Hartigan's dip of the sorted values `x`, following the algorithm of Hartigan and Hartigan as
+/// fixed in the `diptest` R package. The arrays are indexed from 1 as in the original.
+fn dip<F: Float + FromPrimitive>(x: &[F]) -> F {
+    let n = x.len();
+    if n == 0 {
+        return F::nan();
+    }
+    let fi = |i: usize| F::from_usize(i).unwrap();
+    let one = F::from_f64(1.).unwrap();
+    let mut dip = one;
+    if n < 2 || x[0] == x[n - 1] {
+        return dip / fi(2 * n);
+    }
+    let xs: Vec<F> = std::iter::once(x[0]).chain(x.iter().copied()).collect();
+
+    // Indices over which the convex minorant and the concave majorant have to be combined.
+    let mut mn = vec![0; n + 1];
+    mn[1] = 1;
+    for j in 2..=n {
+        mn[j] = j - 1;
+        loop {
+            let mnj = mn[j];
+            let mnmnj = mn[mnj];
+            if mnj == 1
+                || (xs[j] - xs[mnj]) * (fi(mnj) - fi(mnmnj))
+                    < (xs[mnj] - xs[mnmnj]) * (fi(j) - fi(mnj))
+            {
+                break;
+            }
+            mn[j] = mnmnj;
+        }
+    }
+    let mut mj = vec![0; n + 1];
+    mj[n] = n;
+    for k in (1..n).rev() {
+        mj[k] = k + 1;
+        loop {
+            let mjk = mj[k];
+            let mjmjk = mj[mjk];
+            if mjk == n
+                || (xs[k] - xs[mjk]) * (fi(mjk) - fi(mjmjk))
+                    < (xs[mjk] - xs[mjmjk]) * (fi(k) - fi(mjk))
+            {
+                break;
+            }
+            mj[k] = mjmjk;
+        }
+    }
+
+    let (mut low, mut high) = (1, n);
+    let mut gcm = vec![0; n + 2];
+    let mut lcm = vec![0; n + 2];
+    loop {
+        // Change points of the convex minorant from `high` to `low`.
+        let mut ic = 1;
+        gcm[1] = high;
+        while gcm[ic] > low {
+            let i = gcm[ic];
+            ic += 1;
+            gcm[ic] = mn[i];
+        }
+        let l_gcm = ic;
+        let mut ix = l_gcm - 1;
+        let mut ig = l_gcm;
+        // Change points of the concave majorant from `low` to `high`.
+        ic = 1;
+        lcm[1] = low;
+        while lcm[ic] < high {
+            let i = lcm[ic];
+            ic += 1;
+            lcm[ic] = mj[i];
+        }
+        let l_lcm = ic;
+        let mut iv = 2;
+        let mut ih = 2;
+
+        // Largest distance between the minorant and the majorant.
+        let mut d = F::from_f64(0.).unwrap();
+        if l_gcm != 2 || l_lcm != 2 {
+            loop {
+                let (gcmix, lcmiv) = (gcm[ix], lcm[iv]);
+                if gcmix > lcmiv {
+                    let gcmi1 = gcm[ix + 1];
+                    let dx = (fi(lcmiv) - fi(gcmi1) + one)
+                        - (xs[lcmiv] - xs[gcmi1]) * (fi(gcmix) - fi(gcmi1))
+                            / (xs[gcmix] - xs[gcmi1]);
+                    iv += 1;
+                    if dx >= d {
+                        d = dx;
+                        ig = ix + 1;
+                        ih = iv - 1;
+                    }
+                } else {
+                    let lcmiv1 = lcm[iv - 1];
+                    let dx = (xs[gcmix] - xs[lcmiv1]) * (fi(lcmiv) - fi(lcmiv1))
+                        / (xs[lcmiv] - xs[lcmiv1])
+                        - (fi(gcmix) - fi(lcmiv1) - one);
+                    ix -= 1;
+                    if dx >= d {
+                        d = dx;
+                        ig = ix + 1;
+                        ih = iv;
+                    }
+                }
+                ix = ix.max(1);
+                iv = iv.min(l_lcm);
+                if gcm[ix] == lcm[iv] {
+                    break;
+                }
+            }
+        } else {
+            d = one;
+        }
+        if d < dip {
+            break;
+        }
+
+        // Dips of the convex minorant and of the concave majorant between `low` and `high`.
+        let mut dip_l = F::from_f64(0.).unwrap();
+        for j in ig..l_gcm {
+            let mut max_t = one;
+            let (jb, je) = (gcm[j + 1], gcm[j]);
+            if je - jb > 1 && xs[je] != xs[jb] {
+                let c = (fi(je) - fi(jb)) / (xs[je] - xs[jb]);
+                for jj in jb..=je {
+                    max_t = max_t.max(fi(jj) - fi(jb) + one - (xs[jj] - xs[jb]) * c);
+                }
+            }
+            dip_l = dip_l.max(max_t);
+        }
+        let mut dip_u = F::from_f64(0.).unwrap();
+        for j in ih..l_lcm {
+            let mut max_t = one;
+            let (jb, je) = (lcm[j], lcm[j + 1]);
+            if je - jb > 1 && xs[je] != xs[jb] {
+                let c = (fi(je) - fi(jb)) / (xs[je] - xs[jb]);
+                for jj in jb..=je {
+                    max_t = max_t.max((xs[jj] - xs[jb]) * c - (fi(jj) - fi(jb) - one));
+                }
+            }
+            dip_u = dip_u.max(max_t);
+        }
+        dip = dip.max(dip_l.max(dip_u));
+
+        // Without this check the cycle may never end.
+        if low == gcm[ig] && high == lcm[ih] {
+            break;
+        }
+        low = gcm[ig];
+        high = lcm[ih];
+    }
+    dip / fi(2 * n)
+}
+
+/// Hartigan's dip statistic over a rolling window, a measure of multimodality. The dip is the
+/// largest distance between the empirical distribution function of the window and the closest
+/// unimodal distribution function. It is at least `1 / (2n)` for `n` values, is close to 0 for a
+/// unimodal window and goes up to `0.25` for two well separated modes of equal mass.
+/// The statistic is exact for the window but the test is approximate: no p-value is computed and
+/// the threshold is left to the caller. The critical values of the test shrink like `1 / sqrt(n)`,
+/// see the tables of Hartigan and Hartigan. The window is kept sorted, so a query does not sort
+/// and costs about `O(w)`, `w` being the window size. `get` returns the dip, `NaN` while the
+/// window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::dip::DipTestApprox;
+/// use watermill::stats::Univariate;
+/// let mut dip_test: DipTestApprox<f64> = DipTestApprox::new(100).unwrap();
+/// // Evenly spaced values, the empirical distribution is already unimodal.
+/// for i in 0..100{
+///     dip_test.update(i as f64);
+/// }
+/// assert!((dip_test.dip() - 1. / 200.).abs() < 1e-12);
+/// // Two equal clusters far apart.
+/// for i in 0..100{
+///     dip_test.update(if i % 2 == 0 { 0. } else { 100. });
+/// }
+/// assert!((dip_test.dip() - 0.25).abs() < 1e-12);
+/// assert!(dip_test.is_likely_multimodal(0.1));
+/// ```
+/// # References
+/// [^1]: [Hartigan, J.A. and Hartigan, P.M., 1985. The dip test of unimodality. The Annals of Statistics, 13(1), pp.70-84.](https://projecteuclid.org/journals/annals-of-statistics/volume-13/issue-1/The-Dip-Test-of-Unimodality/10.1214/aos/1176346577.full)
+///
+/// [^2]: [Maechler, M., 2021. diptest: Hartigan's dip test statistic for unimodality - corrected.](https://cran.r-project.org/package=diptest)
+#[derive(Serialize, Deserialize)]
+pub struct DipTestApprox<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> DipTestApprox<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size),
+        })
+    }
+    /// Dip of the current window.
+    pub fn dip(&self) -> F {
+        let sorted: Vec<F> = self
+            .sorted_window
+            .sorted_window
+            .iter()
+            .map(|x| x.into_inner())
+            .collect();
+        dip(&sorted)
+    }
+    /// Returns `true` if the dip of the window is greater than `threshold`.
+    pub fn is_likely_multimodal(&self, threshold: F) -> bool {
+        self.dip() > threshold
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for DipTestApprox<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.dip()
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn unimodal_and_bimodal_windows() {
+        use crate::dip::DipTestApprox;
+        use crate::stats::Univariate;
+        let window_size = 200;
+        assert!(DipTestApprox::<f64>::new(0).is_err());
+        let mut dip_test: DipTestApprox<f64> = DipTestApprox::new(window_size).unwrap();
+        assert!(dip_test.get().is_nan());
+        dip_test.update(1.);
+        assert_eq!(dip_test.get(), 0.5);
+        // A triangular distribution, peaked at 0.
+        for i in 0..window_size {
+            let u = (i as f64 + 0.5) / window_size as f64;
+            let x = if u < 0.5 {
+                (2. * u).sqrt() - 1.
+            } else {
+                1. - (2. * (1. - u)).sqrt()
+            };
+            dip_test.update(x);
+        }
+        let unimodal = dip_test.dip();
+        assert!(unimodal < 0.02);
+        assert!(!dip_test.is_likely_multimodal(0.05));
+        // Two triangular bumps centered on -5 and 5.
+        for i in 0..window_size {
+            let u = ((i / 2) as f64 + 0.5) / (window_size / 2) as f64;
+            let x = if u < 0.5 {
+                (2. * u).sqrt() - 1.
+            } else {
+                1. - (2. * (1. - u)).sqrt()
+            };
+            dip_test.update(if i % 2 == 0 { x - 5. } else { x + 5. });
+        }
+        let bimodal = dip_test.dip();
+        assert!(bimodal > 0.15);
+        assert!(dip_test.is_likely_multimodal(0.05));
+        // The dip does not depend on the order of arrival.
+        let mut reversed: DipTestApprox<f64> = DipTestApprox::new(window_size).unwrap();
+        for i in (0..window_size).rev() {
+            let u = ((i / 2) as f64 + 0.5) / (window_size / 2) as f64;
+            let x = if u < 0.5 {
+                (2. * u).sqrt() - 1.
+            } else {
+                1. - (2. * (1. - u)).sqrt()
+            };
+            reversed.update(if i % 2 == 0 { x - 5. } else { x + 5. });
+        }
+        assert!((reversed.dip() - bimodal).abs() < 1e-12);
+    }
+    #[test]
+    fn uneven_and_tied_windows() {
+        use crate::dip::dip;
+        use crate::stats::uniform;
+        // `diptest::dip` in R.
+        assert!((dip::<f64>(&[0., 0., 1., 1., 1.]) - 0.2).abs() < 1e-12);
+        let uneven: [f64; 5] = [20903., 34266., 87841., 94468., 97313.];
+        assert!((dip(&uneven) - 0.160_073_500_851_534_2).abs() < 1e-12);
+        // The dip never goes beyond `0.25`.
+        let mut uniform = uniform(5);
+        for len in (2..12).cycle().take(20_000) {
+            let mut x: Vec<f64> = (0..len).map(|_| uniform()).collect();
+            x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let value = dip(&x);
+            assert!(value >= 1. / (2 * len) as f64 - 1e-12 && value <= 0.25 + 1e-12);
+        }
+    }
+}
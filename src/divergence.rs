@@ -0,0 +1,291 @@
+use num::{Float, FromPrimitive};
+use ordered_float::NotNan;
+use std::collections::HashMap;
+use std::ops::{AddAssign, SubAssign};
+
+use crate::counter::Counter;
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Kullback–Leibler divergence `KL(P‖Q) = Σ p_i ln(p_i / q_i)` of the categorical stream `P` from
+/// a fixed reference distribution `Q`, in nats.
+/// `P` is the empirical distribution of the values seen by `update`. The reference is normalized to
+/// sum to 1, then smoothed over the categories of the reference and of the stream so that a value
+/// missing from the reference has a finite divergence: `q_i = (r_i + epsilon) / (1 + k epsilon)`,
+/// `k` being the number of categories. `get` returns 0 while the stream is empty.
+/// # Arguments
+/// * `reference` - Weight of each category of the reference, the weights should not be negative.
+/// * `epsilon` - Smoothing added to the reference probabilities, should be small and greater than 0.
+/// # Examples
+/// ```
+/// use ordered_float::NotNan;
+/// use std::collections::HashMap;
+/// use watermill::divergence::KLDivergence;
+/// use watermill::stats::Univariate;
+/// let reference: HashMap<NotNan<f64>, f64> =
+///     [(NotNan::new(0.).unwrap(), 0.5), (NotNan::new(1.).unwrap(), 0.5)].into_iter().collect();
+/// let mut kl: KLDivergence<f64> = KLDivergence::new(reference, 1e-9).unwrap();
+/// for x in [0., 1., 1., 0.].iter(){
+///     kl.update(*x);
+/// }
+/// assert!(kl.get().abs() < 1e-6);
+/// // The stream drifts to a single category.
+/// for _ in 0..96{
+///     kl.update(0.);
+/// }
+/// let expected = 0.98 * (0.98_f64 / 0.5).ln() + 0.02 * (0.02_f64 / 0.5).ln();
+/// assert!((kl.get() - expected).abs() < 1e-6);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Kullback–Leibler divergence](https://en.wikipedia.org/wiki/Kullback%E2%80%93Leibler_divergence)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KLDivergence<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Normalized reference distribution.
+    reference: HashMap<NotNan<F>, F>,
+    pub epsilon: F,
+    counter: Counter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> KLDivergence<F> {
+    pub fn new(reference: HashMap<NotNan<F>, F>, epsilon: F) -> Result<Self, &'static str> {
+        let zero = F::from_f64(0.).unwrap();
+        if epsilon <= zero {
+            return Err("epsilon should be greater than 0");
+        }
+        if reference.values().any(|weight| *weight < zero) {
+            return Err("The weights of the reference should not be negative");
+        }
+        let total = reference
+            .values()
+            .fold(zero, |total, weight| total + *weight);
+        if total <= zero {
+            return Err("The weights of the reference should sum to a positive value");
+        }
+        Ok(Self {
+            reference: reference
+                .into_iter()
+                .map(|(category, weight)| (category, weight / total))
+                .collect(),
+            epsilon,
+            counter: Counter::new(),
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for KLDivergence<F> {
+    fn update(&mut self, x: F) {
+        self.counter.add(x);
+    }
+    fn get(&self) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        if self.counter.total() == 0 {
+            return zero;
+        }
+        let unseen = self
+            .counter
+            .counts
+            .keys()
+            .filter(|category| !self.reference.contains_key(*category))
+            .count();
+        let k = F::from_usize(self.reference.len() + unseen).unwrap();
+        let normalization = F::from_f64(1.).unwrap() + k * self.epsilon;
+        let total = F::from_usize(self.counter.total()).unwrap();
+        let mut divergence = zero;
+        for (category, count) in self.counter.counts.iter() {
+            let p = F::from_usize(*count).unwrap() / total;
+            let r = self.reference.get(category).copied().unwrap_or(zero);
+            let q = (r + self.epsilon) / normalization;
+            divergence += p * (p / q).ln();
+        }
+        divergence
+    }
+    fn reset(&mut self) {
+        self.counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.counter.total())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for KLDivergence<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.counter.remove(x)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for KLDivergence<F> {}
+
+/// Population stability index, `PSI = Σ (a_i - e_i) ln(a_i / e_i)` between the expected
+/// proportions `e_i` of a set of bins and the actual proportions `a_i` of the values seen by
+/// `update`. Values outside of the edges are counted in the first or the last bin. Proportions
+/// below `epsilon` are replaced by `epsilon` so that an empty bin has a finite contribution.
+/// `get` returns 0 while the stream is empty.
+/// # Arguments
+/// * `edges` - Increasing edges of the bins, one more than the number of bins.
+/// * `expected` - Expected proportion of each bin, usually measured on the training data.
+/// * `epsilon` - Floor of the proportions, e.g. `1e-4`.
+/// # Examples
+/// ```
+/// use watermill::divergence::PSI;
+/// use watermill::stats::Univariate;
+/// let mut psi: PSI<f64> = PSI::new(vec![0., 1., 2., 3.], vec![0.5, 0.3, 0.2], 1e-4).unwrap();
+/// for x in [0.5, 0.5, 1.5, 1.5, 2.5].iter(){
+///     psi.update(*x);
+/// }
+/// // The actual proportions are 0.4, 0.4 and 0.2.
+/// let expected = (0.4 - 0.5) * (0.4_f64 / 0.5).ln() + (0.4 - 0.3) * (0.4_f64 / 0.3).ln();
+/// assert!((psi.get() - expected).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Yurdakul, B., 2018. Statistical properties of population stability index. Western Michigan University.](https://scholarworks.wmich.edu/dissertations/3208/)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PSI<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    edges: Vec<F>,
+    expected: Vec<F>,
+    pub epsilon: F,
+    counts: Vec<usize>,
+    total: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PSI<F> {
+    pub fn new(edges: Vec<F>, expected: Vec<F>, epsilon: F) -> Result<Self, &'static str> {
+        if expected.is_empty() || edges.len() != expected.len() + 1 {
+            return Err("There should be one more edge than expected proportions");
+        }
+        if edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err("The edges should be strictly increasing");
+        }
+        if expected.iter().any(|e| *e < F::from_f64(0.).unwrap()) {
+            return Err("The expected proportions should not be negative");
+        }
+        if epsilon <= F::from_f64(0.).unwrap() {
+            return Err("epsilon should be greater than 0");
+        }
+        let n_bins = expected.len();
+        Ok(Self {
+            edges,
+            expected,
+            epsilon,
+            counts: vec![0; n_bins],
+            total: 0,
+        })
+    }
+    fn bin(&self, x: F) -> usize {
+        let interior = &self.edges[1..self.edges.len() - 1];
+        interior.partition_point(|edge| *edge <= x)
+    }
+    /// Number of values in each bin.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PSI<F> {
+    fn update(&mut self, x: F) {
+        let bin = self.bin(x);
+        self.counts[bin] += 1;
+        self.total += 1;
+    }
+    fn get(&self) -> F {
+        let mut psi = F::from_f64(0.).unwrap();
+        if self.total == 0 {
+            return psi;
+        }
+        let total = F::from_usize(self.total).unwrap();
+        for (count, e) in self.counts.iter().zip(self.expected.iter()) {
+            let a = (F::from_usize(*count).unwrap() / total).max(self.epsilon);
+            let e = e.max(self.epsilon);
+            psi += (a - e) * (a / e).ln();
+        }
+        psi
+    }
+    fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.total = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.total)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for PSI<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        let bin = self.bin(x);
+        if self.counts[bin] == 0 {
+            return Err("The value is not in the bins");
+        }
+        self.counts[bin] -= 1;
+        self.total -= 1;
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for PSI<F> {}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn unseen_categories_are_smoothed() {
+        use crate::divergence::KLDivergence;
+        use crate::stats::{Revertable, Univariate};
+        use ordered_float::NotNan;
+        use std::collections::HashMap;
+        // Unnormalized weights.
+        let reference: HashMap<NotNan<f64>, f64> = [(1., 2.), (2., 1.), (3., 1.)]
+            .iter()
+            .map(|(category, weight)| (NotNan::new(*category).unwrap(), *weight))
+            .collect();
+        assert!(KLDivergence::new(reference.clone(), 0.).is_err());
+        assert!(KLDivergence::new(reference.clone(), -0.01).is_err());
+        let mut kl: KLDivergence<f64> = KLDivergence::new(reference.clone(), 1e-12).unwrap();
+        assert_eq!(kl.get(), 0.);
+        for x in [1., 1., 2., 3.] {
+            kl.update(x);
+        }
+        assert!(kl.get().abs() < 1e-9);
+        // A category missing from the reference has a large but finite divergence.
+        kl.update(4.);
+        assert!(kl.get().is_finite() && kl.get() > 4.);
+        let epsilon = 0.01;
+        let mut smoothed: KLDivergence<f64> = KLDivergence::new(reference, epsilon).unwrap();
+        for x in [1., 1., 2., 3., 4.] {
+            smoothed.update(x);
+        }
+        let normalization = 1. + 4. * epsilon;
+        let expected: f64 = [(0.4, 0.5), (0.2, 0.25), (0.2, 0.25), (0.2, 0.)]
+            .iter()
+            .map(|(p, r)| {
+                let q: f64 = (r + epsilon) / normalization;
+                p * (p / q).ln()
+            })
+            .sum();
+        assert!((smoothed.get() - expected).abs() < 1e-12);
+        smoothed.revert(4.).unwrap();
+        assert!(smoothed.get() < expected);
+        assert!(KLDivergence::<f64>::new(HashMap::new(), epsilon).is_err());
+    }
+    #[test]
+    fn psi_bins_and_empty_bins() {
+        use crate::divergence::PSI;
+        use crate::stats::{Revertable, Univariate};
+        let expected = vec![0.25, 0.25, 0.25, 0.25];
+        let mut psi: PSI<f64> = PSI::new(vec![0., 1., 2., 3., 4.], expected, 1e-4).unwrap();
+        assert_eq!(psi.get(), 0.);
+        // Values outside of the edges fall in the extreme bins, edges belong to the upper bin.
+        for x in [-5., 1., 2.5, 10.] {
+            psi.update(x);
+        }
+        assert_eq!(psi.counts(), &[1, 1, 1, 1]);
+        assert!(psi.get().abs() < 1e-12);
+        psi.revert(10.).unwrap();
+        psi.revert(2.5).unwrap();
+        assert!(psi.revert(3.).is_err());
+        // Two bins are empty and floored at `epsilon`.
+        let expected_psi =
+            2. * (0.5 - 0.25) * (0.5_f64 / 0.25).ln() + 2. * (1e-4 - 0.25) * (1e-4_f64 / 0.25).ln();
+        assert!((psi.get() - expected_psi).abs() < 1e-12);
+        psi.reset();
+        assert_eq!(psi.get(), 0.);
+        assert!(PSI::new(vec![0., 1.], vec![0.5, 0.5], 1e-4).is_err());
+        assert!(PSI::new(vec![0., 2., 1.], vec![0.5, 0.5], 1e-4).is_err());
+    }
+}
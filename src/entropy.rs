@@ -0,0 +1,156 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::counter::{Counter, RollingCounter};
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+
+fn shannon<F: Float + FromPrimitive + AddAssign + SubAssign>(counter: &Counter<F>) -> F {
+    if counter.is_empty() {
+        return F::from_f64(0.).unwrap();
+    }
+    let total = F::from_usize(counter.total()).unwrap();
+    let mut entropy = F::from_f64(0.).unwrap();
+    for (_, count) in counter.iter() {
+        let p = F::from_usize(count).unwrap() / total;
+        entropy -= p * p.ln();
+    }
+    entropy
+}
+
+/// Running Shannon entropy of a categorical stream, `-Σ p_i ln(p_i)` where `p_i` is the share of
+/// the `i`-th distinct value. It is expressed in nats, 0 for a pure stream and `ln(k)` for `k`
+/// equally frequent values.
+/// # Examples
+/// ```
+/// use watermill::entropy::Entropy;
+/// use watermill::stats::{Revertable, Univariate};
+/// let mut running_entropy: Entropy<f64> = Entropy::new();
+/// for x in [0., 1., 1., 0.].iter(){
+///     running_entropy.update(*x);
+/// }
+/// assert!((running_entropy.get() - 2_f64.ln()).abs() < 1e-12);
+/// running_entropy.revert(1.).unwrap();
+/// running_entropy.revert(1.).unwrap();
+/// assert_eq!(running_entropy.get(), 0.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on entropy](https://en.wikipedia.org/wiki/Entropy_(information_theory))
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Entropy<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counter: Counter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Entropy<F> {
+    pub fn new() -> Self {
+        Self {
+            counter: Counter::new(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Entropy<F> {
+    fn update(&mut self, x: F) {
+        self.counter.add(x);
+    }
+    fn get(&self) -> F {
+        shannon(&self.counter)
+    }
+    fn reset(&mut self) {
+        self.counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.counter.total())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Entropy<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.counter.remove(x)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Entropy<F> {}
+
+/// Rolling Shannon entropy, in nats. The counts of the window are kept up to date on each update
+/// and `get` costs `O(k)`, `k` being the number of distinct values in the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::entropy::RollingEntropy;
+/// use watermill::stats::Univariate;
+/// let mut rolling_entropy: RollingEntropy<f64> = RollingEntropy::new(2).unwrap();
+/// for x in [1., 2., 2.].iter(){
+///     rolling_entropy.update(*x);
+/// }
+/// assert_eq!(rolling_entropy.get(), 0.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingEntropy<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    rolling_counter: RollingCounter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingEntropy<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            rolling_counter: RollingCounter::new(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.rolling_counter.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.rolling_counter.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.rolling_counter.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingEntropy<F> {
+    fn update(&mut self, x: F) {
+        self.rolling_counter.push_back(x);
+    }
+    fn get(&self) -> F {
+        shannon(&self.rolling_counter.counter)
+    }
+    fn reset(&mut self) {
+        self.rolling_counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.rolling_counter.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn rises_when_the_window_becomes_multi_class() {
+        use crate::entropy::RollingEntropy;
+        use crate::stats::Univariate;
+        assert!(RollingEntropy::<f64>::new(0).is_err());
+        let mut rolling_entropy: RollingEntropy<f64> = RollingEntropy::new(12).unwrap();
+        for _ in 0..20 {
+            rolling_entropy.update(0.);
+        }
+        assert_eq!(rolling_entropy.get(), 0.);
+        // Classes 0, 1, 2 cycle in, the entropy grows until the window forgets the pure stream.
+        let mut previous = 0.;
+        for i in 0..12 {
+            rolling_entropy.update((i % 3) as f64);
+            let entropy = rolling_entropy.get();
+            if i % 3 != 0 {
+                assert!(entropy > previous);
+            }
+            previous = entropy;
+        }
+        assert!((rolling_entropy.get() - 3_f64.ln()).abs() < 1e-12);
+    }
+}
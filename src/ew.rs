@@ -0,0 +1,121 @@
+use num::{Float, FromPrimitive, Num};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::mean::Mean;
+use crate::stats::Univariate;
+use crate::sum::Sum;
+use serde::{Deserialize, Serialize};
+
+/// Statistics whose accumulated state can be scaled down, which is what exponential weighting needs.
+pub trait Decay<F: Num + Copy> {
+    /// Multiplies the weight of every value seen so far by `factor`.
+    fn decay(&mut self, factor: F);
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Decay<F> for Sum<F> {
+    fn decay(&mut self, factor: F) {
+        self.sum = self.sum * factor;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Decay<F> for Mean<F> {
+    fn decay(&mut self, factor: F) {
+        // The mean is unchanged, only the weight of past values shrinks.
+        self.n.count = self.n.count * factor;
+        self.forget_exact_count();
+    }
+}
+
+/// Generic exponentially weighted wrapper.
+/// Before each update the weight of the values already seen by the wrapped statistic is multiplied by `1 - alpha`.
+/// * `EW<F, Sum<F>>` computes `x + (1 - alpha) * sum`, a decaying total.
+/// * `EW<F, Mean<F>>` computes the exactly weighted average `Σ (1 - alpha)^i x_{t-i} / Σ (1 - alpha)^i`.
+///   It starts from the first value like `EWMean` and converges to `EWMean` as the influence of the
+///   first values vanishes.
+/// # Arguments
+/// * `inner` - The statistic to weight, it must implement `Decay`.
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values.
+/// # Examples
+/// ```
+/// use watermill::ew::EW;
+/// use watermill::ewmean::EWMean;
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let mut ew_mean: EW<f64, Mean<f64>> = EW::new(Mean::new(), 0.5);
+/// let mut running_ewmean: EWMean<f64> = EWMean::new(0.5);
+/// for i in 1..=60{
+///     let x = (i % 7) as f64;
+///     ew_mean.update(x);
+///     running_ewmean.update(x);
+/// }
+/// assert!((ew_mean.get() - running_ewmean.get()).abs() < 1e-12);
+/// ```
+/// A decaying sum:
+/// ```
+/// use watermill::ew::EW;
+/// use watermill::sum::Sum;
+/// use watermill::stats::Univariate;
+/// let mut ew_sum: EW<f64, Sum<f64>> = EW::new(Sum::new(), 0.5);
+/// for x in [4., 0., 0.]{
+///     ew_sum.update(x);
+/// }
+/// assert_eq!(ew_sum.get(), 1.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EW<F: Float + FromPrimitive + AddAssign + SubAssign, U> {
+    pub inner: U,
+    pub alpha: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign, U> EW<F, U> {
+    pub fn new(inner: U, alpha: F) -> Self {
+        Self { inner, alpha }
+    }
+}
+
+impl<F, U> Univariate<F> for EW<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Decay<F>,
+{
+    fn update(&mut self, x: F) {
+        self.inner.decay(F::from_f64(1.).unwrap() - self.alpha);
+        self.inner.update(x);
+    }
+    /// A missing reading still decays the weight of the values already seen.
+    fn update_opt(&mut self, x: Option<F>) {
+        match x {
+            Some(x) => self.update(x),
+            None => self.inner.decay(F::from_f64(1.).unwrap() - self.alpha),
+        }
+    }
+    fn get(&self) -> F {
+        self.inner.get()
+    }
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.inner.n_samples()
+    }
+}
+
+/// Exponentially weighted sum, each update computes `sum = x + (1 - alpha) * sum`: a total whose
+/// past values fade out, e.g. a decaying event volume.
+/// # Examples
+/// ```
+/// use watermill::ew::EWSum;
+/// use watermill::sum::Sum;
+/// use watermill::stats::Univariate;
+/// let mut volume: EWSum<f64> = EWSum::new(Sum::new(), 0.5);
+/// // A burst of 3 events, then nothing.
+/// for x in [1., 1., 1.]{
+///     volume.update(x);
+/// }
+/// assert_eq!(volume.get(), 1.75);
+/// for x in [0., 0.]{
+///     volume.update(x);
+/// }
+/// assert_eq!(volume.get(), 0.4375);
+/// ```
+pub type EWSum<F> = EW<F, Sum<F>>;
@@ -0,0 +1,104 @@
+use crate::ewmean::{Adjust, EWMean};
+use crate::stats::Univariate;
+use num::{Float, FromPrimitive};
+use serde::{Deserialize, Serialize};
+use std::ops::{AddAssign, SubAssign};
+
+/// Exponentially weighted mean absolute deviation, a spread less sensitive to outliers than
+/// `EWVariance`.
+/// Each update first moves the mean towards `x`, then feeds `|x - mean|` to a second `EWMean`,
+/// i.e. the deviation is measured from the mean that already includes `x`.
+/// # Arguments
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+/// * `adjust` - How the first values are weighted, see `Adjust`. Defaults to `Adjust::Recursive`
+///   because `Adjust::Legacy` mistakes a deviation of exactly 0 for the absence of values.
+/// # Examples
+/// ```
+/// use watermill::ewmad::EWMAD;
+/// use watermill::stats::Univariate;
+/// let mut running_ewmad: EWMAD<f64> = EWMAD::new(0.5);
+/// // The means are 1, 2 and 3.5, the deviations 0, 1 and 1.5.
+/// for x in [1., 3., 5.].iter(){
+///     running_ewmad.update(*x);
+/// }
+/// assert_eq!(running_ewmad.get(), 0.5 * 0.5 + 0.5 * 1.5);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the average absolute deviation](https://en.wikipedia.org/wiki/Average_absolute_deviation)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EWMAD<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub mean: EWMean<F>,
+    pub deviation: EWMean<F>,
+    pub alpha: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWMAD<F> {
+    pub fn new(alpha: F) -> Self {
+        Self::with_adjust(alpha, Adjust::Recursive)
+    }
+    pub fn with_adjust(alpha: F, adjust: Adjust) -> Self {
+        Self {
+            mean: EWMean::with_adjust(alpha, adjust),
+            deviation: EWMean::with_adjust(alpha, adjust),
+            alpha,
+        }
+    }
+}
+
+impl<F> Default for EWMAD<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(0.5).unwrap())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWMAD<F> {
+    fn update(&mut self, x: F) {
+        self.mean.update(x);
+        self.deviation.update((x - self.mean.get()).abs());
+    }
+    fn update_opt(&mut self, x: Option<F>) {
+        match x {
+            Some(x) => self.update(x),
+            None => {
+                self.mean.update_opt(None);
+                self.deviation.update_opt(None);
+            }
+        }
+    }
+    fn get(&self) -> F {
+        self.deviation.get()
+    }
+    fn reset(&mut self) {
+        self.mean.reset();
+        self.deviation.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn outliers_move_the_mad_less_than_the_std() {
+        use crate::ewmad::EWMAD;
+        use crate::ewmean::Adjust;
+        use crate::ewvariance::EWVariance;
+        use crate::stats::Univariate;
+        let mut running_ewmad: EWMAD<f64> = EWMAD::new(0.1);
+        let mut running_ewvariance: EWVariance<f64> =
+            EWVariance::with_adjust(0.1, Adjust::Recursive);
+        for i in 0..200 {
+            let x = if i % 2 == 0 { 1. } else { -1. };
+            running_ewmad.update(x);
+            running_ewvariance.update(x);
+        }
+        let (mad, std) = (running_ewmad.get(), running_ewvariance.get().sqrt());
+        assert!((mad - 1.).abs() < 0.1 && (std - 1.).abs() < 0.1);
+        running_ewmad.update(50.);
+        running_ewvariance.update(50.);
+        assert!(running_ewmad.get() / mad < running_ewvariance.get().sqrt() / std);
+        running_ewmad.reset();
+        assert_eq!(running_ewmad.get(), 0.);
+    }
+}
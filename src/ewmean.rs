@@ -1,11 +1,34 @@
 use num::{Float, FromPrimitive};
 use std::ops::{AddAssign, SubAssign};
 
-use crate::stats::Univariate;
+use crate::stats::{Revertable, Univariate};
 use serde::{Deserialize, Serialize};
+/// How the first values are weighted by exponentially weighted statistics.
+/// `Recursive` and `Weighted` follow the `adjust=False` and `adjust=True` options of pandas `ewm`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Adjust {
+    /// The first value becomes the mean, and so does any value seen while the mean is exactly `0`.
+    #[default]
+    Legacy,
+    /// `y_0 = x_0` then `y_t = (1 - alpha) * y_{t-1} + alpha * x_t`.
+    Recursive,
+    /// Weighted average of all the values with weights `(1 - alpha)^i`, `i` being the age of a
+    /// value. The running denominator makes the first values unbiased.
+    Weighted,
+}
+
+pub(crate) fn zero<F: Float>() -> F {
+    F::zero()
+}
+
+fn no_previous<F: Float>() -> Option<(F, F)> {
+    None
+}
+
 /// Exponentially weighted mean.
 /// # Arguments
 /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+/// * `adjust` - How the first values are weighted, see `Adjust`. Defaults to `Adjust::Legacy`.
 /// # Examples
 /// ```
 /// use watermill::ewmean::EWMean;
@@ -17,6 +40,15 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_ewmean.get(), 9.4296875);
 /// ```
+/// With `Adjust::Weighted` the result is the same as `pd.Series(data).ewm(alpha=0.5).mean()`:
+/// ```
+/// use watermill::ewmean::{Adjust, EWMean};
+/// use watermill::stats::Univariate;
+/// let mut running_ewmean: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Weighted);
+/// running_ewmean.update(1.);
+/// running_ewmean.update(3.);
+/// assert!((running_ewmean.get() - 7. / 3.).abs() < 1e-12);
+/// ```
 /// # References
 /// [^1]: [Finch, T., 2009. Incremental calculation of weighted mean and variance. University of Cambridge, 4(11-5), pp.41-42.](https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf)
 ///
@@ -25,12 +57,26 @@ use serde::{Deserialize, Serialize};
 pub struct EWMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub mean: F,
     pub alpha: F,
+    #[serde(default)]
+    pub adjust: Adjust,
+    /// Sum of the weights of the values seen so far, unused by `Adjust::Legacy`.
+    #[serde(default = "zero")]
+    weight: F,
+    /// Mean and weight before the last update, used by `revert`.
+    #[serde(default = "no_previous")]
+    previous: Option<(F, F)>,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWMean<F> {
     pub fn new(alpha: F) -> Self {
+        Self::with_adjust(alpha, Adjust::Legacy)
+    }
+    pub fn with_adjust(alpha: F, adjust: Adjust) -> Self {
         Self {
             mean: F::from_f64(0.0).unwrap(),
             alpha,
+            adjust,
+            weight: F::from_f64(0.0).unwrap(),
+            previous: None,
         }
     }
 }
@@ -40,22 +86,333 @@ where
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
     fn default() -> Self {
-        Self {
+        Self::new(F::from_f64(0.5).unwrap())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWMean<F> {
+    fn update(&mut self, x: F) {
+        let one = F::from_f64(1.).unwrap();
+        self.previous = Some((self.mean, self.weight));
+        match self.adjust {
+            Adjust::Legacy => {
+                if self.mean == F::from_f64(0.).unwrap() {
+                    self.mean = x;
+                } else {
+                    self.mean = self.alpha * x + (one - self.alpha) * self.mean;
+                }
+            }
+            Adjust::Recursive => {
+                if self.weight == F::from_f64(0.).unwrap() {
+                    self.mean = x;
+                } else {
+                    // `weight` is below 1 when readings were missing since the last update.
+                    let old_weight = (one - self.alpha) * self.weight;
+                    self.mean =
+                        (old_weight * self.mean + self.alpha * x) / (old_weight + self.alpha);
+                }
+                self.weight = one;
+            }
+            Adjust::Weighted => {
+                self.weight = (one - self.alpha) * self.weight + one;
+                self.mean += (x - self.mean) / self.weight;
+            }
+        }
+    }
+    fn get(&self) -> F {
+        self.mean
+    }
+    /// A missing reading leaves the mean untouched but, like pandas with `ignore_na=False`,
+    /// still decays the weight of the past values unless the mode is `Adjust::Legacy`.
+    fn update_opt(&mut self, x: Option<F>) {
+        match x {
+            Some(x) => self.update(x),
+            None => {
+                self.previous = Some((self.mean, self.weight));
+                if self.adjust != Adjust::Legacy {
+                    self.weight = self.weight * (F::from_f64(1.).unwrap() - self.alpha);
+                }
+            }
+        }
+    }
+    fn reset(&mut self) {
+        self.mean = F::from_f64(0.).unwrap();
+        self.weight = F::from_f64(0.).unwrap();
+        self.previous = None;
+    }
+}
+
+/// Best-effort revert: the exponential weights do not allow removing an arbitrary old value, so
+/// only the most recent update can be undone. `revert` restores the mean and the weight stored
+/// before that update, `x` is not used. Reverting twice in a row, or before any update, returns
+/// an error. `EWMean` is therefore not `RollableUnivariate`.
+/// # Examples
+/// ```
+/// use watermill::ewmean::EWMean;
+/// use watermill::stats::{Revertable, Univariate};
+/// let mut running_ewmean: EWMean<f64> = EWMean::new(0.5);
+/// running_ewmean.update(1.);
+/// running_ewmean.update(3.);
+/// running_ewmean.update(100.);
+/// running_ewmean.revert(100.).unwrap();
+/// assert_eq!(running_ewmean.get(), 2.);
+/// assert!(running_ewmean.revert(3.).is_err());
+/// ```
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for EWMean<F> {
+    fn revert(&mut self, _x: F) -> Result<(), &'static str> {
+        match self.previous.take() {
+            Some((mean, weight)) => {
+                self.mean = mean;
+                self.weight = weight;
+                Ok(())
+            }
+            None => Err("EWMean can only revert its last update"),
+        }
+    }
+}
+/// Time-aware exponentially weighted mean, for irregularly sampled streams.
+/// The weight of the previous mean decays with the time elapsed since the last update: a value
+/// seen at time `t` gives `mean = w * mean + (1 - w) * x` with `w = exp(-(t - last_t) / tau)`.
+/// `update` assumes one time unit between two values, use `update_at` to provide timestamps. A
+/// missing reading passed to `update_opt` also takes a time unit, so the next value decays the
+/// mean over the whole gap.
+/// `get_at` reads the mean as it would be at a later time, decayed towards 0 by
+/// `exp(-(t - last_t) / tau)`, so a stream which stopped updating fades out instead of looking fresh.
+/// # Arguments
+/// * `tau` - Time constant of the decay, in the unit of the timestamps.
+/// # Examples
+/// ```
+/// use watermill::ewmean::TimeEWMean;
+/// use watermill::stats::Univariate;
+/// let mut running_ewmean: TimeEWMean<f64> = TimeEWMean::new(10.).unwrap();
+/// running_ewmean.update_at(4., 0.);
+/// running_ewmean.update_at(8., 10.);
+/// let w = (-1_f64).exp();
+/// assert!((running_ewmean.get() - (4. * w + 8. * (1. - w))).abs() < 1e-12);
+/// // Ten time units later the reading has decayed by `exp(-1)`.
+/// assert!((running_ewmean.get_at(20.) - running_ewmean.get() * w).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TimeEWMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub tau: F,
+    mean: F,
+    last_t: Option<F>,
+    /// Missing readings since the last update, each one delays the implicit time of `update`.
+    #[serde(default)]
+    missing: u64,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> TimeEWMean<F> {
+    pub fn new(tau: F) -> Result<Self, &'static str> {
+        if tau <= F::from_f64(0.).unwrap() {
+            return Err("tau should be greater than 0");
+        }
+        Ok(Self {
+            tau,
             mean: F::from_f64(0.).unwrap(),
-            alpha: F::from_f64(0.5).unwrap(),
+            last_t: None,
+            missing: 0,
+        })
+    }
+    fn decay(&self, t: F) -> F {
+        match self.last_t {
+            Some(last_t) => (-(t - last_t).max(F::from_f64(0.).unwrap()) / self.tau).exp(),
+            None => F::from_f64(0.).unwrap(),
         }
     }
+    /// Updates the mean with a value `x` observed at time `t`.
+    pub fn update_at(&mut self, x: F, t: F) {
+        let w = self.decay(t);
+        self.mean = w * self.mean + (F::from_f64(1.).unwrap() - w) * x;
+        self.last_t = Some(t);
+        self.missing = 0;
+    }
+    /// Time of the last update.
+    pub fn last_time(&self) -> Option<F> {
+        self.last_t
+    }
+    /// Mean as seen at time `t`, decayed since the last update. The statistic is left untouched.
+    pub fn get_at(&self, t: F) -> F {
+        self.mean * self.decay(t)
+    }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWMean<F> {
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for TimeEWMean<F> {
     fn update(&mut self, x: F) {
-        if self.mean == F::from_f64(0.).unwrap() {
-            self.mean = x;
-        } else {
-            self.mean = self.alpha * x + (F::from_f64(1.).unwrap() - self.alpha) * self.mean;
+        let t = self.last_t.map_or(F::from_f64(0.).unwrap(), |t| {
+            t + F::from_u64(self.missing + 1).unwrap()
+        });
+        self.update_at(x, t);
+    }
+    /// A missing reading leaves the mean untouched but takes a time unit, like the weight decay
+    /// of `EWMean::update_opt`. It is ignored before the first value.
+    fn update_opt(&mut self, x: Option<F>) {
+        match x {
+            Some(x) => self.update(x),
+            None => {
+                if self.last_t.is_some() {
+                    self.missing += 1;
+                }
+            }
         }
     }
     fn get(&self) -> F {
         self.mean
     }
+    fn reset(&mut self) {
+        self.mean = F::from_f64(0.).unwrap();
+        self.last_t = None;
+        self.missing = 0;
+    }
+}
+
+#[cfg(test)]
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> crate::stats::ApproxEq<F> for EWMean<F> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool {
+        use crate::stats::close;
+        self.adjust == other.adjust
+            && close(self.mean, other.mean, eps)
+            && close(self.alpha, other.alpha, eps)
+            && close(self.weight, other.weight, eps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn adjust_matches_pandas() {
+        use crate::ewmean::{Adjust, EWMean};
+        use crate::stats::Univariate;
+        // `pd.Series(data).ewm(alpha=0.5, adjust=...).mean()`
+        let data = [1., 3., 5., 4., 6., 8., 7., 9., 11.];
+        let adjusted = [
+            1.0,
+            2.3333333333333335,
+            3.857142857142857,
+            3.933333333333333,
+            5.0,
+            6.523809523809524,
+            6.7637795275590555,
+            7.886274509803922,
+            9.446183953033268,
+        ];
+        let unadjusted = [
+            1.0, 2.0, 3.5, 3.75, 4.875, 6.4375, 6.71875, 7.859375, 9.4296875,
+        ];
+        let mut weighted: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Weighted);
+        let mut recursive: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Recursive);
+        for ((x, a), u) in data.iter().zip(adjusted.iter()).zip(unadjusted.iter()) {
+            weighted.update(*x);
+            recursive.update(*x);
+            assert!((weighted.get() - a).abs() < 1e-12);
+            assert!((recursive.get() - u).abs() < 1e-12);
+        }
+        // Unlike `Adjust::Legacy`, a mean of exactly 0 is not mistaken for the first value.
+        let mut recursive: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Recursive);
+        for x in [1., -1., 4.] {
+            recursive.update(x);
+        }
+        assert_eq!(recursive.get(), 2.);
+    }
+
+    #[test]
+    fn missing_values_decay_the_weights() {
+        use crate::ewmean::{Adjust, EWMean};
+        use crate::stats::Univariate;
+        // `pd.Series([1, None, 3, None, None, 5]).ewm(alpha=0.5, adjust=...).mean()`
+        let data = [Some(1.), None, Some(3.), None, None, Some(5.)];
+        let adjusted = [1., 1., 2.6, 2.6, 2.6, 4.675675675675675];
+        let unadjusted = [
+            1.,
+            1.,
+            2.3333333333333335,
+            2.3333333333333335,
+            2.3333333333333335,
+            4.466666666666667,
+        ];
+        let mut weighted: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Weighted);
+        let mut recursive: EWMean<f64> = EWMean::with_adjust(0.5, Adjust::Recursive);
+        let mut legacy: EWMean<f64> = EWMean::new(0.5);
+        for ((x, a), u) in data.iter().zip(adjusted.iter()).zip(unadjusted.iter()) {
+            weighted.update_opt(*x);
+            recursive.update_opt(*x);
+            legacy.update_opt(*x);
+            assert!((weighted.get() - a).abs() < 1e-12);
+            assert!((recursive.get() - u).abs() < 1e-12);
+        }
+        // `Adjust::Legacy` simply skips the missing readings.
+        assert_eq!(legacy.get(), 3.5);
+    }
+
+    #[test]
+    fn time_ewmean_fades_when_stale() {
+        use crate::ewmean::TimeEWMean;
+        use crate::stats::Univariate;
+        let mut running_ewmean: TimeEWMean<f64> = TimeEWMean::new(5.).unwrap();
+        assert_eq!(running_ewmean.get_at(3.), 0.);
+        for (t, x) in [(0., 10.), (1., 10.), (2.5, 10.)] {
+            running_ewmean.update_at(x, t);
+        }
+        assert!((running_ewmean.get() - 10.).abs() < 1e-12);
+        assert!((running_ewmean.get_at(2.5) - 10.).abs() < 1e-12);
+        assert!(running_ewmean.get_at(100.) < 1e-7);
+        // Reading does not mutate the statistic.
+        assert!((running_ewmean.get() - 10.).abs() < 1e-12);
+        assert_eq!(running_ewmean.last_time(), Some(2.5));
+        running_ewmean.update(0.);
+        assert_eq!(running_ewmean.last_time(), Some(3.5));
+        assert!(TimeEWMean::<f64>::new(0.).is_err());
+    }
+    #[test]
+    fn time_ewmean_missing_values_take_a_time_unit() {
+        use crate::ewmean::TimeEWMean;
+        use crate::stats::Univariate;
+        let mut with_gaps: TimeEWMean<f64> = TimeEWMean::new(2.).unwrap();
+        let mut timestamped: TimeEWMean<f64> = TimeEWMean::new(2.).unwrap();
+        let data = [
+            None,
+            Some(4.),
+            None,
+            None,
+            Some(1.),
+            Some(7.),
+            None,
+            Some(3.),
+        ];
+        for (t, x) in data.iter().enumerate() {
+            with_gaps.update_opt(*x);
+            if let Some(x) = x {
+                timestamped.update_at(*x, t as f64 - 1.);
+            }
+            assert!((with_gaps.get() - timestamped.get()).abs() < 1e-12);
+            assert_eq!(with_gaps.last_time(), timestamped.last_time());
+        }
+    }
+    #[test]
+    fn revert_restores_the_previous_mean() {
+        use crate::ewmean::{Adjust, EWMean};
+        use crate::stats::{Revertable, Univariate};
+        for adjust in [Adjust::Legacy, Adjust::Recursive, Adjust::Weighted] {
+            let mut running_ewmean: EWMean<f64> = EWMean::with_adjust(0.3, adjust);
+            assert!(running_ewmean.revert(1.).is_err());
+            for x in [0.1, 7., -2.5, 3.3] {
+                running_ewmean.update(x);
+            }
+            let before = running_ewmean;
+            running_ewmean.update(1e6);
+            running_ewmean.revert(1e6).unwrap();
+            assert_eq!(running_ewmean.get(), before.get());
+            // The weight is restored too, the next update matches the untouched mean.
+            let mut untouched = before;
+            running_ewmean.update(4.);
+            untouched.update(4.);
+            assert_eq!(running_ewmean.get(), untouched.get());
+            running_ewmean.revert(4.).unwrap();
+            assert!(running_ewmean.revert(-2.5).is_err());
+            running_ewmean.update_opt(None);
+            running_ewmean.revert(0.).unwrap();
+            running_ewmean.update(4.);
+            assert_eq!(running_ewmean.get(), untouched.get());
+        }
+    }
 }
@@ -0,0 +1,113 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Exponentially weighted quantile, which forgets old values and follows distribution shifts.
+/// The estimate starts at the first value and then moves towards each new value with the
+/// stochastic gradient update of the pinball loss:
+/// * `estimate += step * q` if `x > estimate`
+/// * `estimate -= step * (1 - q)` if `x < estimate`
+///
+/// The step is `learning_rate` times an exponentially weighted mean of `|x - estimate|`, so it is
+/// expressed in the unit of the data, decays as the estimate settles and grows back after a shift.
+/// # Arguments
+/// * `q` - Quantile value, must be between 0 and 1.
+/// * `learning_rate` - In `(0, 1]`, the closer it is to 1 the faster the estimator adapts and the noisier it is.
+/// # Examples
+/// ```
+/// use watermill::ewquantile::EWQuantile;
+/// use watermill::stats::Univariate;
+/// let mut ew_median: EWQuantile<f64> = EWQuantile::new(0.5, 0.05).unwrap();
+/// for i in 0..2000{
+///     ew_median.update((i % 21) as f64);
+/// }
+/// assert!((ew_median.get() - 10.).abs() < 1.);
+/// ```
+/// # References
+/// [^1]: [Ma, Q., Muthukrishnan, S. and Sandler, M., 2013. Frugal streaming for estimating quantiles. In Space-Efficient Data Structures, Streams, and Algorithms (pp. 77-96).](https://arxiv.org/abs/1407.1121)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EWQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub q: F,
+    pub learning_rate: F,
+    estimate: F,
+    /// Exponentially weighted mean absolute deviation from the estimate.
+    scale: F,
+    initialized: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWQuantile<F> {
+    pub fn new(q: F, learning_rate: F) -> Result<Self, &'static str> {
+        if q < F::from_f64(0.).unwrap() || q > F::from_f64(1.).unwrap() {
+            return Err("q should be between 0 and 1");
+        }
+        if learning_rate <= F::from_f64(0.).unwrap() || learning_rate > F::from_f64(1.).unwrap() {
+            return Err("learning_rate should be in (0, 1]");
+        }
+        Ok(Self {
+            q,
+            learning_rate,
+            estimate: F::from_f64(0.).unwrap(),
+            scale: F::from_f64(0.).unwrap(),
+            initialized: false,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWQuantile<F> {
+    fn update(&mut self, x: F) {
+        if !self.initialized {
+            self.estimate = x;
+            self.initialized = true;
+            return;
+        }
+        let one = F::from_f64(1.).unwrap();
+        self.scale = (one - self.learning_rate) * self.scale
+            + self.learning_rate * (x - self.estimate).abs();
+        let step = self.learning_rate * self.scale;
+        if x > self.estimate {
+            self.estimate += step * self.q;
+        } else if x < self.estimate {
+            self.estimate -= step * (one - self.q);
+        }
+    }
+    fn get(&self) -> F {
+        self.estimate
+    }
+    fn reset(&mut self) {
+        self.estimate = F::from_f64(0.).unwrap();
+        self.scale = F::from_f64(0.).unwrap();
+        self.initialized = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn tracks_a_shift_of_the_median() {
+        use crate::ewquantile::EWQuantile;
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        let mut ew_median: EWQuantile<f64> = EWQuantile::new(0.5, 0.05).unwrap();
+        let mut ew_q90: EWQuantile<f64> = EWQuantile::new(0.9, 0.05).unwrap();
+        let mut median: Quantile<f64> = Quantile::new(0.5).unwrap();
+        // Uniform noise between -10 and 10, the median jumps from 0 to 100 halfway.
+        let noise = |i: usize| ((i * 7919) % 101) as f64 / 5. - 10.;
+        for i in 0..2000 {
+            ew_median.update(noise(i));
+            ew_q90.update(noise(i));
+            median.update(noise(i));
+        }
+        assert!(ew_median.get().abs() < 2.);
+        assert!((ew_q90.get() - 8.).abs() < 2.);
+        for i in 2000..2500 {
+            ew_median.update(100. + noise(i));
+            ew_q90.update(100. + noise(i));
+            median.update(100. + noise(i));
+        }
+        assert!((ew_median.get() - 100.).abs() < 2.);
+        assert!((ew_q90.get() - 108.).abs() < 2.);
+        // The P² median does not forget the values before the shift.
+        assert!(median.get() < 50.);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ewmean::EWMean;
+use crate::ewmean::{Adjust, EWMean};
 use crate::stats::Univariate;
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
@@ -7,6 +7,9 @@ use std::ops::{AddAssign, SubAssign};
 /// Exponentially weighted variance.
 /// # Arguments
 /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+/// * `adjust` - How the first values are weighted, see `Adjust`. Defaults to `Adjust::Legacy`.
+///   `Adjust::Recursive` and `Adjust::Weighted` match the biased variance of pandas, i.e.
+///   `pd.Series(data).ewm(alpha=alpha, adjust=...).var(bias=True)`.
 /// # Examples
 /// ```
 /// use watermill::ewvariance::EWVariance;
@@ -30,9 +33,12 @@ pub struct EWVariance<F: Float + FromPrimitive + AddAssign + SubAssign> {
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWVariance<F> {
     pub fn new(alpha: F) -> Self {
+        Self::with_adjust(alpha, Adjust::Legacy)
+    }
+    pub fn with_adjust(alpha: F, adjust: Adjust) -> Self {
         Self {
-            mean: EWMean::new(alpha),
-            sq_mean: EWMean::new(alpha),
+            mean: EWMean::with_adjust(alpha, adjust),
+            sq_mean: EWMean::with_adjust(alpha, adjust),
             alpha,
         }
     }
@@ -43,12 +49,7 @@ where
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
     fn default() -> Self {
-        let alpha = F::from_f64(0.5).unwrap();
-        Self {
-            mean: EWMean::new(alpha),
-            sq_mean: EWMean::new(alpha),
-            alpha,
-        }
+        Self::new(F::from_f64(0.5).unwrap())
     }
 }
 
@@ -57,7 +58,82 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWVaria
         self.mean.update(x);
         self.sq_mean.update(x.powf(F::from_i8(2).unwrap()))
     }
+    fn update_opt(&mut self, x: Option<F>) {
+        self.mean.update_opt(x);
+        self.sq_mean
+            .update_opt(x.map(|x| x.powf(F::from_i8(2).unwrap())));
+    }
     fn get(&self) -> F {
         self.sq_mean.get() - self.mean.get().powf(F::from_i8(2).unwrap())
     }
+    fn reset(&mut self) {
+        self.mean.reset();
+        self.sq_mean.reset();
+    }
+}
+#[cfg(test)]
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> crate::stats::ApproxEq<F> for EWVariance<F> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool {
+        crate::stats::close(self.alpha, other.alpha, eps)
+            && self.mean.approx_eq(&other.mean, eps)
+            && self.sq_mean.approx_eq(&other.sq_mean, eps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn adjust_matches_pandas() {
+        use crate::ewmean::Adjust;
+        use crate::ewvariance::EWVariance;
+        use crate::stats::Univariate;
+        // `pd.Series(data).ewm(alpha=0.5, adjust=...).var(bias=True)`
+        let data = [1., 3., 5., 4., 6., 8., 7., 9., 11.];
+        let adjusted = [
+            0.0,
+            0.8888888888888875,
+            2.1224489795918355,
+            0.9955555555555549,
+            1.5483870967741922,
+            3.011337868480723,
+            1.550499100998202,
+            2.0223606305267197,
+            3.433013813519409,
+        ];
+        let unadjusted = [
+            0.0,
+            1.0,
+            2.75,
+            1.4375,
+            1.984375,
+            3.43359375,
+            1.7958984375,
+            2.198974609375,
+            3.56536865234375,
+        ];
+        let mut weighted: EWVariance<f64> = EWVariance::with_adjust(0.5, Adjust::Weighted);
+        let mut recursive: EWVariance<f64> = EWVariance::with_adjust(0.5, Adjust::Recursive);
+        for ((x, a), u) in data.iter().zip(adjusted.iter()).zip(unadjusted.iter()) {
+            weighted.update(*x);
+            recursive.update(*x);
+            assert!((weighted.get() - a).abs() < 1e-9);
+            assert!((recursive.get() - u).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        use crate::ewmean::Adjust;
+        use crate::ewvariance::EWVariance;
+        use crate::stats::{ApproxEq, Univariate};
+        let mut running_ewvariance: EWVariance<f64> =
+            EWVariance::with_adjust(0.3, Adjust::Weighted);
+        for i in 1..=10 {
+            running_ewvariance.update(1. / i as f64);
+        }
+        let serialized = serde_json::to_string(&running_ewvariance).unwrap();
+        let deserialized: EWVariance<f64> = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.approx_eq(&running_ewvariance, 1e-12));
+        assert!(!deserialized.approx_eq(&EWVariance::with_adjust(0.3, Adjust::Weighted), 1e-12));
+    }
 }
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use crate::ewmean::EWMean;
+use crate::ewquantile::EWQuantile;
+use crate::ewvariance::EWVariance;
+use crate::iqr::IQR;
+use crate::kurtosis::Kurtosis;
+use crate::maximum::{Max, RollingMax};
+use crate::mean::{Mean, RollingMean};
+use crate::minimum::{Min, RollingMin};
+use crate::ptp::PeakToPeak;
+use crate::quantile::{Quantile, RollingQuantile};
+use crate::skew::Skew;
+use crate::stats::Univariate;
+use crate::sum::Sum;
+use crate::variance::{RollingVariance, Variance};
+
+/// Builds a boxed statistic from its parameters.
+pub type Constructor = Box<dyn Fn(&[f64]) -> Box<dyn Univariate<f64>>>;
+
+/// Registry of named constructors, to build statistics from a configuration.
+/// `new` registers the built-in statistics below, a missing trailing parameter takes its default
+/// value and boolean parameters are true when they are not 0. `build` returns an error instead of
+/// a statistic when a parameter is missing or out of range.
+///
+/// | Name               | Parameters                           |
+/// |--------------------|--------------------------------------|
+/// | `mean`             |                                      |
+/// | `sum`              |                                      |
+/// | `min`              |                                      |
+/// | `max`              |                                      |
+/// | `ptp`              |                                      |
+/// | `variance`         | `ddof = 1`                           |
+/// | `kurtosis`         | `bias = 0`                           |
+/// | `skew`             | `bias = 0`                           |
+/// | `quantile`         | `q = 0.5`                            |
+/// | `iqr`              | `q_inf = 0.25`, `q_sup = 0.75`       |
+/// | `ewmean`           | `alpha = 0.5`                        |
+/// | `ewvariance`       | `alpha = 0.5`                        |
+/// | `ewquantile`       | `q = 0.5`, `learning_rate = 0.05`    |
+/// | `rolling_mean`     | `window_size`                        |
+/// | `rolling_variance` | `window_size`, `ddof = 1`            |
+/// | `rolling_min`      | `window_size`                        |
+/// | `rolling_max`      | `window_size`                        |
+/// | `rolling_quantile` | `q`, `window_size`                   |
+/// # Examples
+/// ```
+/// use watermill::factory::StatFactory;
+/// let factory = StatFactory::new();
+/// let mut rolling_mean = factory.build("rolling_mean", &[2.]).unwrap();
+/// for x in [1., 2., 3.].iter(){
+///     rolling_mean.update(*x);
+/// }
+/// assert_eq!(rolling_mean.get(), 2.5);
+/// assert!(factory.build("unknown", &[]).is_err());
+/// assert!(factory.build("rolling_mean", &[]).is_err());
+/// ```
+pub struct StatFactory {
+    constructors: HashMap<String, Builder>,
+}
+
+/// Fallible constructor, the built-in statistics check their parameters instead of panicking.
+type Builder = Box<dyn Fn(&[f64]) -> Result<Box<dyn Univariate<f64>>, &'static str>>;
+
+fn param(params: &[f64], i: usize, default: f64) -> f64 {
+    params.get(i).copied().unwrap_or(default)
+}
+
+fn required(params: &[f64], i: usize) -> Result<f64, &'static str> {
+    params
+        .get(i)
+        .copied()
+        .ok_or("a required parameter is missing")
+}
+
+fn integer(value: f64) -> Result<u32, &'static str> {
+    if value < 0. || value.fract() != 0. || value > u32::MAX as f64 {
+        return Err("parameter should be a non-negative integer");
+    }
+    Ok(value as u32)
+}
+
+fn window_size(value: f64) -> Result<usize, &'static str> {
+    match integer(value)? {
+        0 => Err("Window size should not equals to 0"),
+        window_size => Ok(window_size as usize),
+    }
+}
+
+fn probability(value: f64) -> Result<f64, &'static str> {
+    if !(0. ..=1.).contains(&value) {
+        return Err("parameter should be between 0 and 1");
+    }
+    Ok(value)
+}
+
+impl StatFactory {
+    /// Registry with the built-in statistics.
+    pub fn new() -> Self {
+        let mut factory = Self::empty();
+        factory.register("mean", Box::new(|_| Box::new(Mean::new())));
+        factory.register("sum", Box::new(|_| Box::new(Sum::new())));
+        factory.register("min", Box::new(|_| Box::new(Min::new())));
+        factory.register("max", Box::new(|_| Box::new(Max::new())));
+        factory.register("ptp", Box::new(|_| Box::new(PeakToPeak::new())));
+        factory.register_builtin(
+            "variance",
+            Box::new(|p| Ok(Box::new(Variance::new(integer(param(p, 0, 1.))?)))),
+        );
+        factory.register(
+            "kurtosis",
+            Box::new(|p| Box::new(Kurtosis::new(param(p, 0, 0.) != 0.))),
+        );
+        factory.register(
+            "skew",
+            Box::new(|p| Box::new(Skew::new(param(p, 0, 0.) != 0.))),
+        );
+        factory.register_builtin(
+            "quantile",
+            Box::new(|p| Ok(Box::new(Quantile::new(probability(param(p, 0, 0.5))?)?))),
+        );
+        factory.register_builtin(
+            "iqr",
+            Box::new(|p| {
+                let q_inf = probability(param(p, 0, 0.25))?;
+                let q_sup = probability(param(p, 1, 0.75))?;
+                Ok(Box::new(IQR::new(q_inf, q_sup)?))
+            }),
+        );
+        factory.register_builtin(
+            "ewmean",
+            Box::new(|p| Ok(Box::new(EWMean::new(probability(param(p, 0, 0.5))?)))),
+        );
+        factory.register_builtin(
+            "ewvariance",
+            Box::new(|p| Ok(Box::new(EWVariance::new(probability(param(p, 0, 0.5))?)))),
+        );
+        factory.register_builtin(
+            "ewquantile",
+            Box::new(|p| {
+                let q = probability(param(p, 0, 0.5))?;
+                Ok(Box::new(EWQuantile::new(q, param(p, 1, 0.05))?))
+            }),
+        );
+        factory.register_builtin(
+            "rolling_mean",
+            Box::new(|p| {
+                let window_size = window_size(required(p, 0)?)?;
+                Ok(Box::new(RollingMean::new(Mean::new(), window_size)?))
+            }),
+        );
+        factory.register_builtin(
+            "rolling_variance",
+            Box::new(|p| {
+                let window_size = window_size(required(p, 0)?)?;
+                let variance = Variance::new(integer(param(p, 1, 1.))?);
+                Ok(Box::new(RollingVariance::new(variance, window_size)?))
+            }),
+        );
+        factory.register_builtin(
+            "rolling_min",
+            Box::new(|p| Ok(Box::new(RollingMin::new(window_size(required(p, 0)?)?)))),
+        );
+        factory.register_builtin(
+            "rolling_max",
+            Box::new(|p| Ok(Box::new(RollingMax::new(window_size(required(p, 0)?)?)))),
+        );
+        factory.register_builtin(
+            "rolling_quantile",
+            Box::new(|p| {
+                let q = probability(required(p, 0)?)?;
+                let window_size = window_size(required(p, 1)?)?;
+                Ok(Box::new(RollingQuantile::new(q, window_size)?))
+            }),
+        );
+        factory
+    }
+    /// Registry without any statistic.
+    pub fn empty() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+    /// Registers `constructor` under `name`, replacing any statistic registered with that name.
+    pub fn register(&mut self, name: &str, constructor: Constructor) {
+        self.register_builtin(name, Box::new(move |params| Ok(constructor(params))));
+    }
+    fn register_builtin(&mut self, name: &str, builder: Builder) {
+        self.constructors.insert(name.to_string(), builder);
+    }
+    /// Builds the statistic registered under `name`, returns an error if there is none or if a
+    /// built-in statistic rejects its parameters: a missing required parameter, a quantile outside
+    /// of `[0, 1]`, a negative or fractional `ddof` or `window_size`.
+    pub fn build(
+        &self,
+        name: &str,
+        params: &[f64],
+    ) -> Result<Box<dyn Univariate<f64>>, &'static str> {
+        match self.constructors.get(name) {
+            Some(builder) => builder(params),
+            None => Err("no statistic is registered under this name"),
+        }
+    }
+    /// Returns `true` if a statistic is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+    /// Names of the registered statistics, in arbitrary order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.constructors.keys().map(|name| name.as_str())
+    }
+}
+
+impl Default for StatFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn build_a_custom_statistic_by_name() {
+        use crate::factory::StatFactory;
+        use crate::mean::Mean;
+        use crate::stats::Univariate;
+        use crate::warmup::WarmUp;
+        let mut factory = StatFactory::new();
+        factory.register(
+            "warm_mean",
+            Box::new(|p| Box::new(WarmUp::new(Mean::<f64>::new(), p[0] as usize))),
+        );
+        assert!(factory.contains("warm_mean"));
+        let mut stats: Vec<Box<dyn Univariate<f64>>> = ["warm_mean", "variance", "max"]
+            .iter()
+            .map(|name| factory.build(name, &[3.]).unwrap())
+            .collect();
+        for x in [1., 5., 3.] {
+            assert!(stats[0].get().is_nan());
+            for stat in stats.iter_mut() {
+                stat.update(x);
+            }
+        }
+        assert_eq!(stats[0].get(), 3.);
+        // `ddof = 3` leaves the variance at 0 until a fourth value.
+        assert_eq!(stats[1].get(), 0.);
+        assert_eq!(stats[2].get(), 5.);
+        assert!(StatFactory::empty().build("mean", &[]).is_err());
+        assert!(factory.names().count() > 1);
+    }
+    #[test]
+    fn invalid_parameters_are_errors() {
+        use crate::factory::StatFactory;
+        let factory = StatFactory::new();
+        // Missing required parameters.
+        assert!(factory.build("rolling_mean", &[]).is_err());
+        assert!(factory.build("rolling_quantile", &[0.5]).is_err());
+        // Out of range parameters.
+        assert!(factory.build("quantile", &[1.5]).is_err());
+        assert!(factory.build("iqr", &[0.75, 0.25]).is_err());
+        assert!(factory.build("ewmean", &[-0.1]).is_err());
+        assert!(factory.build("variance", &[-1.]).is_err());
+        assert!(factory.build("variance", &[0.5]).is_err());
+        assert!(factory.build("rolling_min", &[0.]).is_err());
+        assert!(factory.build("rolling_max", &[2.5]).is_err());
+        assert!(factory.build("rolling_variance", &[3., -2.]).is_err());
+        assert!(factory.build("rolling_quantile", &[0.5, 3.]).is_ok());
+    }
+}
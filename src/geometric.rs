@@ -0,0 +1,101 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running geometric standard deviation, `exp(std(ln(x)))`.
+/// The logarithm is only defined for positive values, so zeros and negative values are skipped.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom of the variance of the logarithms. Defaults to `1` like `scipy.stats.gstd`.
+/// # Examples
+/// ```
+/// use watermill::geometric::GeometricStdDev;
+/// use watermill::stats::Univariate;
+/// let mut running_gstd: GeometricStdDev<f64> = GeometricStdDev::default();
+/// for x in [1., 2., 0., 4., -3., 8.].iter(){
+///     running_gstd.update(*x);
+/// }
+/// // scipy.stats.gstd([1, 2, 4, 8])
+/// assert!((running_gstd.get() - 2.4469666667017522).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Geometric standard deviation](https://en.wikipedia.org/wiki/Geometric_standard_deviation)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GeometricStdDev<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Variance of the logarithms of the positive values.
+    pub variance: Variance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> GeometricStdDev<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+        }
+    }
+    /// Geometric standard deviation with `ddof = 0`, the variance of the logarithms is divided by `n`.
+    pub fn population() -> Self {
+        Self::new(0)
+    }
+    /// Geometric standard deviation with `ddof = 1`, the variance of the logarithms is divided by
+    /// `n - 1`. Same as `GeometricStdDev::default`.
+    pub fn sample() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F> Default for GeometricStdDev<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for GeometricStdDev<F> {
+    fn update(&mut self, x: F) {
+        if x > F::from_f64(0.).unwrap() {
+            self.variance.update(x.ln());
+        }
+    }
+    fn get(&self) -> F {
+        self.variance.get().sqrt().exp()
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for GeometricStdDev<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        if x > F::from_f64(0.).unwrap() {
+            self.variance.revert(x.ln())?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F>
+    for GeometricStdDev<F>
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn rolling_gstd_skips_non_positive_values() {
+        use crate::geometric::GeometricStdDev;
+        use crate::rolling::windowed;
+        let data: Vec<f64> = vec![5., 2., 8., 4., 16., 1.];
+        let rolled = windowed(&data, 5, GeometricStdDev::default());
+        // scipy.stats.gstd([2, 8, 4, 16, 1])
+        assert!((rolled[1] - 2.992059427797328).abs() < 1e-12);
+        let rolled = windowed(&[0_f64, 2., -1., 8.], 3, GeometricStdDev::default());
+        assert_eq!(rolled[0], 1.);
+        assert!((rolled[1] - (2_f64.sqrt() * 2_f64.ln()).exp()).abs() < 1e-12);
+    }
+}
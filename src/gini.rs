@@ -0,0 +1,258 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::counter::{Counter, RollingCounter};
+use crate::sorted_window::SortedWindow;
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+
+fn impurity<F: Float + FromPrimitive + AddAssign + SubAssign>(counter: &Counter<F>) -> F {
+    if counter.is_empty() {
+        return F::from_f64(0.).unwrap();
+    }
+    let total = F::from_usize(counter.total()).unwrap();
+    let mut sum_sq = F::from_f64(0.).unwrap();
+    for (_, count) in counter.iter() {
+        let p = F::from_usize(count).unwrap() / total;
+        sum_sq += p * p;
+    }
+    F::from_f64(1.).unwrap() - sum_sq
+}
+
+/// Running Gini impurity of a categorical stream, `1 - Σ p_i²` where `p_i` is the share of the
+/// `i`-th distinct value. It is 0 for a pure stream and `1 - 1/k` for `k` equally frequent values.
+/// # Examples
+/// ```
+/// use watermill::gini::Gini;
+/// use watermill::stats::{Revertable, Univariate};
+/// let mut running_gini: Gini<f64> = Gini::new();
+/// for x in [0., 1., 1., 0.].iter(){
+///     running_gini.update(*x);
+/// }
+/// assert_eq!(running_gini.get(), 0.5);
+/// running_gini.revert(1.).unwrap();
+/// assert!((running_gini.get() - 4. / 9.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Gini impurity](https://en.wikipedia.org/wiki/Decision_tree_learning#Gini_impurity)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Gini<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counter: Counter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Gini<F> {
+    pub fn new() -> Self {
+        Self {
+            counter: Counter::new(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Gini<F> {
+    fn update(&mut self, x: F) {
+        self.counter.add(x);
+    }
+    fn get(&self) -> F {
+        impurity(&self.counter)
+    }
+    fn reset(&mut self) {
+        self.counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.counter.total())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Gini<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.counter.remove(x)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Gini<F> {}
+
+/// Rolling Gini impurity.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::gini::RollingGini;
+/// use watermill::stats::Univariate;
+/// let mut rolling_gini: RollingGini<f64> = RollingGini::new(2).unwrap();
+/// for x in [1., 2., 2.].iter(){
+///     rolling_gini.update(*x);
+/// }
+/// assert_eq!(rolling_gini.get(), 0.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingGini<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    rolling_counter: RollingCounter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingGini<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            rolling_counter: RollingCounter::new(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.rolling_counter.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.rolling_counter.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.rolling_counter.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingGini<F> {
+    fn update(&mut self, x: F) {
+        self.rolling_counter.push_back(x);
+    }
+    fn get(&self) -> F {
+        impurity(&self.rolling_counter.counter)
+    }
+    fn reset(&mut self) {
+        self.rolling_counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.rolling_counter.len())
+    }
+}
+
+/// Rolling Gini coefficient, the inequality of the values of the window, not to be confused with
+/// the Gini impurity. With the `n` values of the window sorted in ascending order,
+/// `G = 2 Σ i x_i / (n Σ x_i) - (n + 1) / n`, `i` starting at 1. It is 0 when all the values are
+/// equal and `(n - 1) / n` when a single value is positive. The values should not be negative,
+/// `get` returns 0 for an empty window or a window summing to 0.
+/// Each call to `get` costs `O(window_size)`.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::gini::RollingGiniCoefficient;
+/// use watermill::stats::Univariate;
+/// let mut rolling_gini: RollingGiniCoefficient<f64> = RollingGiniCoefficient::new(3).unwrap();
+/// for x in [5., 1., 2., 3.].iter(){
+///     rolling_gini.update(*x);
+/// }
+/// assert!((rolling_gini.get() - 2. / 9.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Gini coefficient](https://en.wikipedia.org/wiki/Gini_coefficient)
+#[derive(Serialize, Deserialize)]
+pub struct RollingGiniCoefficient<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingGiniCoefficient<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingGiniCoefficient<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        let mut sum = F::from_f64(0.).unwrap();
+        let mut weighted_sum = F::from_f64(0.).unwrap();
+        for (i, x) in self.sorted_window.sorted_window.iter().enumerate() {
+            sum += x.into_inner();
+            weighted_sum += F::from_usize(i + 1).unwrap() * x.into_inner();
+        }
+        if sum == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        let n = F::from_usize(self.sorted_window.len()).unwrap();
+        F::from_f64(2.).unwrap() * weighted_sum / (n * sum) - (n + F::from_f64(1.).unwrap()) / n
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn pure_and_uniform_streams() {
+        use crate::gini::{Gini, RollingGini};
+        use crate::stats::Univariate;
+        let mut running_gini: Gini<f64> = Gini::new();
+        assert_eq!(running_gini.get(), 0.);
+        for _ in 0..10 {
+            running_gini.update(3.);
+        }
+        assert_eq!(running_gini.get(), 0.);
+        assert!(RollingGini::<f64>::new(0).is_err());
+        for k in [2, 3, 7] {
+            let mut running_gini: Gini<f64> = Gini::new();
+            let mut rolling_gini: RollingGini<f64> = RollingGini::new(k).unwrap();
+            for i in 0..10 * k {
+                running_gini.update((i % k) as f64);
+                rolling_gini.update((i % k) as f64);
+            }
+            let expected = 1. - 1. / k as f64;
+            assert!((running_gini.get() - expected).abs() < 1e-12);
+            assert!((rolling_gini.get() - expected).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn gini_coefficient_of_equal_and_unequal_windows() {
+        use crate::gini::RollingGiniCoefficient;
+        use crate::stats::Univariate;
+        assert!(RollingGiniCoefficient::<f64>::new(0).is_err());
+        let mut rolling_gini: RollingGiniCoefficient<f64> = RollingGiniCoefficient::new(5).unwrap();
+        assert_eq!(rolling_gini.get(), 0.);
+        for _ in 0..8 {
+            rolling_gini.update(4.);
+        }
+        assert_eq!(rolling_gini.get(), 0.);
+        // A single positive value among five is the maximal inequality.
+        for x in [0., 0., 0., 0., 9.] {
+            rolling_gini.update(x);
+        }
+        assert!((rolling_gini.get() - 0.8).abs() < 1e-12);
+        // Brute force `Σ_i Σ_j |x_i - x_j| / (2 n² mean)` on the window 0, 0, 9, 1, 3.
+        for x in [1., 3.] {
+            rolling_gini.update(x);
+        }
+        let window = [0., 0., 9., 1., 3.];
+        let mean = window.iter().sum::<f64>() / 5.;
+        let mut total = 0.;
+        for a in window.iter() {
+            for b in window.iter() {
+                total += (a - b).abs();
+            }
+        }
+        assert!((rolling_gini.get() - total / (2. * 25. * mean)).abs() < 1e-12);
+    }
+}
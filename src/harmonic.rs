@@ -0,0 +1,123 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Bivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Weighted harmonic mean of `a` and `b`, `(1 + beta²) * a * b / (beta² * a + b)`, i.e. the
+/// F-beta score when `a` is the precision and `b` the recall. `beta = 1` gives the plain harmonic
+/// mean, a larger `beta` gives more weight to `b`. The result is 0 when `a` or `b` is 0.
+/// # Examples
+/// ```
+/// use watermill::harmonic::harmonic_combine;
+/// assert!((harmonic_combine(0.5_f64, 1., 1.) - 2. / 3.).abs() < 1e-12);
+/// assert_eq!(harmonic_combine(0_f64, 1., 1.), 0.);
+/// ```
+pub fn harmonic_combine<F: Float>(a: F, b: F, beta: F) -> F {
+    if a == F::zero() || b == F::zero() {
+        return F::zero();
+    }
+    let beta2 = beta * beta;
+    (F::one() + beta2) * a * b / (beta2 * a + b)
+}
+
+/// Streaming weighted harmonic mean of two statistics.
+/// `update(x, y)` feeds `x` to the first statistic and `y` to the second one, `get` combines their
+/// current values with `harmonic_combine`.
+/// # Arguments
+/// * `a` - First statistic, e.g. a precision rate.
+/// * `b` - Second statistic, e.g. a recall rate.
+/// * `beta` - Weight of `b` relative to `a`, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::harmonic::HarmonicCombine;
+/// use watermill::mean::Mean;
+/// use watermill::stats::Bivariate;
+/// let mut f1: HarmonicCombine<f64, Mean<f64>, Mean<f64>> =
+///     HarmonicCombine::new(Mean::new(), Mean::new(), 1.).unwrap();
+/// // Hits of the precision and recall streams, precision is 0.5 and recall is 1.
+/// for (precision_hit, recall_hit) in [(1., 1.), (0., 1.), (1., 1.), (0., 1.)].iter(){
+///     f1.update(*precision_hit, *recall_hit);
+/// }
+/// assert!((f1.get() - 2. / 3.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the F-score](https://en.wikipedia.org/wiki/F-score)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct HarmonicCombine<F, A, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    A: Univariate<F>,
+    B: Univariate<F>,
+{
+    pub a: A,
+    pub b: B,
+    pub beta: F,
+    n: usize,
+}
+
+impl<F, A, B> HarmonicCombine<F, A, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    A: Univariate<F>,
+    B: Univariate<F>,
+{
+    pub fn new(a: A, b: B, beta: F) -> Result<Self, &'static str> {
+        if beta <= F::from_f64(0.).unwrap() {
+            return Err("beta should be greater than 0");
+        }
+        Ok(Self { a, b, beta, n: 0 })
+    }
+}
+
+impl<F, A, B> Bivariate<F> for HarmonicCombine<F, A, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    A: Univariate<F>,
+    B: Univariate<F>,
+{
+    fn update(&mut self, x: F, y: F) {
+        self.a.update(x);
+        self.b.update(y);
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        harmonic_combine(self.a.get(), self.b.get(), self.beta)
+    }
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn f_beta_weights_the_second_statistic() {
+        use crate::ewmean::EWMean;
+        use crate::harmonic::{harmonic_combine, HarmonicCombine};
+        use crate::mean::Mean;
+        use crate::stats::Bivariate;
+        let (precision, recall): (f64, f64) = (0.8, 0.4);
+        let f2 = harmonic_combine(precision, recall, 2.);
+        assert!((f2 - 5. * 0.32 / (4. * 0.8 + 0.4)).abs() < 1e-12);
+        // Weighting recall more pulls the score towards it.
+        assert!(f2 < harmonic_combine(precision, recall, 1.));
+        let mut f2_stream: HarmonicCombine<f64, Mean<f64>, EWMean<f64>> =
+            HarmonicCombine::new(Mean::new(), EWMean::new(0.5), 2.).unwrap();
+        for _ in 0..10 {
+            f2_stream.update(precision, recall);
+        }
+        assert!((f2_stream.get() - f2).abs() < 1e-12);
+        assert_eq!(f2_stream.n_samples(), Some(10));
+        f2_stream.reset();
+        assert_eq!(f2_stream.get(), 0.);
+        assert!(
+            HarmonicCombine::<f64, Mean<f64>, Mean<f64>>::new(Mean::new(), Mean::new(), 0.)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Running histogram with fixed, equally wide bins.
+/// Memory is bounded by the number of bins and any quantile can be queried. Values are assumed to
+/// be uniformly spread inside their bin, so the error of `quantile` is bounded by the bin width.
+/// Values outside of `[lower, upper)` are counted in the first or the last bin.
+/// `get` returns the estimated median.
+/// # Arguments
+/// * `lower` - Lower edge of the first bin.
+/// * `upper` - Upper edge of the last bin.
+/// * `n_bins` - Number of bins.
+/// # Examples
+/// ```
+/// use watermill::histogram::Histogram;
+/// use watermill::stats::Univariate;
+/// let mut histogram: Histogram<f64> = Histogram::new(0., 10., 10).unwrap();
+/// for i in 0..100{
+///     histogram.update(i as f64 / 10.);
+/// }
+/// assert_eq!(histogram.get(), 5.);
+/// assert_eq!(histogram.quantile(0.25), 2.5);
+/// assert_eq!(histogram.cdf(7.5), 0.75);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Histogram<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    lower: F,
+    upper: F,
+    counts: Vec<usize>,
+    total: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Histogram<F> {
+    pub fn new(lower: F, upper: F, n_bins: usize) -> Result<Self, &'static str> {
+        if n_bins == 0 {
+            return Err("n_bins should be greater than 0");
+        }
+        if lower >= upper {
+            return Err("lower must be strictly less than upper");
+        }
+        Ok(Self {
+            lower,
+            upper,
+            counts: vec![0; n_bins],
+            total: 0,
+        })
+    }
+    /// Width of a bin.
+    pub fn bin_width(&self) -> F {
+        (self.upper - self.lower) / F::from_usize(self.counts.len()).unwrap()
+    }
+    /// Number of values in each bin.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+    /// Number of values seen.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+    fn bin(&self, x: F) -> usize {
+        let index = ((x - self.lower) / self.bin_width())
+            .floor()
+            .max(F::from_f64(0.).unwrap());
+        index
+            .to_usize()
+            .unwrap_or(usize::MAX)
+            .min(self.counts.len() - 1)
+    }
+    /// Estimated `q` quantile, `NaN` if the histogram is empty.
+    pub fn quantile(&self, q: F) -> F {
+        if self.total == 0 {
+            return F::nan();
+        }
+        let target = q
+            .max(F::from_f64(0.).unwrap())
+            .min(F::from_f64(1.).unwrap())
+            * F::from_usize(self.total).unwrap();
+        let mut cumulative = F::from_f64(0.).unwrap();
+        for (i, count) in self.counts.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+            let count = F::from_usize(*count).unwrap();
+            if cumulative + count >= target {
+                let left_edge = self.lower + F::from_usize(i).unwrap() * self.bin_width();
+                return left_edge + (target - cumulative) / count * self.bin_width();
+            }
+            cumulative += count;
+        }
+        self.upper
+    }
+    /// Estimated fraction of the values lower than or equal to `x`, `NaN` if the histogram is empty.
+    pub fn cdf(&self, x: F) -> F {
+        if self.total == 0 {
+            return F::nan();
+        }
+        if x < self.lower {
+            return F::from_f64(0.).unwrap();
+        }
+        if x >= self.upper {
+            return F::from_f64(1.).unwrap();
+        }
+        let bin = self.bin(x);
+        let below: usize = self.counts[..bin].iter().sum();
+        let left_edge = self.lower + F::from_usize(bin).unwrap() * self.bin_width();
+        let inside = (x - left_edge) / self.bin_width() * F::from_usize(self.counts[bin]).unwrap();
+        (F::from_usize(below).unwrap() + inside) / F::from_usize(self.total).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Histogram<F> {
+    fn update(&mut self, x: F) {
+        let bin = self.bin(x);
+        self.counts[bin] += 1;
+        self.total += 1;
+    }
+    fn get(&self) -> F {
+        self.quantile(F::from_f64(0.5).unwrap())
+    }
+    fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.total = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.total)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Histogram<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        let bin = self.bin(x);
+        if self.counts[bin] == 0 {
+            return Err("The value is not in the histogram");
+        }
+        self.counts[bin] -= 1;
+        self.total -= 1;
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Histogram<F> {}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn error_is_bounded_by_bin_width() {
+        use crate::histogram::Histogram;
+        use crate::stats::{uniform, Univariate};
+        let mut uniform = uniform(3);
+        let mut histogram: Histogram<f64> = Histogram::new(0., 1., 50).unwrap();
+        let mut data: Vec<f64> = (0..20_000).map(|_| uniform()).collect();
+        for x in data.iter() {
+            histogram.update(*x);
+        }
+        data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for q in [0.01, 0.1, 0.25, 0.5, 0.9, 0.99] {
+            let exact = data[(q * (data.len() - 1) as f64) as usize];
+            assert!((histogram.quantile(q) - exact).abs() <= histogram.bin_width());
+        }
+        for x in [0.05, 0.333, 0.5, 0.875] {
+            let exact = data.iter().filter(|v| **v <= x).count() as f64 / data.len() as f64;
+            assert!((histogram.cdf(x) - exact).abs() <= 0.01);
+        }
+        assert_eq!(histogram.cdf(-1.), 0.);
+        assert_eq!(histogram.cdf(1.), 1.);
+    }
+}
@@ -0,0 +1,246 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+fn check_smoothing<F: Float + FromPrimitive>(value: F) -> Result<(), &'static str> {
+    if value <= F::from_f64(0.).unwrap() || value > F::from_f64(1.).unwrap() {
+        return Err("Smoothing parameters should be in (0, 1]");
+    }
+    Ok(())
+}
+
+/// Double exponential smoothing, also known as Holt's linear trend method.
+/// The level is initialised with the first value and the trend with the difference between the
+/// first two values, the smoothing equations are applied from the third value onwards:
+/// * `level = alpha * x + (1 - alpha) * (level + trend)`
+/// * `trend = beta * (level - previous_level) + (1 - beta) * trend`
+///
+/// `get` returns the one-step ahead forecast `level + trend`.
+/// # Arguments
+/// * `alpha` - Smoothing of the level, in `(0, 1]`.
+/// * `beta` - Smoothing of the trend, in `(0, 1]`.
+/// # Examples
+/// ```
+/// use watermill::holt::HoltSmoother;
+/// use watermill::stats::Univariate;
+/// let mut holt: HoltSmoother<f64> = HoltSmoother::new(0.5, 0.3).unwrap();
+/// for t in 0..20{
+///     holt.update(2. * t as f64 + 1.);
+/// }
+/// assert!((holt.get() - 41.).abs() < 1e-9);
+/// assert!((holt.forecast(5) - 49.).abs() < 1e-9);
+/// ```
+/// # References
+/// [^1]: [Hyndman, R.J., & Athanasopoulos, G. (2021) Forecasting: principles and practice, 3rd edition, Trend methods](https://otexts.com/fpp3/holt.html)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct HoltSmoother<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub alpha: F,
+    pub beta: F,
+    level: F,
+    trend: F,
+    n: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> HoltSmoother<F> {
+    pub fn new(alpha: F, beta: F) -> Result<Self, &'static str> {
+        check_smoothing(alpha)?;
+        check_smoothing(beta)?;
+        Ok(Self {
+            alpha,
+            beta,
+            level: F::from_f64(0.).unwrap(),
+            trend: F::from_f64(0.).unwrap(),
+            n: 0,
+        })
+    }
+    /// Current level.
+    pub fn level(&self) -> F {
+        self.level
+    }
+    /// Current trend, i.e. the expected change per step.
+    pub fn trend(&self) -> F {
+        self.trend
+    }
+    /// Forecast `h` steps ahead, `level + h * trend`.
+    pub fn forecast(&self, h: usize) -> F {
+        self.level + F::from_usize(h).unwrap() * self.trend
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for HoltSmoother<F> {
+    fn update(&mut self, x: F) {
+        let one = F::from_f64(1.).unwrap();
+        match self.n {
+            0 => self.level = x,
+            1 => {
+                self.trend = x - self.level;
+                self.level = x;
+            }
+            _ => {
+                let previous_level = self.level;
+                self.level = self.alpha * x + (one - self.alpha) * (self.level + self.trend);
+                self.trend =
+                    self.beta * (self.level - previous_level) + (one - self.beta) * self.trend;
+            }
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.forecast(1)
+    }
+    fn reset(&mut self) {
+        self.level = F::from_f64(0.).unwrap();
+        self.trend = F::from_f64(0.).unwrap();
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+/// Triple exponential smoothing with additive seasonality, also known as the Holt-Winters method.
+/// The first `season_length` values initialise the model: the level is their mean, the trend is 0
+/// and the seasonal indices are the deviations from the mean. Then for the `t`-th value:
+/// * `level = alpha * (x - season[t % m]) + (1 - alpha) * (level + trend)`
+/// * `trend = beta * (level - previous_level) + (1 - beta) * trend`
+/// * `season[t % m] = gamma * (x - level) + (1 - gamma) * season[t % m]`
+///
+/// `get` returns the one-step ahead forecast, forecasts are `NaN` until the first season is
+/// complete. Only additive seasonality is supported for now, multiplicative seasonality, for
+/// series whose seasonal swings grow with the level, is left for later.
+/// # Arguments
+/// * `alpha` - Smoothing of the level, in `(0, 1]`.
+/// * `beta` - Smoothing of the trend, in `(0, 1]`.
+/// * `gamma` - Smoothing of the seasonal indices, in `(0, 1]`.
+/// * `season_length` - Number of values in a season, e.g. `7` for daily values with a weekly pattern.
+/// # Examples
+/// ```
+/// use watermill::holt::HoltWinters;
+/// use watermill::stats::Univariate;
+/// let mut holt_winters: HoltWinters<f64> = HoltWinters::new(0.5, 0.1, 0.5, 3).unwrap();
+/// for t in 0..30{
+///     holt_winters.update([5., 1., 3.][t % 3]);
+/// }
+/// assert!((holt_winters.get() - 5.).abs() < 1e-9);
+/// assert!((holt_winters.forecast(2) - 1.).abs() < 1e-9);
+/// ```
+/// # References
+/// [^1]: [Hyndman, R.J., & Athanasopoulos, G. (2021) Forecasting: principles and practice, 3rd edition, Holt-Winters’ additive method](https://otexts.com/fpp3/holt-winters.html)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HoltWinters<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub alpha: F,
+    pub beta: F,
+    pub gamma: F,
+    level: F,
+    trend: F,
+    season: Vec<F>,
+    season_length: usize,
+    n: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> HoltWinters<F> {
+    pub fn new(alpha: F, beta: F, gamma: F, season_length: usize) -> Result<Self, &'static str> {
+        check_smoothing(alpha)?;
+        check_smoothing(beta)?;
+        check_smoothing(gamma)?;
+        if season_length == 0 {
+            return Err("season_length should be greater than 0");
+        }
+        Ok(Self {
+            alpha,
+            beta,
+            gamma,
+            level: F::from_f64(0.).unwrap(),
+            trend: F::from_f64(0.).unwrap(),
+            season: Vec::with_capacity(season_length),
+            season_length,
+            n: 0,
+        })
+    }
+    /// Current level.
+    pub fn level(&self) -> F {
+        self.level
+    }
+    /// Current trend, i.e. the expected change per step.
+    pub fn trend(&self) -> F {
+        self.trend
+    }
+    /// Current seasonal indices, the `i`-th one applies to the values `t` with `t % m == i`.
+    pub fn season(&self) -> &[F] {
+        &self.season
+    }
+    /// Forecast `h` steps ahead, `level + h * trend` plus the seasonal index of the forecast step.
+    pub fn forecast(&self, h: usize) -> F {
+        if self.n < self.season_length {
+            return F::nan();
+        }
+        self.level
+            + F::from_usize(h).unwrap() * self.trend
+            + self.season[(self.n + h - 1) % self.season_length]
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for HoltWinters<F> {
+    fn update(&mut self, x: F) {
+        let one = F::from_f64(1.).unwrap();
+        if self.n < self.season_length {
+            self.season.push(x);
+            if self.season.len() == self.season_length {
+                let mean = self
+                    .season
+                    .iter()
+                    .fold(F::from_f64(0.).unwrap(), |acc, v| acc + *v)
+                    / F::from_usize(self.season_length).unwrap();
+                self.level = mean;
+                for v in self.season.iter_mut() {
+                    *v -= mean;
+                }
+            }
+        } else {
+            let i = self.n % self.season_length;
+            let previous_level = self.level;
+            self.level =
+                self.alpha * (x - self.season[i]) + (one - self.alpha) * (self.level + self.trend);
+            self.trend = self.beta * (self.level - previous_level) + (one - self.beta) * self.trend;
+            self.season[i] = self.gamma * (x - self.level) + (one - self.gamma) * self.season[i];
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.forecast(1)
+    }
+    fn reset(&mut self) {
+        self.level = F::from_f64(0.).unwrap();
+        self.trend = F::from_f64(0.).unwrap();
+        self.season.clear();
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn holt_winters_follows_the_seasonal_pattern() {
+        use crate::holt::HoltWinters;
+        use crate::stats::Univariate;
+        let pattern = [3., -1., -4., 2.];
+        let truth = |t: usize| 10. + 0.5 * t as f64 + pattern[t % 4];
+        let mut holt_winters: HoltWinters<f64> = HoltWinters::new(0.4, 0.2, 0.3, 4).unwrap();
+        for t in 0..4 {
+            assert!(holt_winters.get().is_nan());
+            holt_winters.update(truth(t));
+        }
+        for t in 4..400 {
+            holt_winters.update(truth(t));
+        }
+        for h in 1..=8 {
+            assert!((holt_winters.forecast(h) - truth(399 + h)).abs() < 1e-3);
+        }
+        assert!((holt_winters.trend() - 0.5).abs() < 1e-3);
+    }
+}
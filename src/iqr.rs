@@ -18,6 +18,9 @@ use std::ops::{AddAssign, SubAssign};
 ///     running_iqr.update(i as f64);
 /// }
 /// assert_eq!(running_iqr.get(), 50.0);
+/// // Tukey's fences are 1.5 IQR beyond the quartiles.
+/// assert!(running_iqr.is_outlier(200., 1.5));
+/// assert!(!running_iqr.is_outlier(90., 1.5));
 /// ```
 ///
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -37,6 +40,23 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> IQR<F> {
             q_sup: Quantile::new(q_sup).unwrap(),
         })
     }
+    fn quantiles(&self) -> (F, F) {
+        (self.q_inf.get(), self.q_sup.get())
+    }
+    /// Tukey's lower fence, `q_inf - k * IQR`, usually with `k = 1.5`.
+    pub fn lower_fence(&self, k: F) -> F {
+        let (quantile_inf, quantile_sup) = self.quantiles();
+        quantile_inf - k * (quantile_sup - quantile_inf)
+    }
+    /// Tukey's upper fence, `q_sup + k * IQR`, usually with `k = 1.5`.
+    pub fn upper_fence(&self, k: F) -> F {
+        let (quantile_inf, quantile_sup) = self.quantiles();
+        quantile_sup + k * (quantile_sup - quantile_inf)
+    }
+    /// Returns `true` if `x` is outside of the fences.
+    pub fn is_outlier(&self, x: F, k: F) -> bool {
+        x < self.lower_fence(k) || x > self.upper_fence(k)
+    }
 }
 
 impl<F> Default for IQR<F>
@@ -59,6 +79,13 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for IQR<F>
     fn get(&self) -> F {
         self.q_sup.get() - self.q_inf.get()
     }
+    fn reset(&mut self) {
+        self.q_inf.reset();
+        self.q_sup.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.q_inf.n_samples()
+    }
 }
 
 /// Rolling interquartile range.
@@ -137,6 +164,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingIQR<F> {
             frac_sup,
         })
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
     fn prepare(&self, q: F, is_inf: bool) -> (usize, usize, F) {
         if self.sorted_window.len() < self.window_size {
             let idx =
@@ -155,13 +194,7 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingIQR<F> {
         }
         (self.lower_sup, self.higher_sup, self.frac_sup)
     }
-}
-
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingIQR<F> {
-    fn update(&mut self, x: F) {
-        self.sorted_window.push_back(x);
-    }
-    fn get(&self) -> F {
+    fn quantiles(&self) -> (F, F) {
         let (lower_inf, higher_inf, frac_inf) = self.prepare(self.q_inf, true);
         let (lower_sup, higher_sup, frac_sup) = self.prepare(self.q_sup, false);
 
@@ -169,9 +202,38 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
             + (self.sorted_window[higher_inf] - self.sorted_window[lower_inf]) * frac_inf;
         let quantile_sup = self.sorted_window[lower_sup]
             + (self.sorted_window[higher_sup] - self.sorted_window[lower_sup]) * frac_sup;
+        (quantile_inf, quantile_sup)
+    }
+    /// Tukey's lower fence, `q_inf - k * IQR`, usually with `k = 1.5`.
+    pub fn lower_fence(&self, k: F) -> F {
+        let (quantile_inf, quantile_sup) = self.quantiles();
+        quantile_inf - k * (quantile_sup - quantile_inf)
+    }
+    /// Tukey's upper fence, `q_sup + k * IQR`, usually with `k = 1.5`.
+    pub fn upper_fence(&self, k: F) -> F {
+        let (quantile_inf, quantile_sup) = self.quantiles();
+        quantile_sup + k * (quantile_sup - quantile_inf)
+    }
+    /// Returns `true` if `x` is outside of the fences.
+    pub fn is_outlier(&self, x: F, k: F) -> bool {
+        x < self.lower_fence(k) || x > self.upper_fence(k)
+    }
+}
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingIQR<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        let (quantile_inf, quantile_sup) = self.quantiles();
         quantile_sup - quantile_inf
     }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
 }
 #[cfg(test)]
 mod test {
@@ -187,4 +249,29 @@ mod test {
         }
         assert_eq!(rolling_iqr.get(), 0.0);
     }
+    #[test]
+    fn tukey_fences_flag_a_known_outlier() {
+        use crate::iqr::{RollingIQR, IQR};
+        use crate::stats::Univariate;
+        let mut running_iqr: IQR<f64> = IQR::default();
+        let mut rolling_iqr: RollingIQR<f64> = RollingIQR::new(0.25, 0.75, 9).unwrap();
+        // The quartiles of the window are 3 and 7.
+        for x in [5., 1., 9., 3., 7., 2., 8., 4., 6.] {
+            running_iqr.update(x);
+            rolling_iqr.update(x);
+        }
+        assert_eq!(rolling_iqr.lower_fence(1.5), -3.);
+        assert_eq!(rolling_iqr.upper_fence(1.5), 13.);
+        assert!(!rolling_iqr.is_outlier(13., 1.5));
+        assert!(rolling_iqr.is_outlier(13.5, 1.5));
+        assert!(rolling_iqr.is_outlier(-4., 1.5));
+        assert!(!rolling_iqr.is_outlier(13.5, 3.));
+        let spread = running_iqr.get();
+        assert_eq!(
+            running_iqr.upper_fence(1.5),
+            running_iqr.q_sup.get() + 1.5 * spread
+        );
+        assert!(running_iqr.is_outlier(running_iqr.upper_fence(1.5) + 1., 1.5));
+        assert!(!running_iqr.is_outlier(5., 1.5));
+    }
 }
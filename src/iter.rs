@@ -1,18 +1,21 @@
-use num::{Float, FromPrimitive};
+use num::{Bounded, Float, FromPrimitive};
 use std::ops::{AddAssign, SubAssign};
 
+use crate::correlation::PearsonCorrelation;
 use crate::count::Count;
+use crate::covariance::Covariance;
 use crate::ewmean::EWMean;
 use crate::ewvariance::EWVariance;
 use crate::iqr::IQR;
 use crate::kurtosis::Kurtosis;
+use crate::linreg::SimpleLinReg;
 use crate::maximum::{AbsMax, Max};
 use crate::mean::Mean;
 use crate::minimum::Min;
 use crate::ptp::PeakToPeak;
 use crate::quantile::Quantile;
 use crate::skew::Skew;
-use crate::stats::Univariate;
+use crate::stats::{Bivariate, Univariate};
 use crate::sum::Sum;
 use crate::variance::Variance;
 
@@ -221,7 +224,7 @@ pub trait IterStatisticsExtend: Iterator {
     /// ```
     fn online_max(self) -> IterStat<Self>
     where
-        Self::Item: Float + FromPrimitive + AddAssign + SubAssign,
+        Self::Item: Float + FromPrimitive + AddAssign + SubAssign + Bounded,
         Self: Sized,
     {
         IterStat {
@@ -265,7 +268,7 @@ pub trait IterStatisticsExtend: Iterator {
     /// ```
     fn online_min(self) -> IterStat<Self>
     where
-        Self::Item: Float + FromPrimitive + AddAssign + SubAssign,
+        Self::Item: Float + FromPrimitive + AddAssign + SubAssign + Bounded,
         Self: Sized,
     {
         IterStat {
@@ -287,7 +290,7 @@ pub trait IterStatisticsExtend: Iterator {
     /// ```
     fn online_ptp(self) -> IterStat<Self>
     where
-        Self::Item: Float + FromPrimitive + AddAssign + SubAssign,
+        Self::Item: Float + FromPrimitive + AddAssign + SubAssign + Bounded,
         Self: Sized,
     {
         IterStat {
@@ -372,3 +375,104 @@ pub trait IterStatisticsExtend: Iterator {
     }
 }
 impl<I: Iterator> IterStatisticsExtend for I {}
+
+#[doc(hidden)]
+pub struct IterBivariateStat<I, F>
+where
+    I: Iterator<Item = (F, F)>,
+    F: Float + FromPrimitive + AddAssign + SubAssign + 'static,
+{
+    stat: Box<dyn Bivariate<F>>,
+    underlying: I,
+}
+
+impl<I, F> Iterator for IterBivariateStat<I, F>
+where
+    I: Iterator<Item = (F, F)>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    type Item = F;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((x, y)) = self.underlying.next() {
+            self.stat.update(x, y);
+            return Some(self.stat.get());
+        }
+        None
+    }
+}
+
+pub trait BivariateIterExtend<F>: Iterator<Item = (F, F)> {
+    /// Running covariance.
+    /// # Arguments
+    /// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen pairs.
+    /// # Examples
+    ///
+    /// ```
+    /// use watermill::iter::BivariateIterExtend;
+    /// let x: Vec<f64> = vec![1., 2., 3.];
+    /// let y: Vec<f64> = vec![2., 1., 6.];
+    /// let vec_true: Vec<f64> = vec![0., -0.5, 2.];
+    /// for (d, t) in x.into_iter().zip(y).online_covariance(1).zip(vec_true.into_iter()){
+    ///     assert_eq!(d, t);
+    /// }
+    ///
+    /// ```
+    fn online_covariance(self, ddof: u32) -> IterBivariateStat<Self, F>
+    where
+        F: Float + FromPrimitive + AddAssign + SubAssign,
+        Self: Sized,
+    {
+        IterBivariateStat {
+            stat: Box::new(Covariance::new(ddof)),
+            underlying: self,
+        }
+    }
+    /// Running Pearson correlation.
+    /// # Examples
+    ///
+    /// ```
+    /// use watermill::iter::BivariateIterExtend;
+    /// let x: Vec<f64> = vec![1., 2., 3.];
+    /// let y: Vec<f64> = vec![2., 1., 6.];
+    /// let vec_true: Vec<f64> = vec![0., -1., 0.7559289460184544];
+    /// for (d, t) in x.into_iter().zip(y).online_correlation().zip(vec_true.into_iter()){
+    ///     assert!((d - t).abs() < 1e-12);
+    /// }
+    ///
+    /// ```
+    fn online_correlation(self) -> IterBivariateStat<Self, F>
+    where
+        F: Float + FromPrimitive + AddAssign + SubAssign,
+        Self: Sized,
+    {
+        IterBivariateStat {
+            stat: Box::new(PearsonCorrelation::default()),
+            underlying: self,
+        }
+    }
+    /// Running slope of the least squares regression of `y` on `x`.
+    /// # Examples
+    ///
+    /// ```
+    /// use watermill::iter::BivariateIterExtend;
+    /// let x: Vec<f64> = vec![1., 2., 3.];
+    /// let y: Vec<f64> = vec![2., 1., 6.];
+    /// let vec_true: Vec<f64> = vec![0., -1., 2.];
+    /// for (d, t) in x.into_iter().zip(y).online_slope().zip(vec_true.into_iter()){
+    ///     assert_eq!(d, t);
+    /// }
+    ///
+    /// ```
+    fn online_slope(self) -> IterBivariateStat<Self, F>
+    where
+        F: Float + FromPrimitive + AddAssign + SubAssign,
+        Self: Sized,
+    {
+        IterBivariateStat {
+            stat: Box::new(SimpleLinReg::new()),
+            underlying: self,
+        }
+    }
+}
+impl<F, I: Iterator<Item = (F, F)>> BivariateIterExtend<F> for I {}
@@ -70,20 +70,12 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Kurtosi
         self.central_moments.update_m2();
     }
     fn get(&self) -> F {
-        let n = self.central_moments.count.get();
-        let mut kurtosis: F = F::from_f64(0.).unwrap();
-        if self.central_moments.m2 != F::from_f64(0.).unwrap() {
-            kurtosis += n * self.central_moments.m4
-                / self.central_moments.m2.powf(F::from_f64(2.).unwrap());
-        }
-        if (!self.bias) && n > F::from_f64(3.).unwrap() {
-            return F::from_f64(1.).unwrap()
-                / (n - F::from_f64(2.).unwrap())
-                / (n - F::from_f64(3.).unwrap())
-                * ((n.powf(F::from_f64(2.).unwrap()) - F::from_f64(1.).unwrap()) * kurtosis
-                    - F::from_f64(3.).unwrap()
-                        * (n - F::from_f64(1.).unwrap()).powf(F::from_f64(2.).unwrap()));
-        }
-        kurtosis - F::from_f64(3.).unwrap()
+        self.central_moments.kurtosis(self.bias)
+    }
+    fn reset(&mut self) {
+        *self = Self::new(self.bias);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.central_moments.count.n_samples()
     }
 }
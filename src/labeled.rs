@@ -0,0 +1,111 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Attaches a human-readable label to a statistic, e.g. to name the entries of a dashboard.
+/// The label is serialized with the statistic, the wrapped statistic is updated and read as if it
+/// were not wrapped.
+/// # Arguments
+/// * `inner` - The labeled statistic.
+/// # Examples
+/// ```
+/// use watermill::labeled::Labeled;
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let mut stats: Vec<Labeled<Mean<f64>>> =
+///     vec![Labeled::new(Mean::new()).with_label("latency"), Labeled::new(Mean::new())];
+/// for x in [1., 2., 3.].iter(){
+///     stats[0].update(*x);
+/// }
+/// assert_eq!(stats[0].label(), Some("latency"));
+/// assert_eq!(stats[0].get(), 2.);
+/// assert_eq!(stats[1].label(), None);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Labeled<U> {
+    pub inner: U,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl<U> Labeled<U> {
+    pub fn new(inner: U) -> Self {
+        Self { inner, label: None }
+    }
+    /// Sets the label, replacing the previous one.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+    /// Label of the statistic, `None` if it was never set.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+impl<F, U> Univariate<F> for Labeled<U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F>,
+{
+    fn update(&mut self, x: F) {
+        self.inner.update(x);
+    }
+    fn update_opt(&mut self, x: Option<F>) {
+        self.inner.update_opt(x);
+    }
+    fn get(&self) -> F {
+        self.inner.get()
+    }
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.inner.n_samples()
+    }
+}
+
+impl<F, U> Revertable<F> for Labeled<U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Revertable<F>,
+{
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.inner.revert(x)
+    }
+}
+
+impl<F, U> RollableUnivariate<F> for Labeled<U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn labeled_mean_serde_round_trip() {
+        use crate::labeled::Labeled;
+        use crate::mean::Mean;
+        use crate::stats::Univariate;
+        let mut running_mean: Labeled<Mean<f64>> =
+            Labeled::new(Mean::new()).with_label("p50 latency");
+        for x in [1., 2., 6.] {
+            running_mean.update(x);
+        }
+        let serialized = serde_json::to_string(&running_mean).unwrap();
+        let mut deserialized: Labeled<Mean<f64>> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.label(), Some("p50 latency"));
+        assert_eq!(deserialized.get(), 3.);
+        deserialized.update(7.);
+        assert_eq!(deserialized.get(), 4.);
+        // The label is optional in the serialized form.
+        let unlabeled: Labeled<Mean<f64>> = serde_json::from_str(
+            &serde_json::to_string(&Labeled::new(Mean::<f64>::new())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(unlabeled.label(), None);
+    }
+}
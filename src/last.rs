@@ -0,0 +1,99 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Most recent value of the stream, `NaN` until the first update.
+/// `Last` is not `Revertable`: the values before the last one are not kept, so there is nothing
+/// to restore.
+/// # Examples
+/// ```
+/// use watermill::last::Last;
+/// use watermill::stats::Univariate;
+/// let mut last: Last<f64> = Last::new();
+/// assert!(last.get().is_nan());
+/// for x in [3., 1., 2.].iter(){
+///     last.update(*x);
+/// }
+/// assert_eq!(last.get(), 2.);
+/// ```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Last<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub value: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Last<F> {
+    pub fn new() -> Self {
+        Self { value: None }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Last<F> {
+    fn update(&mut self, x: F) {
+        self.value = Some(x);
+    }
+    fn get(&self) -> F {
+        self.value.unwrap_or_else(F::nan)
+    }
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// First value of the stream, `NaN` until the first update. The following updates are ignored
+/// until `reset`.
+/// # Examples
+/// ```
+/// use watermill::last::First;
+/// use watermill::stats::Univariate;
+/// let mut first: First<f64> = First::new();
+/// assert!(first.get().is_nan());
+/// for x in [3., 1., 2.].iter(){
+///     first.update(*x);
+/// }
+/// assert_eq!(first.get(), 3.);
+/// ```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct First<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub value: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> First<F> {
+    pub fn new() -> Self {
+        Self { value: None }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for First<F> {
+    fn update(&mut self, x: F) {
+        if self.value.is_none() {
+            self.value = Some(x);
+        }
+    }
+    fn get(&self) -> F {
+        self.value.unwrap_or_else(F::nan)
+    }
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn missing_readings_are_skipped() {
+        use crate::last::{First, Last};
+        use crate::stats::Univariate;
+        let mut first: First<f64> = First::new();
+        let mut last: Last<f64> = Last::new();
+        for x in [None, Some(4.), Some(5.), None] {
+            first.update_opt(x);
+            last.update_opt(x);
+        }
+        assert_eq!(first.get(), 4.);
+        assert_eq!(last.get(), 5.);
+        first.reset();
+        first.update(6.);
+        assert_eq!(first.get(), 6.);
+    }
+}
@@ -33,38 +33,112 @@
 //!| Statistics                      | Revertable ?|
 //!|---------------------------------|----------|
 //!| Mean                            | ✅        |
+//!| Weighted moving average         | ✅        |
 //!| Variance                        | ✅        |
+//!| One-sample t-test               | ✅        |
+//!| Welch's t-test                  | ❌        |
+//!| Normal distribution fit         | ✅        |
+//!| Geometric standard deviation    | ✅        |
+//!| Coefficient of variation        | ✅        |
 //!| Sum                             | ✅        |
+//!| Sum of absolute values          | ✅        |
+//!| Sum of squares                  | ✅        |
+//!| Sum of products                 | ✅        |
+//!| Product                         | ✅        |
 //!| Min                             | ✅        |
 //!| Max                             | ✅        |
+//!| Argmin, argmax                  | ❌        |
 //!| Count                           | ❌        |
+//!| Log count, harmonic number      | ✅        |
+//!| First, last value               | ❌        |
+//!| Delta, percent change           | ❌        |
+//!| Monotonic run length            | ❌        |
+//!| Mode                            | ✅        |
+//!| Gini impurity                   | ✅        |
+//!| Gini coefficient                | ✅        |
+//!| Entropy                         | ✅        |
+//!| KL divergence                   | ✅        |
+//!| Population stability index      | ✅        |
+//!| Count-min sketch                | ❌        |
 //!| Quantile                        | ✅        |
 //!| Peak to peak                    | ✅        |
+//!| Range, midrange                 | ❌        |
 //!| Exponentially weighted mean     | ❌        |
 //!| Exponentially weighted variance | ❌        |
+//!| Exponentially weighted quantile | ❌        |
+//!| Exponentially weighted MAD      | ❌        |
+//!| MACD                            | ❌        |
+//!| Bollinger bands                 | ✅        |
 //!| Interquartile range             | ✅        |
+//!| Histogram                       | ✅        |
+//!| Dip test                        | ✅        |
 //!| Kurtosis                        | ❌        |
 //!| Skewness                        | ❌        |
-//!| Covariance                      | ❌        |
+//!| Covariance                      | ✅        |
+//!| Pearson correlation             | ✅        |
+//!| Spearman correlation            | ✅        |
+//!| Autocorrelation function        | ❌        |
+//!| Simple linear regression        | ❌        |
+//!| Theil–Sen slope                 | ✅        |
+//!| Exponential trend               | ❌        |
+//!| Value at risk                   | ❌        |
 //!## Inspiration
 //!The `stats` module of the [`river`](https://github.com/online-ml/river) library in `Python` greatly inspired this crate.
 
+pub mod acf;
+pub mod bollinger;
+pub mod cached;
+pub mod clipper;
+pub mod cms;
+pub mod correlation;
 pub mod count;
+pub mod counter;
+pub mod cov_matrix;
 pub mod covariance;
+pub mod cv;
+pub mod delta;
+pub mod dip;
+pub mod divergence;
+pub mod entropy;
+pub mod ew;
+pub mod ewmad;
 pub mod ewmean;
+pub mod ewquantile;
 pub mod ewvariance;
+pub mod factory;
+pub mod geometric;
+pub mod gini;
+pub mod harmonic;
+pub mod histogram;
+pub mod holt;
 pub mod iqr;
 pub mod iter;
 pub mod kurtosis;
+pub mod labeled;
+pub mod last;
+pub mod linreg;
+pub mod macd;
 pub mod maximum;
 pub mod mean;
 pub mod minimum;
+pub mod mode;
 pub mod moments;
+pub mod normal;
+pub mod oja;
+pub mod product;
 pub mod ptp;
 pub mod quantile;
+pub mod rank;
+pub mod reservoir;
+pub mod risk;
 pub mod rolling;
+pub mod scaler;
 pub mod skew;
 pub mod sorted_window;
 pub mod stats;
+pub mod strided;
 pub mod sum;
+pub mod ttest;
 pub mod variance;
+pub mod warmup;
+pub mod zscore;
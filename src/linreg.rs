@@ -0,0 +1,251 @@
+use num::{Float, FromPrimitive};
+use std::{
+    collections::VecDeque,
+    ops::{AddAssign, SubAssign},
+};
+
+use crate::covariance::Covariance;
+use crate::stats::{Bivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running simple linear regression of `y` on `x` with ordinary least squares.
+/// `get` returns the slope, the intercept is available with `intercept`.
+/// # Examples
+/// ```
+/// use watermill::linreg::SimpleLinReg;
+/// use watermill::stats::Bivariate;
+/// let mut running_linreg: SimpleLinReg<f64> = SimpleLinReg::new();
+/// for i in 0..10{
+///     running_linreg.update(i as f64, 3. * i as f64 + 1.);
+/// }
+/// assert!((running_linreg.get() - 3.).abs() < 1e-12);
+/// assert!((running_linreg.intercept() - 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on simple linear regression](https://www.wikiwand.com/en/Simple_linear_regression)
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct SimpleLinReg<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub cov: Covariance<F>,
+    pub var_x: Variance<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> SimpleLinReg<F> {
+    pub fn new() -> Self {
+        Self {
+            cov: Covariance::default(),
+            var_x: Variance::default(),
+        }
+    }
+    /// Intercept of the fitted line.
+    pub fn intercept(&self) -> F {
+        self.cov.mean_y.get() - self.get() * self.cov.mean_x.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for SimpleLinReg<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.cov.update(x, y);
+        self.var_x.update(x);
+    }
+    fn get(&self) -> F {
+        let var_x = self.var_x.get();
+        if var_x > F::from_f64(0.).unwrap() {
+            return self.cov.get() / var_x;
+        }
+        F::from_f64(0.).unwrap()
+    }
+    fn reset(&mut self) {
+        self.cov.reset();
+        self.var_x.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.cov.n_samples()
+    }
+}
+
+/// Running exponential trend, the growth rate of `y` over time.
+/// `update(t, y)` regresses `ln(y)` on `t` with a `SimpleLinReg`, and `get` returns the growth
+/// rate per unit of time `exp(slope) - 1`, e.g. `0.05` for 5% growth per step. Pairs with `y <= 0`
+/// have no logarithm and are ignored, they are not counted by `n_samples`.
+/// # Examples
+/// ```
+/// use watermill::linreg::ExponentialTrend;
+/// use watermill::stats::Bivariate;
+/// let mut running_trend: ExponentialTrend<f64> = ExponentialTrend::new();
+/// for t in 0..20{
+///     running_trend.update(t as f64, 100. * 1.05_f64.powi(t));
+/// }
+/// assert!((running_trend.get() - 0.05).abs() < 1e-12);
+/// assert!((running_trend.log_slope() - 1.05_f64.ln()).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct ExponentialTrend<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Regression of `ln(y)` on `t`.
+    pub linreg: SimpleLinReg<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ExponentialTrend<F> {
+    pub fn new() -> Self {
+        Self {
+            linreg: SimpleLinReg::new(),
+        }
+    }
+    /// Slope of `ln(y)`, the continuously compounded growth rate.
+    pub fn log_slope(&self) -> F {
+        self.linreg.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for ExponentialTrend<F> {
+    fn update(&mut self, t: F, y: F) {
+        if y > F::from_f64(0.).unwrap() {
+            self.linreg.update(t, y.ln());
+        }
+    }
+    fn get(&self) -> F {
+        self.log_slope().exp() - F::from_f64(1.).unwrap()
+    }
+    fn reset(&mut self) {
+        self.linreg.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.linreg.n_samples()
+    }
+}
+
+fn median<F: Float + FromPrimitive>(values: &mut [F]) -> F {
+    if values.is_empty() {
+        return F::from_f64(0.).unwrap();
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        (values[middle - 1] + values[middle]) / F::from_f64(2.).unwrap()
+    }
+}
+
+/// Rolling Theil–Sen estimator, a slope robust to outliers.
+/// `get` returns the median of the slopes between every two pairs of the window with distinct
+/// `x`, and 0 if there is no such couple. The intercept is the median of `y - slope * x`.
+/// Each call to `get` computes the `w (w - 1) / 2` pairwise slopes, `w` being the size of the
+/// window, so it costs `O(w² log w)` and is meant for small windows.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::linreg::RollingTheilSen;
+/// use watermill::stats::Bivariate;
+/// let mut rolling_theil_sen: RollingTheilSen<f64> = RollingTheilSen::new(5).unwrap();
+/// for (x, y) in [(0., 1.), (1., 3.), (2., 50.), (3., 7.), (4., 9.)]{
+///     rolling_theil_sen.update(x, y);
+/// }
+/// assert_eq!(rolling_theil_sen.get(), 2.);
+/// assert_eq!(rolling_theil_sen.intercept(), 1.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Theil–Sen estimator](https://en.wikipedia.org/wiki/Theil%E2%80%93Sen_estimator)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingTheilSen<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    window: VecDeque<(F, F)>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingTheilSen<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+    /// Intercept of the fitted line.
+    pub fn intercept(&self) -> F {
+        let slope = self.get();
+        let mut residuals: Vec<F> = self.window.iter().map(|(x, y)| *y - slope * *x).collect();
+        median(&mut residuals)
+    }
+    /// Number of pairs currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no pair has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` pairs.
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RollingTheilSen<F> {
+    fn update(&mut self, x: F, y: F) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back((x, y));
+    }
+    fn get(&self) -> F {
+        let mut slopes = Vec::with_capacity(self.window.len() * self.window.len() / 2);
+        for (i, (x_i, y_i)) in self.window.iter().enumerate() {
+            for (x_j, y_j) in self.window.iter().skip(i + 1) {
+                if x_i != x_j {
+                    slopes.push((*y_j - *y_i) / (*x_j - *x_i));
+                }
+            }
+        }
+        median(&mut slopes)
+    }
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn theil_sen_resists_outliers() {
+        use crate::linreg::{RollingTheilSen, SimpleLinReg};
+        use crate::stats::Bivariate;
+        let window_size = 20;
+        assert!(RollingTheilSen::<f64>::new(0).is_err());
+        let mut rolling_theil_sen: RollingTheilSen<f64> =
+            RollingTheilSen::new(window_size).unwrap();
+        let mut ols: SimpleLinReg<f64> = SimpleLinReg::new();
+        for i in 0..100 {
+            let x = i as f64;
+            let noise = ((i * 7) % 5) as f64 * 0.1 - 0.2;
+            // Values decay to 0 at the end of the window, which pulls the OLS slope down.
+            let y = if i >= 97 { 0. } else { 0.5 * x + 2. + noise };
+            rolling_theil_sen.update(x, y);
+            if i >= 100 - window_size {
+                ols.update(x, y);
+            }
+        }
+        assert!((rolling_theil_sen.get() - 0.5).abs() < 0.05);
+        let prediction = rolling_theil_sen.intercept() + 90. * rolling_theil_sen.get();
+        assert!((prediction - 47.).abs() < 0.5);
+        assert!((ols.get() - 0.5).abs() > 0.5);
+    }
+    #[test]
+    fn exponential_trend_ignores_non_positive_values() {
+        use crate::linreg::ExponentialTrend;
+        use crate::stats::Bivariate;
+        let mut running_trend: ExponentialTrend<f64> = ExponentialTrend::new();
+        for (t, y) in [(0., 8.), (1., 0.), (2., 4.), (3., -1.), (4., 2.)] {
+            running_trend.update(t, y);
+        }
+        // The population halves every two steps.
+        assert_eq!(running_trend.n_samples(), Some(3));
+        assert!((running_trend.get() - (0.5_f64.sqrt() - 1.)).abs() < 1e-12);
+        running_trend.reset();
+        assert_eq!(running_trend.n_samples(), Some(0));
+        assert_eq!(running_trend.get(), 0.);
+    }
+}
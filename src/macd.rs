@@ -0,0 +1,116 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::ewmean::{Adjust, EWMean};
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Moving average convergence divergence (MACD).
+/// The MACD line is the difference between a fast and a slow exponentially weighted mean of the
+/// values, the signal line is an exponentially weighted mean of the MACD line and the histogram is
+/// their difference. Each mean is defined by its span `s`, i.e. `alpha = 2 / (s + 1)`, and follows
+/// `Adjust::Recursive`, like `ewm(span=s, adjust=False)` in pandas.
+/// # Arguments
+/// * `fast` - Span of the fast mean, usually `12`.
+/// * `slow` - Span of the slow mean, must be larger than `fast`, usually `26`.
+/// * `signal` - Span of the signal line, usually `9`.
+/// # Examples
+/// ```
+/// use watermill::macd::Macd;
+/// use watermill::stats::Univariate;
+/// let mut macd: Macd<f64> = Macd::new(12, 26, 9).unwrap();
+/// for i in 0..200{
+///     macd.update(i as f64);
+/// }
+/// // On a steady trend the slow mean lags `(26 - 12) / 2` steps behind the fast one.
+/// assert!((macd.get() - 7.).abs() < 1e-3);
+/// assert!((macd.signal() - 7.).abs() < 1e-3);
+/// assert!(macd.histogram() >= 0.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on MACD](https://en.wikipedia.org/wiki/MACD)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Macd<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub fast: EWMean<F>,
+    pub slow: EWMean<F>,
+    /// Exponentially weighted mean of the MACD line.
+    pub signal: EWMean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Macd<F> {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Result<Self, &'static str> {
+        if fast == 0 || signal == 0 {
+            return Err("spans should be at least 1");
+        }
+        if fast >= slow {
+            return Err("slow should be larger than fast");
+        }
+        let ewmean = |span: usize| {
+            let alpha = F::from_f64(2.).unwrap() / F::from_usize(span + 1).unwrap();
+            EWMean::with_adjust(alpha, Adjust::Recursive)
+        };
+        Ok(Self {
+            fast: ewmean(fast),
+            slow: ewmean(slow),
+            signal: ewmean(signal),
+        })
+    }
+    /// MACD line, the fast mean minus the slow mean.
+    pub fn macd(&self) -> F {
+        self.fast.get() - self.slow.get()
+    }
+    /// Signal line, the exponentially weighted mean of the MACD line.
+    pub fn signal(&self) -> F {
+        self.signal.get()
+    }
+    /// MACD line minus the signal line.
+    pub fn histogram(&self) -> F {
+        self.macd() - self.signal()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Macd<F> {
+    fn update(&mut self, x: F) {
+        self.fast.update(x);
+        self.slow.update(x);
+        self.signal.update(self.macd());
+    }
+    fn get(&self) -> F {
+        self.macd()
+    }
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn matches_the_recursive_definition() {
+        use crate::macd::Macd;
+        use crate::stats::Univariate;
+        assert!(Macd::<f64>::new(26, 12, 9).is_err());
+        assert!(Macd::<f64>::new(0, 12, 9).is_err());
+        let data = [10., 11., 13., 12., 9., 8., 8.5, 7., 7.5, 6.];
+        let mut macd: Macd<f64> = Macd::new(3, 6, 4).unwrap();
+        let (a_fast, a_slow, a_signal) = (2. / 4., 2. / 7., 2. / 5.);
+        let (mut fast, mut slow, mut signal) = (data[0], data[0], 0.);
+        for (i, x) in data.iter().enumerate() {
+            macd.update(*x);
+            if i > 0 {
+                fast = a_fast * x + (1. - a_fast) * fast;
+                slow = a_slow * x + (1. - a_slow) * slow;
+                signal = a_signal * (fast - slow) + (1. - a_signal) * signal;
+            }
+            assert!((macd.get() - (fast - slow)).abs() < 1e-12);
+            assert!((macd.signal() - signal).abs() < 1e-12);
+            assert!((macd.histogram() - (fast - slow - signal)).abs() < 1e-12);
+        }
+        // The values are falling, the fast mean is below the slow one.
+        assert!(macd.get() < 0.);
+        macd.reset();
+        macd.update(5.);
+        assert_eq!(macd.get(), 0.);
+    }
+}
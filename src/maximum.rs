@@ -1,6 +1,7 @@
+use crate::minimum::TieBreak;
 use crate::sorted_window::SortedWindow;
 use crate::stats::Univariate;
-use num::{Float, FromPrimitive};
+use num::{Bounded, Float, FromPrimitive, Num};
 use serde::{Deserialize, Serialize};
 use std::ops::{AddAssign, SubAssign};
 /// Running max.
@@ -14,28 +15,221 @@ use std::ops::{AddAssign, SubAssign};
 /// }
 /// assert_eq!(running_max.get(), 9.0);
 /// ```
+/// `Max` does not require a floating point type:
+/// ```
+/// use watermill::maximum::Max;
+/// use watermill::stats::Univariate;
+/// let mut running_max: Max<i32> = Max::new();
+/// for i in -10..-3{
+///     running_max.update(i);
+/// }
+/// assert_eq!(running_max.get(), -4);
+/// ```
 ///
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
-pub struct Max<F: Float + FromPrimitive + AddAssign + SubAssign> {
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Max<F: Num + Copy + PartialOrd + Bounded> {
     pub max: F,
+    /// The max only moves when a value exceeds it by more than `tolerance`.
+    #[serde(default = "num::zero")]
+    pub tolerance: F,
+    /// `false` until the first value, the initial `max` is only a placeholder.
+    #[serde(default)]
+    initialized: bool,
 }
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Max<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> Max<F> {
     pub fn new() -> Self {
+        Self::with_tolerance(F::zero())
+    }
+    /// Running max which ignores fluctuations smaller than `tolerance`, which should not be
+    /// negative. The first value always becomes the max, then a value `x` replaces the max only
+    /// if `x > max + tolerance`. The reported max is therefore within `tolerance` of the
+    /// true max of the values seen so far.
+    pub fn with_tolerance(tolerance: F) -> Self {
         Self {
             max: F::min_value(),
+            tolerance,
+            initialized: false,
         }
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Max<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> Default for Max<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> Univariate<F> for Max<F> {
     fn update(&mut self, x: F) {
-        if self.max < x {
+        if !self.initialized {
+            self.initialized = true;
+            // A max serialized before `initialized` existed holds a real value unless it is the
+            // placeholder.
+            if self.max == F::min_value() {
+                self.max = x;
+                return;
+            }
+        }
+        // `x > max + tolerance`, rearranged so that nothing overflows for signed or unsigned
+        // integers: `x - max` is only taken when both are non-negative.
+        let exceeds = x > self.max
+            && if self.max >= F::zero() {
+                x - self.max > self.tolerance
+            } else {
+                x > self.max + self.tolerance
+            };
+        if exceeds {
             self.max = x;
         }
     }
     fn get(&self) -> F {
         self.max
     }
+    fn reset(&mut self) {
+        *self = Self::with_tolerance(self.tolerance);
+    }
+}
+
+/// Running max which also records when the current extreme was seen.
+/// `update_at` takes an explicit timestamp, while `update` uses the index of the sample (starting
+/// at 0) as the time. Ties keep the time of the first occurrence.
+/// # Examples
+/// ```
+/// use watermill::maximum::TimedMax;
+/// use watermill::stats::Univariate;
+/// let mut timed_max: TimedMax<f64> = TimedMax::new();
+/// for (t, x) in [3., 7., 5., 7., 2.].iter().enumerate(){
+///     timed_max.update_at(*x, 10. * t as f64);
+/// }
+/// assert_eq!(timed_max.value(), 7.);
+/// assert_eq!(timed_max.time(), 10.);
+/// timed_max.update(7.);
+/// assert_eq!(timed_max.time(), 10.);
+/// ```
+///
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct TimedMax<F: Num + Copy + PartialOrd + Bounded> {
+    max: F,
+    time: F,
+    /// Number of samples, the implicit time of `update`.
+    n: u64,
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> TimedMax<F> {
+    pub fn new() -> Self {
+        Self {
+            max: F::min_value(),
+            time: F::zero(),
+            n: 0,
+        }
+    }
+    /// Updates the max with a sample `x` observed at time `t`.
+    pub fn update_at(&mut self, x: F, t: F) {
+        if x > self.max || self.n == 0 {
+            self.max = x;
+            self.time = t;
+        }
+        self.n = self.n.saturating_add(1);
+    }
+    /// Current max.
+    pub fn value(&self) -> F {
+        self.max
+    }
+    /// Time at which the current max was seen.
+    pub fn time(&self) -> F {
+        self.time
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> Univariate<F> for TimedMax<F> {
+    /// The time is the index of the sample, it saturates at the largest value of `F`.
+    fn update(&mut self, x: F) {
+        let t = F::from_u64(self.n).unwrap_or_else(F::max_value);
+        self.update_at(x, t);
+    }
+    fn get(&self) -> F {
+        self.max
+    }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n as usize)
+    }
+}
+
+/// Running argmax, the absolute index (starting at 0) of the max in the stream, i.e. the number
+/// of values seen before it. `index` returns it as a `usize`, `get` converts it to `F` and
+/// saturates at the largest value of `F` when the index does not fit, e.g. past 127 for `i8`. Both
+/// are `0` before the first update.
+/// # Arguments
+/// * `tie_break` - Which occurrence is reported when the max is seen several times, see `TieBreak`. Defaults to `TieBreak::First`.
+/// # Examples
+/// ```
+/// use watermill::minimum::TieBreak;
+/// use watermill::maximum::ArgMax;
+/// use watermill::stats::Univariate;
+/// let mut running_argmax: ArgMax<f64> = ArgMax::new();
+/// for x in [1., 4., 3., 4., 2.].iter(){
+///     running_argmax.update(*x);
+/// }
+/// assert_eq!(running_argmax.get(), 1.);
+/// assert_eq!(running_argmax.index(), 1);
+/// let mut running_argmax: ArgMax<f64> = ArgMax::with_tie_break(TieBreak::Last);
+/// for x in [1., 4., 3., 4., 2.].iter(){
+///     running_argmax.update(*x);
+/// }
+/// assert_eq!(running_argmax.get(), 3.);
+/// ```
+///
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct ArgMax<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> {
+    max: F,
+    index: usize,
+    n: usize,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> ArgMax<F> {
+    pub fn new() -> Self {
+        Self::with_tie_break(TieBreak::First)
+    }
+    pub fn with_tie_break(tie_break: TieBreak) -> Self {
+        Self {
+            max: F::min_value(),
+            index: 0,
+            n: 0,
+            tie_break,
+        }
+    }
+    /// Current max.
+    pub fn value(&self) -> F {
+        self.max
+    }
+    /// Index in the stream at which the current max was seen.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> Univariate<F> for ArgMax<F> {
+    fn update(&mut self, x: F) {
+        if self.n == 0 || x > self.max || (x == self.max && self.tie_break == TieBreak::Last) {
+            self.max = x;
+            self.index = self.n;
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        F::from_usize(self.index).unwrap_or_else(F::max_value)
+    }
+    fn reset(&mut self) {
+        *self = Self::with_tie_break(self.tie_break);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
 }
 
 /// Running absolute max.
@@ -72,6 +266,9 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for AbsMax<
     fn get(&self) -> F {
         self.abs_max
     }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
 }
 
 /// Rolling max.
@@ -99,6 +296,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMax<F> {
             sorted_window: SortedWindow::new(window_size),
         }
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMax<F> {
@@ -108,4 +317,331 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.sorted_window.back()
     }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+/// Rolling argmax, the position of the max in the window, `0` being the oldest value.
+/// `get` returns `NaN` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `tie_break` - Which occurrence is reported when the max is in the window several times, see `TieBreak`. Defaults to `TieBreak::First`.
+/// # Examples
+/// ```
+/// use watermill::minimum::TieBreak;
+/// use watermill::maximum::RollingArgMax;
+/// use watermill::stats::Univariate;
+/// let mut rolling_argmax: RollingArgMax<f64> = RollingArgMax::new(4).unwrap();
+/// for x in [5., 1., 5., 3.].iter(){
+///     rolling_argmax.update(*x);
+/// }
+/// assert_eq!(rolling_argmax.get(), 0.);
+/// let mut rolling_argmax: RollingArgMax<f64> = RollingArgMax::with_tie_break(4, TieBreak::Last).unwrap();
+/// for x in [5., 1., 5., 3.].iter(){
+///     rolling_argmax.update(*x);
+/// }
+/// assert_eq!(rolling_argmax.get(), 2.);
+/// assert_eq!(rolling_argmax.argmax_value(), 5.);
+/// ```
+///
+#[derive(Serialize, Deserialize)]
+pub struct RollingArgMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    #[serde(default)]
+    tie_break: TieBreak,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingArgMax<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_tie_break(window_size, TieBreak::First)
+    }
+    pub fn with_tie_break(window_size: usize, tie_break: TieBreak) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size),
+            tie_break,
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+    /// Value of the max, i.e. the value at the position returned by `get`, `NaN` while the window is
+    /// empty.
+    pub fn argmax_value(&self) -> F {
+        if self.sorted_window.is_empty() {
+            return F::nan();
+        }
+        self.sorted_window.back()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingArgMax<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        if self.sorted_window.is_empty() {
+            return F::nan();
+        }
+        let max = self.sorted_window.back();
+        let mut values = self.sorted_window.unsorted_window.iter();
+        let position = match self.tie_break {
+            TieBreak::First => values.position(|x| *x == max),
+            TieBreak::Last => values.rposition(|x| *x == max),
+        };
+        F::from_usize(position.unwrap()).unwrap()
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+/// Rolling absolute max.
+/// The window stores magnitudes, so the result drops to the next largest magnitude when the
+/// largest one leaves the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::maximum::RollingAbsMax;
+/// use watermill::stats::Univariate;
+/// let mut rolling_abs_max: RollingAbsMax<f64> = RollingAbsMax::new(3);
+/// for i in -17..-7{
+///     rolling_abs_max.update(i as f64);
+/// }
+/// assert_eq!(rolling_abs_max.get(), 10.0);
+/// ```
+///
+#[derive(Serialize, Deserialize)]
+pub struct RollingAbsMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingAbsMax<F> {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            sorted_window: SortedWindow::new(window_size),
+        }
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingAbsMax<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x.abs());
+    }
+    fn get(&self) -> F {
+        self.sorted_window.back()
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+#[cfg(test)]
+mod test {
+    #[test]
+    fn unsigned_max() {
+        use crate::maximum::Max;
+        use crate::stats::Univariate;
+        let mut running_max: Max<u32> = Max::new();
+        assert_eq!(running_max.get(), 0);
+        for x in [3_u32, 17, 2, 17, 9] {
+            running_max.update(x);
+        }
+        assert_eq!(running_max.get(), 17);
+    }
+
+    #[test]
+    fn rolling_abs_max_expires_largest_magnitude() {
+        use crate::maximum::RollingAbsMax;
+        use crate::stats::Univariate;
+        let mut rolling_abs_max: RollingAbsMax<f64> = RollingAbsMax::new(3);
+        for (x, expected) in [
+            (-9., 9.),
+            (2., 9.),
+            (-4., 9.),
+            (3., 4.),
+            (1., 4.),
+            (0.5, 3.),
+        ] {
+            rolling_abs_max.update(x);
+            assert_eq!(rolling_abs_max.get(), expected);
+        }
+    }
+
+    #[test]
+    fn timed_max_uses_sample_index() {
+        use crate::maximum::TimedMax;
+        use crate::stats::Univariate;
+        let mut timed_max: TimedMax<i32> = TimedMax::new();
+        for (x, expected_time) in [(-5, 0), (-7, 0), (2, 2), (2, 2), (1, 2), (4, 5)] {
+            timed_max.update(x);
+            assert_eq!(timed_max.time(), expected_time);
+        }
+        assert_eq!(timed_max.get(), 4);
+        // The counter does not overflow narrow types, the time saturates instead.
+        let mut timed_max: TimedMax<i8> = TimedMax::new();
+        for i in 0..300 {
+            timed_max.update(if i == 200 { 100 } else { 0 });
+        }
+        assert_eq!((timed_max.get(), timed_max.time()), (100, i8::MAX));
+        // Nor does it stop at `2^24` with `f32`.
+        let mut timed_max: TimedMax<f32> = TimedMax::new();
+        timed_max.n = 1 << 24;
+        for x in [1., 2., 3.] {
+            timed_max.update(x);
+        }
+        assert_eq!(timed_max.time(), 16_777_218.);
+    }
+
+    #[test]
+    fn arg_max_tie_break_on_a_plateau() {
+        use crate::maximum::{ArgMax, RollingArgMax};
+        use crate::minimum::TieBreak;
+        use crate::stats::Univariate;
+        let data = [1., 6., 6., 6., 0., 4.];
+        let mut first: ArgMax<f64> = ArgMax::new();
+        let mut last: ArgMax<f64> = ArgMax::with_tie_break(TieBreak::Last);
+        let mut rolling_first: RollingArgMax<f64> = RollingArgMax::new(5).unwrap();
+        let mut rolling_last: RollingArgMax<f64> =
+            RollingArgMax::with_tie_break(5, TieBreak::Last).unwrap();
+        for x in data {
+            first.update(x);
+            last.update(x);
+            rolling_first.update(x);
+            rolling_last.update(x);
+        }
+        assert_eq!((first.get(), first.value()), (1., 6.));
+        assert_eq!((last.get(), last.value()), (3., 6.));
+        // The window holds 6, 6, 6, 0, 4.
+        assert_eq!(rolling_first.get(), 0.);
+        assert_eq!(rolling_last.get(), 2.);
+        // The index of the last of 300 equal values does not fit in a `u8`.
+        let mut narrow: ArgMax<u8> = ArgMax::with_tie_break(TieBreak::Last);
+        for _ in 0..300 {
+            narrow.update(7);
+        }
+        assert_eq!((narrow.index(), narrow.get()), (299, u8::MAX));
+        assert!(RollingArgMax::<f64>::new(0).is_err());
+        assert!(RollingArgMax::<f64>::with_tie_break(0, TieBreak::Last).is_err());
+    }
+    #[test]
+    fn arg_max_reports_the_absolute_stream_index() {
+        use crate::maximum::ArgMax;
+        use crate::stats::Univariate;
+        let data = [-9, -8, -7, 6, 5, 4, 3, 2];
+        let mut running_argmax: ArgMax<i32> = ArgMax::new();
+        for (i, x) in data.iter().enumerate() {
+            running_argmax.update(*x);
+            assert_eq!(running_argmax.index(), i.min(3));
+        }
+        assert_eq!(running_argmax.get(), 3);
+        assert_eq!(running_argmax.value(), 6);
+    }
+    #[test]
+    fn tolerance_ignores_small_increases() {
+        use crate::maximum::Max;
+        use crate::minimum::Min;
+        use crate::ptp::PeakToPeak;
+        use crate::stats::Univariate;
+        let mut running_max: Max<f64> = Max::with_tolerance(0.5);
+        let mut running_min: Min<f64> = Min::with_tolerance(0.5);
+        let mut running_ptp: PeakToPeak<f64> = PeakToPeak::with_tolerance(0.5);
+        for x in [10., 10.2, 10.4, 10.5, 9.8, 9.6] {
+            running_max.update(x);
+            running_min.update(x);
+            running_ptp.update(x);
+        }
+        // None of the values moved the extremes by more than 0.5.
+        assert_eq!(running_max.get(), 10.);
+        assert_eq!(running_min.get(), 10.);
+        assert_eq!(running_ptp.get(), 0.);
+        running_max.update(10.6);
+        running_min.update(9.4);
+        running_ptp.update(10.6);
+        assert_eq!(running_max.get(), 10.6);
+        assert_eq!(running_min.get(), 9.4);
+        assert!((running_ptp.get() - 0.6).abs() < 1e-12);
+        running_max.reset();
+        assert_eq!(running_max.tolerance, 0.5);
+        running_max.update(-3.);
+        assert_eq!(running_max.get(), -3.);
+        // A first value equal to the initial placeholder is a real max.
+        let mut integers: Max<i32> = Max::with_tolerance(2);
+        for x in [i32::MIN, i32::MIN + 2] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), i32::MIN);
+        integers.update(i32::MIN + 3);
+        assert_eq!(integers.get(), i32::MIN + 3);
+        // The tolerance is not added to values close to the bounds.
+        let mut integers: Max<i32> = Max::with_tolerance(5);
+        for x in [i32::MAX - 3, i32::MAX] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), i32::MAX - 3);
+        let mut integers: Max<i8> = Max::with_tolerance(5);
+        for x in [-100, 100, 104] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), 100);
+        // Unsigned values smaller than the tolerance.
+        let mut unsigned: Max<u8> = Max::with_tolerance(10);
+        for x in [2, 5, 12] {
+            unsigned.update(x);
+        }
+        assert_eq!(unsigned.get(), 2);
+        unsigned.update(13);
+        assert_eq!(unsigned.get(), 13);
+        // Maxima serialized before the initialization flag existed keep their value.
+        let mut deserialized: Max<f64> = serde_json::from_str(r#"{"max":5.0}"#).unwrap();
+        deserialized.update(1.);
+        assert_eq!(deserialized.get(), 5.);
+    }
+    #[test]
+    fn argmax_value_matches_the_rolling_max() {
+        use crate::maximum::{RollingArgMax, RollingMax};
+        use crate::stats::Univariate;
+        let mut rolling_argmax: RollingArgMax<f64> = RollingArgMax::new(4).unwrap();
+        let mut rolling_max: RollingMax<f64> = RollingMax::new(4);
+        assert!(rolling_argmax.argmax_value().is_nan());
+        for i in 0..50 {
+            let x = ((i * 17) % 11) as f64 - 5.;
+            rolling_argmax.update(x);
+            rolling_max.update(x);
+            assert_eq!(rolling_argmax.argmax_value(), rolling_max.get());
+        }
+    }
 }
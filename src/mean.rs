@@ -1,8 +1,10 @@
 use num::{Float, FromPrimitive};
+use std::collections::VecDeque;
 use std::ops::{AddAssign, SubAssign};
 
 use crate::count::Count;
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::rolling::RollingOwned;
+use crate::stats::{block_sum, fold, Mergeable, Revertable, RollableUnivariate, Univariate};
 use serde::{Deserialize, Serialize};
 
 /// Running mean.
@@ -28,38 +30,165 @@ use serde::{Deserialize, Serialize};
 /// [^2]: [Finch, T., 2009. Incremental calculation of weighted mean and variance. University of Cambridge, 4(11-5), pp.41-42.](https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf)
 ///
 /// [^3]: [Chan, T.F., Golub, G.H. and LeVeque, R.J., 1983. Algorithms for computing the sample variance: Analysis and recommendations. The American Statistician, 37(3), pp.242-247.](https://amstat.tandfonline.com/doi/abs/10.1080/00031305.1983.10483115)
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Mean<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub mean: F,
+    /// Weight of the values seen so far, their number unless the mean was decayed by `ew::EW`.
+    /// Stored in `F`, it stops increasing at `2^53` for `f64`.
     pub n: Count<F>,
+    /// Exact number of values, the divisor of the updates. `None` once the weights are no longer
+    /// integers, the divisor is then `n`.
+    #[serde(default)]
+    exact_n: Option<u64>,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mean<F> {
     pub fn new() -> Self {
         Self {
             mean: F::from_f64(0.0).unwrap(),
             n: Count::new(),
+            exact_n: Some(0),
         }
     }
+    /// Mean of the values of `data`.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// assert_eq!(Mean::fold(&[1., 2., 6.]).get(), 3.);
+    /// ```
+    pub fn fold(data: &[F]) -> Self {
+        fold(Self::new(), data)
+    }
+    /// Exact number of values seen, `None` if the mean was decayed or deserialized from a version
+    /// without the exact count.
+    pub fn exact_count(&self) -> Option<u64> {
+        self.exact_n
+    }
+    /// Forgets the exact count, the divisor of the updates becomes `n`.
+    pub(crate) fn forget_exact_count(&mut self) {
+        self.exact_n = None;
+    }
+    /// Number of values as a divisor: the exact count converted to `F` if it is known, `n`
+    /// otherwise. Statistics built on a `Mean` divide by it rather than by `n`, which stops
+    /// increasing at `2^53` with `f64`.
+    pub fn weight(&self) -> F {
+        match self.exact_n {
+            Some(n) => F::from_u64(n).unwrap(),
+            None => self.n.get(),
+        }
+    }
+    /// Mean of `n` values whose mean is `mean`, e.g. to resume from aggregates computed elsewhere.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::from_parts(2., 3).unwrap();
+    /// running_mean.update(6.);
+    /// assert_eq!(running_mean.get(), 3.);
+    /// ```
+    pub fn from_parts(mean: F, n: usize) -> Result<Self, &'static str> {
+        if n == 0 {
+            return Err("n should be greater than 0");
+        }
+        Ok(Self {
+            mean,
+            n: Count {
+                count: F::from_usize(n).unwrap(),
+            },
+            exact_n: Some(n as u64),
+        })
+    }
 }
 
+impl<F> Default for Mean<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! impl_const_new {
+    ($($t:ty),*) => {
+        $(
+            impl Mean<$t> {
+                /// Same as `Mean::new` but usable in `const` and `static` contexts.
+                pub const fn new_const() -> Self {
+                    Self {
+                        mean: 0.0,
+                        n: Count::<$t>::new_const(),
+                        exact_n: Some(0),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_const_new!(f32, f64);
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Mean<F> {
     fn update(&mut self, x: F) {
         self.n.update(x);
-        self.mean += (F::from_f64(1.).unwrap() / self.n.get()) * (x - self.mean);
+        if let Some(n) = self.exact_n.as_mut() {
+            *n += 1;
+        }
+        self.mean += (F::from_f64(1.).unwrap() / self.weight()) * (x - self.mean);
     }
     fn get(&self) -> F {
         self.mean
     }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        match self.exact_n {
+            Some(n) => Some(n as usize),
+            None => self.n.n_samples(),
+        }
+    }
+}
+
+/// # Examples
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::stats::{Mergeable, Univariate};
+/// let data: Vec<f64> = (0..10).map(|i| i as f64).collect();
+/// let mut running_mean: Mean<f64> = Mean::from_slice(&data[..4]);
+/// running_mean.merge(&Mean::from_slice(&data[4..]));
+/// assert_eq!(running_mean.get(), 4.5);
+/// ```
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable<F> for Mean<F> {
+    fn merge(&mut self, other: &Self) {
+        let weight = self.weight() + other.weight();
+        if weight == F::from_f64(0.).unwrap() {
+            return;
+        }
+        self.mean += (other.mean - self.mean) * other.weight() / weight;
+        self.n.count += other.n.get();
+        self.exact_n = self.exact_n.zip(other.exact_n).map(|(a, b)| a + b);
+    }
+    fn from_slice(data: &[F]) -> Self {
+        if data.is_empty() {
+            return Self::new();
+        }
+        let n = F::from_usize(data.len()).unwrap();
+        Self {
+            mean: block_sum(data, |x| x) / n,
+            n: Count { count: n },
+            exact_n: Some(data.len() as u64),
+        }
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Mean<F> {
     fn revert(&mut self, x: F) -> Result<(), &'static str> {
-        match self.n.revert(x) {
-            Ok(it) => it,
-            Err(err) => return Err(err),
-        };
+        self.n.revert(x)?;
+        if let Some(n) = self.exact_n.as_mut() {
+            *n = n.saturating_sub(1);
+        }
 
-        let count = self.n.get();
+        let count = self.weight();
         if count == F::from_f64(0.).unwrap() {
             self.mean = F::from_f64(0.0).unwrap();
         } else {
@@ -69,4 +198,233 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Mean<F>
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Mean<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Mean<F> {
+    fn get_without(&self, x: F) -> Result<F, &'static str> {
+        let count = self.weight() - F::from_f64(1.).unwrap();
+        if count < F::from_f64(0.).unwrap() {
+            return Err("Count cannot go below 0");
+        }
+        if count == F::from_f64(0.).unwrap() {
+            return Ok(F::from_f64(0.).unwrap());
+        }
+        Ok(self.mean - (F::from_f64(1.0).unwrap() / count) * (x - self.mean))
+    }
+}
+
+/// Rolling mean, see `rolling::RollingOwned`.
+/// # Examples
+/// ```
+/// use watermill::mean::{Mean, RollingMean};
+/// use watermill::stats::Univariate;
+/// let mut rolling_mean: RollingMean<f64> = RollingMean::new(Mean::new(), 3).unwrap();
+/// for i in 0..10{
+///     rolling_mean.update(i as f64);
+/// }
+/// assert_eq!(rolling_mean.get(), 8.);
+/// ```
+pub type RollingMean<F> = RollingOwned<F, Mean<F>>;
+
+/// Weights given by `WeightedRollingMean` to the values of its window.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Kernel<F> {
+    /// Every value has the same weight, as `RollingMean`.
+    Flat,
+    /// The `i`-th oldest value of the window has weight `i + 1`, the newest one has weight `n`.
+    Linear,
+    /// A value seen `k` updates ago has weight `(1 - alpha)^k`, `alpha` being in `[0, 1)`.
+    Exponential(F),
+}
+
+/// Weighted moving average over a rolling window, the weights being set by a `Kernel`.
+/// The weighted mean is recomputed over the window by `get`, in `O(window_size)`, it is `NaN`
+/// while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `kernel` - Weights of the values according to their position in the window.
+/// # Examples
+/// ```
+/// use watermill::mean::{Kernel, WeightedRollingMean};
+/// use watermill::stats::Univariate;
+/// let mut weighted_mean: WeightedRollingMean<f64> =
+///     WeightedRollingMean::new(3, Kernel::Linear).unwrap();
+/// for x in [100., 1., 2., 3.].iter(){
+///     weighted_mean.update(*x);
+/// }
+/// assert_eq!(weighted_mean.get(), (1. + 2. * 2. + 3. * 3.) / 6.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on moving averages](https://en.wikipedia.org/wiki/Moving_average#Weighted_moving_average)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeightedRollingMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub kernel: Kernel<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WeightedRollingMean<F> {
+    pub fn new(window_size: usize, kernel: Kernel<F>) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        if let Kernel::Exponential(alpha) = kernel {
+            if !(alpha >= F::from_f64(0.).unwrap() && alpha < F::from_f64(1.).unwrap()) {
+                return Err("alpha should be in [0, 1)");
+            }
+        }
+        Ok(Self {
+            kernel,
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+    /// Weight of the value seen `age` updates ago, `0` being the newest value of the window. With
+    /// `Kernel::Linear` an age outside of the window has a weight of 0.
+    pub fn weight(&self, age: usize) -> F {
+        match self.kernel {
+            Kernel::Flat => F::from_f64(1.).unwrap(),
+            Kernel::Linear => F::from_usize(self.window.len().saturating_sub(age)).unwrap(),
+            Kernel::Exponential(alpha) => (F::from_f64(1.).unwrap() - alpha).powi(age as i32),
+        }
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for WeightedRollingMean<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(x);
+    }
+    fn get(&self) -> F {
+        let mut weighted_sum = F::from_f64(0.).unwrap();
+        let mut total_weight = F::from_f64(0.).unwrap();
+        for (age, x) in self.window.iter().rev().enumerate() {
+            let weight = self.weight(age);
+            weighted_sum += weight * *x;
+            total_weight += weight;
+        }
+        weighted_sum / total_weight
+    }
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+#[cfg(test)]
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> crate::stats::ApproxEq<F> for Mean<F> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool {
+        crate::stats::close(self.mean, other.mean, eps)
+            && self.n.approx_eq(&other.n, eps)
+            && self.exact_n == other.exact_n
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn get_without_last() {
+        use crate::mean::Mean;
+        use crate::stats::{RollableUnivariate, Univariate};
+        let data = [3., -1., 4., 1.5, 9., 2.6];
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut without_last: Mean<f64> = Mean::new();
+        for (i, x) in data.iter().enumerate() {
+            running_mean.update(*x);
+            assert!((running_mean.get_without(*x).unwrap() - without_last.get()).abs() < 1e-12);
+            if i + 1 < data.len() {
+                without_last.update(*x);
+            }
+        }
+        assert!(Mean::<f64>::new().get_without(1.).is_err());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        use crate::mean::Mean;
+        use crate::stats::{ApproxEq, Univariate};
+        let mut running_mean: Mean<f64> = Mean::new();
+        for i in 1..=10 {
+            running_mean.update(1. / i as f64);
+        }
+        let serialized = serde_json::to_string(&running_mean).unwrap();
+        let deserialized: Mean<f64> = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.approx_eq(&running_mean, 1e-12));
+    }
+    #[test]
+    fn exact_count_beyond_float_precision() {
+        use crate::mean::Mean;
+        use crate::stats::Univariate;
+        let limit = 2_usize.pow(53);
+        let mut running_mean: Mean<f64> = Mean::from_parts(0., limit).unwrap();
+        let k = 1024;
+        for i in 0..k {
+            running_mean.update(if i % 2 == 0 {
+                limit as f64
+            } else {
+                3. * limit as f64
+            });
+        }
+        // `n` is stuck at `2^53`, the exact count is not.
+        assert_eq!(running_mean.n.get(), limit as f64);
+        assert_eq!(running_mean.exact_count(), Some((limit + k) as u64));
+        // `2^54 * k / (2^53 + k)`, dividing by `n` instead is off by about `k^2 / 2^53`.
+        let exact = 2. * k as f64 * limit as f64 / (limit + k) as f64;
+        assert!((running_mean.get() - exact).abs() < 3e-11);
+        // Means serialized before the exact count existed fall back to the weight.
+        let mut deserialized: Mean<f64> =
+            serde_json::from_str(r#"{"mean":1.0,"n":{"count":2.0}}"#).unwrap();
+        assert_eq!(deserialized.exact_count(), None);
+        deserialized.update(4.);
+        assert_eq!(deserialized.get(), 2.);
+    }
+    #[test]
+    fn linear_kernel_weights_recent_values_more() {
+        use crate::mean::{Kernel, Mean, RollingMean, WeightedRollingMean};
+        use crate::stats::Univariate;
+        let window_size = 10;
+        let mut flat: WeightedRollingMean<f64> =
+            WeightedRollingMean::new(window_size, Kernel::Flat).unwrap();
+        let mut linear: WeightedRollingMean<f64> =
+            WeightedRollingMean::new(window_size, Kernel::Linear).unwrap();
+        let mut exponential: WeightedRollingMean<f64> =
+            WeightedRollingMean::new(window_size, Kernel::Exponential(0.5)).unwrap();
+        let mut rolling_mean: RollingMean<f64> =
+            RollingMean::new(Mean::new(), window_size).unwrap();
+        assert!(linear.get().is_nan());
+        // A level shift, the recent values are all 1.
+        for i in 0..25 {
+            let x = if i < 20 { 0. } else { 1. };
+            flat.update(x);
+            linear.update(x);
+            exponential.update(x);
+            rolling_mean.update(x);
+        }
+        assert!((linear.weight(0) / linear.weight(9) - 10.).abs() < 1e-12);
+        assert!((flat.get() - rolling_mean.get()).abs() < 1e-12);
+        assert_eq!(flat.get(), 0.5);
+        // The 5 newest values carry 40 of the 55 linear weights.
+        assert!((linear.get() - 40. / 55.).abs() < 1e-12);
+        assert!(linear.get() > flat.get());
+        assert!(exponential.get() > linear.get());
+        assert_eq!(linear.weight(0), 10.);
+        assert_eq!(linear.weight(10), 0.);
+        assert_eq!(linear.weight(usize::MAX), 0.);
+        assert!(WeightedRollingMean::<f64>::new(0, Kernel::Linear).is_err());
+        assert!(WeightedRollingMean::new(3, Kernel::Exponential(1.)).is_err());
+    }
+}
@@ -1,8 +1,19 @@
 use crate::sorted_window::SortedWindow;
 use crate::stats::Univariate;
-use num::{Float, FromPrimitive};
+use num::{Bounded, Float, FromPrimitive, Num};
 use serde::{Deserialize, Serialize};
 use std::ops::{AddAssign, SubAssign};
+/// Which occurrence of the extreme `ArgMin`, `ArgMax`, `RollingArgMin` and `RollingArgMax`
+/// report when it is seen several times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// The oldest occurrence.
+    #[default]
+    First,
+    /// The most recent occurrence.
+    Last,
+}
+
 /// Running min.
 /// # Examples
 /// ```
@@ -15,28 +26,211 @@ use std::ops::{AddAssign, SubAssign};
 /// assert_eq!(running_min.get(), 1.0);
 /// ```
 ///
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
-pub struct Min<F: Float + FromPrimitive + AddAssign + SubAssign> {
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Min<F: Num + Copy + PartialOrd + Bounded> {
     pub min: F,
+    /// The min only moves when a value goes below it by more than `tolerance`.
+    #[serde(default = "num::zero")]
+    pub tolerance: F,
+    /// `false` until the first value, the initial `min` is only a placeholder.
+    #[serde(default)]
+    initialized: bool,
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Min<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> Min<F> {
     pub fn new() -> Self {
+        Self::with_tolerance(F::zero())
+    }
+    /// Running min which ignores fluctuations smaller than `tolerance`, which should not be
+    /// negative. The first value always becomes the min, then a value `x` replaces the min only
+    /// if `x < min - tolerance`. The reported min is therefore within `tolerance` of the
+    /// true min of the values seen so far.
+    pub fn with_tolerance(tolerance: F) -> Self {
         Self {
             min: F::max_value(),
+            tolerance,
+            initialized: false,
         }
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Min<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> Default for Min<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> Univariate<F> for Min<F> {
     fn update(&mut self, x: F) {
-        if self.min > x {
+        if !self.initialized {
+            self.initialized = true;
+            // A min serialized before `initialized` existed holds a real value unless it is the
+            // placeholder.
+            if self.min == F::max_value() {
+                self.min = x;
+                return;
+            }
+        }
+        // `x < min - tolerance`, rearranged so that nothing overflows for signed or unsigned
+        // integers: `min - x` is only taken when both are non-negative.
+        let exceeds = x < self.min
+            && if x >= F::zero() {
+                self.min - x > self.tolerance
+            } else {
+                x + self.tolerance < self.min
+            };
+        if exceeds {
             self.min = x;
         }
     }
     fn get(&self) -> F {
         self.min
     }
+    fn reset(&mut self) {
+        *self = Self::with_tolerance(self.tolerance);
+    }
+}
+
+/// Running min which also records when the current extreme was seen.
+/// `update_at` takes an explicit timestamp, while `update` uses the index of the sample (starting
+/// at 0) as the time. Ties keep the time of the first occurrence.
+/// # Examples
+/// ```
+/// use watermill::minimum::TimedMin;
+/// use watermill::stats::Univariate;
+/// let mut timed_min: TimedMin<f64> = TimedMin::new();
+/// for (t, x) in [3., 1., 5., 1., 2.].iter().enumerate(){
+///     timed_min.update_at(*x, 10. * t as f64);
+/// }
+/// assert_eq!(timed_min.value(), 1.);
+/// assert_eq!(timed_min.time(), 10.);
+/// timed_min.update(1.);
+/// assert_eq!(timed_min.time(), 10.);
+/// ```
+///
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct TimedMin<F: Num + Copy + PartialOrd + Bounded> {
+    min: F,
+    time: F,
+    /// Number of samples, the implicit time of `update`.
+    n: u64,
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> TimedMin<F> {
+    pub fn new() -> Self {
+        Self {
+            min: F::max_value(),
+            time: F::zero(),
+            n: 0,
+        }
+    }
+    /// Updates the min with a sample `x` observed at time `t`.
+    pub fn update_at(&mut self, x: F, t: F) {
+        if x < self.min || self.n == 0 {
+            self.min = x;
+            self.time = t;
+        }
+        self.n = self.n.saturating_add(1);
+    }
+    /// Current min.
+    pub fn value(&self) -> F {
+        self.min
+    }
+    /// Time at which the current min was seen.
+    pub fn time(&self) -> F {
+        self.time
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> Univariate<F> for TimedMin<F> {
+    /// The time is the index of the sample, it saturates at the largest value of `F`.
+    fn update(&mut self, x: F) {
+        let t = F::from_u64(self.n).unwrap_or_else(F::max_value);
+        self.update_at(x, t);
+    }
+    fn get(&self) -> F {
+        self.min
+    }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n as usize)
+    }
+}
+
+/// Running argmin, the absolute index (starting at 0) of the min in the stream, i.e. the number
+/// of values seen before it. `index` returns it as a `usize`, `get` converts it to `F` and
+/// saturates at the largest value of `F` when the index does not fit, e.g. past 127 for `i8`. Both
+/// are `0` before the first update.
+/// # Arguments
+/// * `tie_break` - Which occurrence is reported when the min is seen several times, see `TieBreak`. Defaults to `TieBreak::First`.
+/// # Examples
+/// ```
+/// use watermill::minimum::TieBreak;
+/// use watermill::minimum::ArgMin;
+/// use watermill::stats::Univariate;
+/// let mut running_argmin: ArgMin<f64> = ArgMin::new();
+/// for x in [4., 1., 3., 1., 2.].iter(){
+///     running_argmin.update(*x);
+/// }
+/// assert_eq!(running_argmin.get(), 1.);
+/// assert_eq!(running_argmin.index(), 1);
+/// let mut running_argmin: ArgMin<f64> = ArgMin::with_tie_break(TieBreak::Last);
+/// for x in [4., 1., 3., 1., 2.].iter(){
+///     running_argmin.update(*x);
+/// }
+/// assert_eq!(running_argmin.get(), 3.);
+/// ```
+///
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct ArgMin<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> {
+    min: F,
+    index: usize,
+    n: usize,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> ArgMin<F> {
+    pub fn new() -> Self {
+        Self::with_tie_break(TieBreak::First)
+    }
+    pub fn with_tie_break(tie_break: TieBreak) -> Self {
+        Self {
+            min: F::max_value(),
+            index: 0,
+            n: 0,
+            tie_break,
+        }
+    }
+    /// Current min.
+    pub fn value(&self) -> F {
+        self.min
+    }
+    /// Index in the stream at which the current min was seen.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded + FromPrimitive> Univariate<F> for ArgMin<F> {
+    fn update(&mut self, x: F) {
+        if self.n == 0 || x < self.min || (x == self.min && self.tie_break == TieBreak::Last) {
+            self.min = x;
+            self.index = self.n;
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        F::from_usize(self.index).unwrap_or_else(F::max_value)
+    }
+    fn reset(&mut self) {
+        *self = Self::with_tie_break(self.tie_break);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
 }
 
 /// Rolling min.
@@ -64,6 +258,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMin<F> {
             sorted_window: SortedWindow::new(window_size),
         }
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMin<F> {
@@ -73,4 +279,215 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.sorted_window.front()
     }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+/// Rolling argmin, the position of the min in the window, `0` being the oldest value.
+/// `get` returns `NaN` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `tie_break` - Which occurrence is reported when the min is in the window several times, see `TieBreak`. Defaults to `TieBreak::First`.
+/// # Examples
+/// ```
+/// use watermill::minimum::TieBreak;
+/// use watermill::minimum::RollingArgMin;
+/// use watermill::stats::Univariate;
+/// let mut rolling_argmin: RollingArgMin<f64> = RollingArgMin::new(4).unwrap();
+/// for x in [1., 5., 1., 3.].iter(){
+///     rolling_argmin.update(*x);
+/// }
+/// assert_eq!(rolling_argmin.get(), 0.);
+/// let mut rolling_argmin: RollingArgMin<f64> = RollingArgMin::with_tie_break(4, TieBreak::Last).unwrap();
+/// for x in [1., 5., 1., 3.].iter(){
+///     rolling_argmin.update(*x);
+/// }
+/// assert_eq!(rolling_argmin.get(), 2.);
+/// assert_eq!(rolling_argmin.argmin_value(), 1.);
+/// ```
+///
+#[derive(Serialize, Deserialize)]
+pub struct RollingArgMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    #[serde(default)]
+    tie_break: TieBreak,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingArgMin<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_tie_break(window_size, TieBreak::First)
+    }
+    pub fn with_tie_break(window_size: usize, tie_break: TieBreak) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size),
+            tie_break,
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+    /// Value of the min, i.e. the value at the position returned by `get`, `NaN` while the window is
+    /// empty.
+    pub fn argmin_value(&self) -> F {
+        if self.sorted_window.is_empty() {
+            return F::nan();
+        }
+        self.sorted_window.front()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingArgMin<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        if self.sorted_window.is_empty() {
+            return F::nan();
+        }
+        let min = self.sorted_window.front();
+        let mut values = self.sorted_window.unsorted_window.iter();
+        let position = match self.tie_break {
+            TieBreak::First => values.position(|x| *x == min),
+            TieBreak::Last => values.rposition(|x| *x == min),
+        };
+        F::from_usize(position.unwrap()).unwrap()
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn arg_min_tie_break_on_a_plateau() {
+        use crate::minimum::{ArgMin, RollingArgMin, TieBreak};
+        use crate::stats::Univariate;
+        let data = [5., 2., 2., 2., 7., 3.];
+        let mut first: ArgMin<f64> = ArgMin::new();
+        let mut last: ArgMin<f64> = ArgMin::with_tie_break(TieBreak::Last);
+        let mut rolling_first: RollingArgMin<f64> = RollingArgMin::new(5).unwrap();
+        let mut rolling_last: RollingArgMin<f64> =
+            RollingArgMin::with_tie_break(5, TieBreak::Last).unwrap();
+        assert!(rolling_first.get().is_nan());
+        for x in data {
+            first.update(x);
+            last.update(x);
+            rolling_first.update(x);
+            rolling_last.update(x);
+        }
+        assert_eq!((first.get(), first.value()), (1., 2.));
+        assert_eq!((last.get(), last.value()), (3., 2.));
+        // The window holds 2, 2, 2, 7, 3.
+        assert_eq!(rolling_first.get(), 0.);
+        assert_eq!(rolling_last.get(), 2.);
+        last.reset();
+        assert_eq!(last.tie_break, TieBreak::Last);
+        let mut integers: ArgMin<i32> = ArgMin::new();
+        for x in [3, -1, 4, -1] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), 1);
+        // The index of the last of 200 equal values does not fit in an `i8`.
+        let mut narrow: ArgMin<i8> = ArgMin::with_tie_break(TieBreak::Last);
+        for _ in 0..200 {
+            narrow.update(-3);
+        }
+        assert_eq!((narrow.index(), narrow.get()), (199, i8::MAX));
+        assert!(RollingArgMin::<f64>::new(0).is_err());
+        assert!(RollingArgMin::<f64>::with_tie_break(0, TieBreak::Last).is_err());
+    }
+    #[test]
+    fn arg_min_reports_the_absolute_stream_index() {
+        use crate::minimum::ArgMin;
+        use crate::stats::Univariate;
+        let data = [9., 8., 7., 6., 5., 4., 3., -1., 2., 0., 1.];
+        let mut running_argmin: ArgMin<f64> = ArgMin::new();
+        assert_eq!(running_argmin.index(), 0);
+        for (i, x) in data.iter().enumerate() {
+            running_argmin.update(*x);
+            assert_eq!(running_argmin.index(), i.min(7));
+        }
+        assert_eq!(running_argmin.get(), 7.);
+        assert_eq!(running_argmin.value(), -1.);
+        running_argmin.reset();
+        running_argmin.update(3.);
+        assert_eq!(running_argmin.index(), 0);
+    }
+    #[test]
+    fn argmin_value_matches_the_rolling_min() {
+        use crate::minimum::{RollingArgMin, RollingMin};
+        use crate::stats::Univariate;
+        let mut rolling_argmin: RollingArgMin<f64> = RollingArgMin::new(4).unwrap();
+        let mut rolling_min: RollingMin<f64> = RollingMin::new(4);
+        assert!(rolling_argmin.argmin_value().is_nan());
+        for i in 0..50 {
+            let x = ((i * 17) % 11) as f64 - 5.;
+            rolling_argmin.update(x);
+            rolling_min.update(x);
+            assert_eq!(rolling_argmin.argmin_value(), rolling_min.get());
+        }
+    }
+    #[test]
+    fn tolerance_near_the_integer_bounds() {
+        use crate::minimum::Min;
+        use crate::stats::Univariate;
+        let mut integers: Min<i32> = Min::with_tolerance(2);
+        for x in [i32::MAX, i32::MAX - 2] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), i32::MAX);
+        integers.update(i32::MAX - 3);
+        assert_eq!(integers.get(), i32::MAX - 3);
+        let mut integers: Min<i32> = Min::with_tolerance(5);
+        for x in [i32::MIN + 3, i32::MIN, 0] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), i32::MIN + 3);
+        let mut integers: Min<i8> = Min::with_tolerance(5);
+        for x in [100, -100, -104, 0] {
+            integers.update(x);
+        }
+        assert_eq!(integers.get(), -100);
+        // Unsigned values smaller than the tolerance.
+        let mut unsigned: Min<u8> = Min::with_tolerance(10);
+        for x in [15, 5, 8, 6] {
+            unsigned.update(x);
+        }
+        assert_eq!(unsigned.get(), 15);
+        unsigned.update(4);
+        assert_eq!(unsigned.get(), 4);
+        assert_eq!(Min::<i32>::default().tolerance, 0);
+    }
+    #[test]
+    fn timed_min_counter_does_not_overflow() {
+        use crate::minimum::TimedMin;
+        use crate::stats::Univariate;
+        let mut timed_min: TimedMin<i8> = TimedMin::new();
+        for i in 0..300 {
+            timed_min.update(if i == 3 { -100 } else { 0 });
+        }
+        assert_eq!((timed_min.get(), timed_min.time()), (-100, 3));
+        timed_min.update(-101);
+        assert_eq!(timed_min.time(), i8::MAX);
+    }
 }
@@ -0,0 +1,204 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::counter::{Counter, RollingCounter};
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Running mode, the most frequent value seen so far.
+/// Ties are broken by returning the smallest value, `get` returns `NaN` before the first update.
+/// # Examples
+/// ```
+/// use watermill::mode::Mode;
+/// use watermill::stats::Univariate;
+/// let mut running_mode: Mode<f64> = Mode::new();
+/// for x in [1., 2., 2., 3., 3., 3.].iter(){
+///     running_mode.update(*x);
+/// }
+/// assert_eq!(running_mode.get(), 3.);
+/// assert_eq!(running_mode.mode_frequency(), 3);
+/// assert_eq!(running_mode.distinct_count(), 3);
+/// assert_eq!(running_mode.mode_ratio(), 0.5);
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Mode<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counter: Counter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mode<F> {
+    pub fn new() -> Self {
+        Self {
+            counter: Counter::new(),
+        }
+    }
+    /// Number of distinct values seen so far.
+    pub fn distinct_count(&self) -> usize {
+        self.counter.len()
+    }
+    /// Number of occurrences of the mode.
+    pub fn mode_frequency(&self) -> usize {
+        self.counter.most_common().map_or(0, |(_, n)| n)
+    }
+    /// Share of the stream taken by the mode, `mode_frequency / n`. It is 1 for a pure stream and
+    /// close to `1 / k` for `k` balanced values, `NaN` before the first update.
+    pub fn mode_ratio(&self) -> F {
+        F::from_usize(self.mode_frequency()).unwrap() / F::from_usize(self.counter.total()).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Mode<F> {
+    fn update(&mut self, x: F) {
+        self.counter.add(x);
+    }
+    fn get(&self) -> F {
+        self.counter.most_common().map_or(F::nan(), |(x, _)| x)
+    }
+    fn reset(&mut self) {
+        self.counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.counter.total())
+    }
+}
+
+/// Rolling mode, the most frequent value of the window.
+/// Ties are broken by returning the smallest value, `get` returns `NaN` before the first update.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::mode::RollingMode;
+/// use watermill::stats::Univariate;
+/// let mut rolling_mode: RollingMode<f64> = RollingMode::new(3).unwrap();
+/// for x in [1., 1., 2., 3., 3.].iter(){
+///     rolling_mode.update(*x);
+/// }
+/// assert_eq!(rolling_mode.get(), 3.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMode<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    rolling_counter: RollingCounter<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMode<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            rolling_counter: RollingCounter::new(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.rolling_counter.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.rolling_counter.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.rolling_counter.is_full()
+    }
+    /// Number of distinct values currently in the window.
+    pub fn distinct_count(&self) -> usize {
+        self.rolling_counter.counter.len()
+    }
+    /// Number of occurrences of the mode in the window.
+    pub fn mode_frequency(&self) -> usize {
+        self.rolling_counter
+            .counter
+            .most_common()
+            .map_or(0, |(_, n)| n)
+    }
+    /// Share of the window taken by the mode, `mode_frequency / len`, `NaN` before the first
+    /// update.
+    pub fn mode_ratio(&self) -> F {
+        F::from_usize(self.mode_frequency()).unwrap() / F::from_usize(self.len()).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMode<F> {
+    fn update(&mut self, x: F) {
+        self.rolling_counter.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.rolling_counter
+            .counter
+            .most_common()
+            .map_or(F::nan(), |(x, _)| x)
+    }
+    fn reset(&mut self) {
+        self.rolling_counter.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.rolling_counter.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn rolling_distinct_count_and_mode_frequency() {
+        use crate::mode::RollingMode;
+        use crate::stats::Univariate;
+        assert!(RollingMode::<f64>::new(0).is_err());
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(5).unwrap();
+        assert!(rolling_mode.get().is_nan());
+        assert_eq!(rolling_mode.distinct_count(), 0);
+        assert_eq!(rolling_mode.mode_frequency(), 0);
+        let expected = [
+            (4., 4., 1, 1),
+            (2., 2., 2, 1),
+            (4., 4., 2, 2),
+            (7., 4., 3, 2),
+            (7., 4., 3, 2),
+            // The first 4 leaves the window.
+            (7., 7., 3, 3),
+            (1., 7., 3, 3),
+            (1., 7., 2, 3),
+        ];
+        for (x, mode, distinct, frequency) in expected {
+            rolling_mode.update(x);
+            assert_eq!(rolling_mode.get(), mode);
+            assert_eq!(rolling_mode.distinct_count(), distinct);
+            assert_eq!(rolling_mode.mode_frequency(), frequency);
+        }
+    }
+
+    #[test]
+    fn mode_ratio_of_an_imbalanced_stream() {
+        use crate::mode::{Mode, RollingMode};
+        use crate::stats::Univariate;
+        let mut running_mode: Mode<f64> = Mode::new();
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(50).unwrap();
+        assert!(running_mode.mode_ratio().is_nan());
+        assert!(rolling_mode.mode_ratio().is_nan());
+        // One minority value every 100, then the window only sees the majority class.
+        for i in 0..1000 {
+            let x = if i % 100 == 0 && i < 900 { 1. } else { 0. };
+            running_mode.update(x);
+            rolling_mode.update(x);
+        }
+        assert_eq!(running_mode.get(), 0.);
+        assert!((running_mode.mode_ratio() - 0.991).abs() < 1e-12);
+        assert_eq!(rolling_mode.mode_ratio(), 1.);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        use crate::mode::RollingMode;
+        use crate::stats::Univariate;
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(3).unwrap();
+        for x in [0.5, 1.5, 1.5] {
+            rolling_mode.update(x);
+        }
+        let serialized = serde_json::to_string(&rolling_mode).unwrap();
+        let mut deserialized: RollingMode<f64> = serde_json::from_str(&serialized).unwrap();
+        deserialized.update(0.5);
+        assert_eq!(deserialized.get(), 1.5);
+        deserialized.update(0.5);
+        assert_eq!(deserialized.get(), 0.5);
+        assert_eq!(deserialized.distinct_count(), 2);
+    }
+}
@@ -60,4 +60,166 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> CentralMoments<F> {
             + F::from_f64(6.).unwrap() * delta_square * self.m2
             - F::from_f64(4.).unwrap() * self.delta * self.m3
     }
+    /// Runs the whole update pipeline, up to the fourth moment.
+    pub fn update(&mut self, x: F) {
+        self.count.update(x);
+        self.update_delta(x);
+        self.update_m1(x);
+        self.update_sum_delta();
+        self.update_m4();
+        self.update_m3();
+        self.update_m2();
+    }
+    /// Skewness of the values seen so far, see `skew::Skew`.
+    pub fn skewness(&self, bias: bool) -> F {
+        let n = self.count.get();
+        let mut skew: F = F::from_f64(0.).unwrap();
+        if self.m2 != F::from_f64(0.).unwrap() {
+            skew += n.powf(F::from_f64(0.5).unwrap()) * self.m3
+                / self.m2.powf(F::from_f64(1.5).unwrap());
+        }
+        if (!bias) && n > F::from_f64(2.).unwrap() {
+            return ((n - F::from_f64(1.).unwrap()) * n).powf(F::from_f64(0.5).unwrap())
+                / (n - F::from_f64(2.).unwrap())
+                * skew;
+        }
+        skew
+    }
+    /// Excess kurtosis of the values seen so far, see `kurtosis::Kurtosis`.
+    pub fn kurtosis(&self, bias: bool) -> F {
+        let n = self.count.get();
+        let mut kurtosis: F = F::from_f64(0.).unwrap();
+        if self.m2 != F::from_f64(0.).unwrap() {
+            kurtosis += n * self.m4 / self.m2.powf(F::from_f64(2.).unwrap());
+        }
+        if (!bias) && n > F::from_f64(3.).unwrap() {
+            return F::from_f64(1.).unwrap()
+                / (n - F::from_f64(2.).unwrap())
+                / (n - F::from_f64(3.).unwrap())
+                * ((n.powf(F::from_f64(2.).unwrap()) - F::from_f64(1.).unwrap()) * kurtosis
+                    - F::from_f64(3.).unwrap()
+                        * (n - F::from_f64(1.).unwrap()).powf(F::from_f64(2.).unwrap()));
+        }
+        kurtosis - F::from_f64(3.).unwrap()
+    }
+}
+
+/// Mean, variance, skewness and kurtosis of a stream from a single set of central moments, each
+/// value goes through the update pipeline once. `get` returns the mean.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom of the variance. The divisor used in calculations is `n - ddof`.
+/// # Examples
+/// ```
+/// use watermill::moments::MomentSummary;
+/// use watermill::stats::Univariate;
+/// let mut summary: MomentSummary<f64> = MomentSummary::default();
+/// for x in [1., 2., 3., 4., 10.].iter(){
+///     summary.update(*x);
+/// }
+/// assert_eq!(summary.mean(), 4.);
+/// assert!((summary.variance() - 12.5).abs() < 1e-12);
+/// assert!(summary.skewness(false) > 0.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MomentSummary<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub central_moments: CentralMoments<F>,
+    pub ddof: u32,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MomentSummary<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            central_moments: CentralMoments::new(),
+            ddof,
+        }
+    }
+    /// Summary with the population variance, `ddof = 0`.
+    pub fn population() -> Self {
+        Self::new(0)
+    }
+    /// Summary with the sample variance, `ddof = 1`. Same as `MomentSummary::default`.
+    pub fn sample() -> Self {
+        Self::new(1)
+    }
+    pub fn mean(&self) -> F {
+        self.central_moments.sum_delta
+    }
+    /// Variance with the `ddof` of the summary, 0 until more than `ddof` values are seen.
+    pub fn variance(&self) -> F {
+        let n = self.central_moments.count.get();
+        let ddof = F::from_u32(self.ddof).unwrap();
+        if n > ddof {
+            return self.central_moments.m2 / (n - ddof);
+        }
+        F::from_f64(0.).unwrap()
+    }
+    pub fn skewness(&self, bias: bool) -> F {
+        self.central_moments.skewness(bias)
+    }
+    pub fn kurtosis(&self, bias: bool) -> F {
+        self.central_moments.kurtosis(bias)
+    }
+}
+
+impl<F> Default for MomentSummary<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for MomentSummary<F> {
+    fn update(&mut self, x: F) {
+        self.central_moments.update(x);
+    }
+    fn get(&self) -> F {
+        self.mean()
+    }
+    fn reset(&mut self) {
+        *self = Self::new(self.ddof);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.central_moments.count.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn summary_matches_the_standalone_statistics() {
+        use crate::kurtosis::Kurtosis;
+        use crate::mean::Mean;
+        use crate::moments::MomentSummary;
+        use crate::skew::Skew;
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        let mut summary: MomentSummary<f64> = MomentSummary::new(0);
+        let mut mean: Mean<f64> = Mean::new();
+        let mut variance: Variance<f64> = Variance::new(0);
+        let mut skew: [Skew<f64>; 2] = [Skew::new(false), Skew::new(true)];
+        let mut kurtosis: [Kurtosis<f64>; 2] = [Kurtosis::new(false), Kurtosis::new(true)];
+        for i in 0..100 {
+            let x = ((i * 37) % 19) as f64 + 0.01 * (i * i) as f64;
+            summary.update(x);
+            mean.update(x);
+            variance.update(x);
+            for stat in skew.iter_mut() {
+                stat.update(x);
+            }
+            for stat in kurtosis.iter_mut() {
+                stat.update(x);
+            }
+            assert!((summary.mean() - mean.get()).abs() < 1e-9);
+            assert!((summary.variance() - variance.get()).abs() < 1e-9);
+            for (bias, (skew, kurtosis)) in [false, true].iter().zip(skew.iter().zip(&kurtosis)) {
+                assert!((summary.skewness(*bias) - skew.get()).abs() < 1e-9);
+                assert!((summary.kurtosis(*bias) - kurtosis.get()).abs() < 1e-9);
+            }
+        }
+        let serialized = serde_json::to_string(&summary).unwrap();
+        let deserialized: MomentSummary<f64> = serde_json::from_str(&serialized).unwrap();
+        assert!((deserialized.kurtosis(false) - summary.kurtosis(false)).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,166 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+
+/// Error function, with the Maclaurin series for `|x| < 3` and the continued fraction of `erfc`
+/// beyond. The absolute error is below `1e-13` and `erf(0)` is exactly `0`.
+pub(crate) fn erf<F: Float + FromPrimitive>(x: F) -> F {
+    let a = x.abs();
+    if a < F::from_f64(3.).unwrap() {
+        let mut term = x;
+        let mut sum = x;
+        for n in 1..200 {
+            term = term * -x * x / F::from_usize(n).unwrap();
+            let delta = term / F::from_usize(2 * n + 1).unwrap();
+            sum = sum + delta;
+            if delta.abs() <= F::epsilon() * sum.abs() {
+                break;
+            }
+        }
+        return F::from_f64(std::f64::consts::FRAC_2_SQRT_PI).unwrap() * sum;
+    }
+    let mut fraction = a;
+    for k in (1..=60).rev() {
+        fraction = a + F::from_f64(k as f64 / 2.).unwrap() / fraction;
+    }
+    let erfc = (-a * a).exp() / (F::from_f64(std::f64::consts::PI).unwrap().sqrt() * fraction);
+    (F::from_f64(1.).unwrap() - erfc).copysign(x)
+}
+
+/// Normal distribution fitted to the stream, with the running mean and standard deviation as
+/// parameters. `get` returns the mean, `pdf` and `cdf` are `NaN` while the standard deviation is 0.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom of the variance. Defaults to `1`.
+/// # Examples
+/// ```
+/// use watermill::normal::NormalSummary;
+/// use watermill::stats::Univariate;
+/// let mut normal: NormalSummary<f64> = NormalSummary::default();
+/// for x in [2., 4., 4., 4., 5., 5., 7., 9.].iter(){
+///     normal.update(*x);
+/// }
+/// let (mean, std) = normal.params();
+/// assert_eq!(mean, 5.);
+/// assert_eq!(normal.cdf(mean), 0.5);
+/// assert!((normal.cdf(mean + std) - 0.8413447460685429).abs() < 1e-12);
+/// assert!((normal.pdf(mean) * std - 0.3989422804014327).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the normal distribution](https://en.wikipedia.org/wiki/Normal_distribution)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NormalSummary<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> NormalSummary<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+        }
+    }
+    /// Normal distribution fitted with the population standard deviation, `ddof = 0`.
+    pub fn population() -> Self {
+        Self::new(0)
+    }
+    /// Normal distribution fitted with the sample standard deviation, `ddof = 1`. Same as
+    /// `NormalSummary::default`.
+    pub fn sample() -> Self {
+        Self::new(1)
+    }
+    /// Running mean and standard deviation.
+    pub fn params(&self) -> (F, F) {
+        (self.variance.mean.get(), self.variance.get().sqrt())
+    }
+    /// Probability density function of the fitted normal distribution.
+    pub fn pdf(&self, x: F) -> F {
+        let (mean, std) = self.params();
+        if std == F::from_f64(0.).unwrap() {
+            return F::nan();
+        }
+        let z = (x - mean) / std;
+        (-z * z / F::from_f64(2.).unwrap()).exp()
+            / (std * F::from_f64(2. * std::f64::consts::PI).unwrap().sqrt())
+    }
+    /// Cumulative distribution function of the fitted normal distribution.
+    pub fn cdf(&self, x: F) -> F {
+        let (mean, std) = self.params();
+        if std == F::from_f64(0.).unwrap() {
+            return F::nan();
+        }
+        let z = (x - mean) / (std * F::from_f64(2.).unwrap().sqrt());
+        (F::from_f64(1.).unwrap() + erf(z)) / F::from_f64(2.).unwrap()
+    }
+}
+
+impl<F> Default for NormalSummary<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for NormalSummary<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.variance.mean.get()
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for NormalSummary<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.variance.revert(x)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for NormalSummary<F> {}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn erf_matches_reference_values() {
+        use crate::normal::erf;
+        // math.erf in Python.
+        for (x, expected) in [
+            (0., 0.),
+            (0.1, 0.1124629160182849),
+            (-0.5, -0.5204998778130465),
+            (1., 0.8427007929497149),
+            (2.5, 0.999593047982555),
+            (3., 0.9999779095030014),
+            (-4.2, -0.9999999971445058),
+        ] {
+            assert!((erf::<f64>(x) - expected).abs() < 1e-13);
+        }
+        assert!((erf(0.5_f32) - 0.5204999).abs() < 1e-6);
+    }
+    #[test]
+    fn population_and_sample() {
+        use crate::normal::NormalSummary;
+        use crate::stats::Univariate;
+        let mut population: NormalSummary<f64> = NormalSummary::population();
+        let mut sample: NormalSummary<f64> = NormalSummary::sample();
+        for x in [2., 4., 4., 4., 5., 5., 7., 9.] {
+            population.update(x);
+            sample.update(x);
+        }
+        assert_eq!(population.params(), (5., 2.));
+        assert_eq!(sample.params().1, (32_f64 / 7.).sqrt());
+        assert_eq!(
+            sample.variance.ddof,
+            NormalSummary::<f64>::default().variance.ddof
+        );
+    }
+}
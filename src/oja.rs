@@ -0,0 +1,119 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::mean::Mean;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Online estimate of the first principal component using Oja's rule.
+/// Observations are centered with running means, then the unit-norm weight vector `w` is moved toward
+/// the direction of largest variance: `w += learning_rate * y * (x - y * w)` with `y = w·x`.
+/// The weight vector starts at `1 / sqrt(dim)` on every axis.
+/// # Arguments
+/// * `dim` - Number of variables.
+/// * `learning_rate` - Step size of each update, small values are slower but more stable.
+/// # Examples
+/// ```
+/// use watermill::oja::OjaPCA;
+/// let mut pca: OjaPCA<f64> = OjaPCA::new(2, 0.01).unwrap();
+/// for i in 0..2000{
+///     let t = ((i * 37) % 101) as f64 / 10. - 5.;
+///     pca.update(&[t, 0.5 * t]).unwrap();
+/// }
+/// let component = pca.component();
+/// assert!((component[0] - 2. / 5_f64.sqrt()).abs() < 1e-6);
+/// assert!((component[1] - 1. / 5_f64.sqrt()).abs() < 1e-6);
+/// ```
+/// # References
+/// [^1]: [Oja, E., 1982. Simplified neuron model as a principal component analyzer. Journal of mathematical biology, 15(3), pp.267-273.](https://link.springer.com/article/10.1007/BF00275687)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OjaPCA<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub dim: usize,
+    pub learning_rate: F,
+    pub means: Vec<Mean<F>>,
+    weights: Vec<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> OjaPCA<F> {
+    pub fn new(dim: usize, learning_rate: F) -> Result<Self, &'static str> {
+        if dim == 0 {
+            return Err("dim should be greater than 0");
+        }
+        if !(learning_rate > F::from_f64(0.).unwrap() && learning_rate.is_finite()) {
+            return Err("learning_rate should be a positive number");
+        }
+        Ok(Self {
+            dim,
+            learning_rate,
+            means: vec![Mean::new(); dim],
+            weights: vec![F::from_usize(dim).unwrap().sqrt().recip(); dim],
+        })
+    }
+    pub fn update(&mut self, x: &[F]) -> Result<(), &'static str> {
+        if x.len() != self.dim {
+            return Err("x should have dim values");
+        }
+        for (mean, xi) in self.means.iter_mut().zip(x.iter()) {
+            mean.update(*xi);
+        }
+        let centered: Vec<F> = x
+            .iter()
+            .zip(self.means.iter())
+            .map(|(xi, mean)| *xi - mean.get())
+            .collect();
+        let y = centered
+            .iter()
+            .zip(self.weights.iter())
+            .fold(F::from_f64(0.).unwrap(), |acc, (xi, wi)| acc + *xi * *wi);
+        for (wi, xi) in self.weights.iter_mut().zip(centered.iter()) {
+            *wi += self.learning_rate * y * (*xi - y * *wi);
+        }
+        // Oja's rule only keeps the norm close to 1, renormalize to avoid drifting.
+        let norm = self
+            .weights
+            .iter()
+            .fold(F::from_f64(0.).unwrap(), |acc, wi| acc + *wi * *wi)
+            .sqrt();
+        if norm > F::from_f64(0.).unwrap() {
+            for wi in self.weights.iter_mut() {
+                *wi = *wi / norm;
+            }
+        }
+        Ok(())
+    }
+    /// Current unit-norm estimate of the first principal component.
+    pub fn component(&self) -> Vec<F> {
+        self.weights.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn aligns_with_dominant_axis() {
+        use crate::oja::OjaPCA;
+        use crate::stats::uniform;
+        let axis = [0.8, -0.6, 0.];
+        let mut uniform = {
+            let mut uniform = uniform(42);
+            move || uniform() - 0.5
+        };
+        assert!(OjaPCA::<f64>::new(0, 0.005).is_err());
+        assert!(OjaPCA::<f64>::new(3, 0.).is_err());
+        assert!(OjaPCA::<f64>::new(3, -0.005).is_err());
+        assert!(OjaPCA::<f64>::new(3, f64::NAN).is_err());
+        let mut pca: OjaPCA<f64> = OjaPCA::new(3, 0.005).unwrap();
+        for _ in 0..20_000 {
+            let t = 10. * uniform();
+            let x: Vec<f64> = axis.iter().map(|a| 5. + a * t + 0.2 * uniform()).collect();
+            pca.update(&x).unwrap();
+        }
+        let cosine: f64 = pca
+            .component()
+            .iter()
+            .zip(axis.iter())
+            .map(|(w, a)| w * a)
+            .sum();
+        assert!(cosine.abs() > 0.999);
+        assert!(pca.update(&[1., 2.]).is_err());
+    }
+}
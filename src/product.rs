@@ -0,0 +1,161 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::rolling::RollingOwned;
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use serde::{Deserialize, Serialize};
+/// Running product.
+/// Zeros are counted instead of being multiplied in: the product is `0` while at least one zero is part
+/// of it, and reverting a zero removes it from the count. Other values are reverted by division.
+/// # Examples
+/// ```
+/// use watermill::stats::{Univariate, Revertable};
+/// use watermill::product::Product;
+/// let mut running_product: Product<f64> = Product::new();
+/// for i in 1..6{
+///     running_product.update(i as f64);
+/// }
+/// assert_eq!(running_product.get(), 120.0);
+///
+/// // You can revert the product
+/// running_product.revert(5.);
+/// assert_eq!(running_product.get(), 24.0);
+/// ```
+/// Rolling compounded returns:
+/// ```
+/// use watermill::product::Product;
+/// use watermill::rolling::Rolling;
+/// use watermill::stats::Univariate;
+/// let growth = vec![1.5, 0.5, 2., 1.25];
+/// let mut running_product: Product<f64> = Product::new();
+/// let mut rolling_product: Rolling<f64> = Rolling::new(&mut running_product, 2).unwrap();
+/// for x in growth.iter(){
+///     rolling_product.update(*x);
+/// }
+/// assert_eq!(rolling_product.get(), 2.5);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Product<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Product of the non-zero values.
+    pub product: F,
+    pub n_zeros: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Product<F> {
+    pub fn new() -> Self {
+        Self {
+            product: F::from_f64(1.).unwrap(),
+            n_zeros: 0,
+        }
+    }
+}
+
+impl<F> Default for Product<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Product<F> {
+    fn update(&mut self, x: F) {
+        if x == F::from_f64(0.).unwrap() {
+            self.n_zeros += 1;
+        } else {
+            self.product = self.product * x;
+        }
+    }
+    fn get(&self) -> F {
+        if self.n_zeros > 0 {
+            return F::from_f64(0.).unwrap();
+        }
+        self.product
+    }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Product<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        if x == F::from_f64(0.).unwrap() {
+            if self.n_zeros == 0 {
+                return Err("No zero to revert");
+            }
+            self.n_zeros -= 1;
+        } else {
+            self.product = self.product / x;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Product<F> {}
+
+/// Rolling product over the last `window_size` values, see `rolling::RollingOwned`.
+/// Evicted values are divided out, and a zero only counts while it is in the window so the
+/// product recovers exactly once the zero leaves.
+/// # Examples
+/// ```
+/// use watermill::product::{Product, RollingProduct};
+/// use watermill::stats::Univariate;
+/// let mut rolling_product: RollingProduct<f64> = RollingProduct::new(Product::new(), 2).unwrap();
+/// for x in [1.5, 0., 2., 1.25].iter(){
+///     rolling_product.update(*x);
+/// }
+/// assert_eq!(rolling_product.get(), 2.5);
+/// ```
+pub type RollingProduct<F> = RollingOwned<F, Product<F>>;
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn zero_enters_and_leaves() {
+        use crate::product::Product;
+        use crate::stats::{Revertable, Univariate};
+        let mut running_product: Product<f64> = Product::new();
+        for x in [2., 0., 3., 0.] {
+            running_product.update(x);
+        }
+        assert_eq!(running_product.get(), 0.);
+        running_product.revert(0.).unwrap();
+        assert_eq!(running_product.get(), 0.);
+        running_product.revert(0.).unwrap();
+        assert_eq!(running_product.get(), 6.);
+        assert!(running_product.revert(0.).is_err());
+    }
+
+    #[test]
+    fn rolling_product() {
+        use crate::product::Product;
+        use crate::rolling::windowed;
+        let data = [1.5, 2., 0., 4., 0.5, 3., 0., 0., 2., 1.25];
+        let rolled = windowed(&data, 3, Product::new());
+        for (value, window) in rolled.iter().zip(data.windows(3)) {
+            assert_eq!(*value, window.iter().product::<f64>());
+        }
+    }
+    #[test]
+    fn rolling_product_with_zero_transit() {
+        use crate::product::{Product, RollingProduct};
+        use crate::stats::Univariate;
+        let data = [1.1, 0.9, 0., 1.2, 1.05, 0.95, 1.3, 0., 0., 0.8, 1.1, 1.02];
+        let window_size = 3;
+        let mut rolling_product: RollingProduct<f64> =
+            RollingProduct::new(Product::new(), window_size).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_product.update(*x);
+            let start = (i + 1).saturating_sub(window_size);
+            let expected: f64 = data[start..=i].iter().product();
+            if expected == 0. {
+                assert_eq!(rolling_product.get(), 0.);
+            } else {
+                assert!((rolling_product.get() / expected - 1.).abs() < 1e-12);
+            }
+        }
+        // Once the zeros have left, nothing of them remains in the product.
+        assert!((rolling_product.get() - 0.8 * 1.1 * 1.02).abs() < 1e-12);
+    }
+}
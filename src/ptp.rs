@@ -1,7 +1,7 @@
 use crate::maximum::{Max, RollingMax};
 use crate::minimum::{Min, RollingMin};
 use crate::stats::Univariate;
-use num::{Float, FromPrimitive};
+use num::{Bounded, Float, FromPrimitive, Num};
 use serde::{Deserialize, Serialize};
 use std::ops::{AddAssign, SubAssign};
 /// Running peak to peak (max - min).
@@ -17,21 +17,29 @@ use std::ops::{AddAssign, SubAssign};
 /// ```
 ///
 #[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
-pub struct PeakToPeak<F: Float + FromPrimitive + AddAssign + SubAssign> {
+pub struct PeakToPeak<F: Num + Copy + PartialOrd + Bounded> {
     pub min: Min<F>,
     pub max: Max<F>,
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> PeakToPeak<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> PeakToPeak<F> {
     pub fn new() -> Self {
         Self {
             min: Min::new(),
             max: Max::new(),
         }
     }
+    /// Peak to peak of a `Min` and a `Max` built with `with_tolerance`, fluctuations smaller than
+    /// `tolerance` do not widen the range.
+    pub fn with_tolerance(tolerance: F) -> Self {
+        Self {
+            min: Min::with_tolerance(tolerance),
+            max: Max::with_tolerance(tolerance),
+        }
+    }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PeakToPeak<F> {
+impl<F: Num + Copy + PartialOrd + Bounded> Univariate<F> for PeakToPeak<F> {
     fn update(&mut self, x: F) {
         self.min.update(x);
         self.max.update(x);
@@ -39,6 +47,72 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PeakToP
     fn get(&self) -> F {
         self.max.get() - self.min.get()
     }
+    fn reset(&mut self) {
+        self.min.reset();
+        self.max.reset();
+    }
+}
+
+/// Running range, the bounds of the values seen so far. Unlike `PeakToPeak` it keeps the bounds
+/// and not only the span, `get` returns the span `max - min`. The accessors are meaningless
+/// before the first update.
+/// # Examples
+/// ```
+/// use watermill::ptp::Range;
+/// use watermill::stats::Univariate;
+/// let mut running_range: Range<f64> = Range::new();
+/// for x in [4., -2., 7., 1.].iter(){
+///     running_range.update(*x);
+/// }
+/// assert_eq!(running_range.min(), -2.);
+/// assert_eq!(running_range.max(), 7.);
+/// assert_eq!(running_range.span(), 9.);
+/// assert_eq!(running_range.midrange(), 2.5);
+/// assert_eq!(running_range.get(), 9.);
+/// ```
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Range<F: Num + Copy + PartialOrd + Bounded> {
+    pub running_min: Min<F>,
+    pub running_max: Max<F>,
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> Range<F> {
+    pub fn new() -> Self {
+        Self {
+            running_min: Min::new(),
+            running_max: Max::new(),
+        }
+    }
+    /// Smallest value seen so far.
+    pub fn min(&self) -> F {
+        self.running_min.get()
+    }
+    /// Largest value seen so far.
+    pub fn max(&self) -> F {
+        self.running_max.get()
+    }
+    /// `max - min`.
+    pub fn span(&self) -> F {
+        self.max() - self.min()
+    }
+    /// `(min + max) / 2`, rounded towards 0 for integers.
+    pub fn midrange(&self) -> F {
+        (self.min() + self.max()) / (F::one() + F::one())
+    }
+}
+
+impl<F: Num + Copy + PartialOrd + Bounded> Univariate<F> for Range<F> {
+    fn update(&mut self, x: F) {
+        self.running_min.update(x);
+        self.running_max.update(x);
+    }
+    fn get(&self) -> F {
+        self.span()
+    }
+    fn reset(&mut self) {
+        self.running_min.reset();
+        self.running_max.reset();
+    }
 }
 
 /// Rolling peak to peak (max - min).
@@ -68,6 +142,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingPeakToPeak<F> {
             max: RollingMax::new(window_size),
         }
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.max.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.max.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.max.is_full()
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingPeakToPeak<F> {
@@ -78,4 +164,11 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.max.get() - self.min.get()
     }
+    fn reset(&mut self) {
+        self.min.reset();
+        self.max.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.min.n_samples()
+    }
 }
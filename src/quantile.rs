@@ -2,7 +2,7 @@ use crate::sorted_window::SortedWindow;
 use num::{Float, FromPrimitive, ToPrimitive};
 use std::ops::{AddAssign, SubAssign};
 
-use crate::stats::Univariate;
+use crate::stats::{fold, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running quantile estimator using P-square Algorithm.
 /// # Arguments
@@ -59,6 +59,40 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Quantile<F> {
             heights_sorted: false,
         })
     }
+    /// Estimate of the `q` quantile of the values of `data`.
+    /// # Examples
+    /// ```
+    /// use watermill::quantile::Quantile;
+    /// use watermill::stats::Univariate;
+    /// let data: Vec<f64> = (0..=100).map(|i| i as f64).collect();
+    /// let median: Quantile<f64> = Quantile::fold(&data, 0.5).unwrap();
+    /// assert_eq!(median.get(), 50.);
+    /// ```
+    pub fn fold(data: &[F], q: F) -> Result<Self, &'static str> {
+        Ok(fold(Self::new(q)?, data))
+    }
+    /// Positions and heights of the five P² markers, for diagnostics. The positions are the
+    /// ranks of the markers among the values seen so far, starting at 1, and the heights are
+    /// their estimated values, the third one being the quantile. Until five values have been seen
+    /// the heights are the raw values, in arrival order.
+    /// # Examples
+    /// ```
+    /// use watermill::quantile::Quantile;
+    /// use watermill::stats::Univariate;
+    /// let mut running_quantile: Quantile<f64> = Quantile::new(0.5).unwrap();
+    /// for i in 0..100{
+    ///     running_quantile.update(((i * 37) % 101) as f64);
+    /// }
+    /// let (positions, heights) = running_quantile.markers();
+    /// assert_eq!(positions.len(), 5);
+    /// assert_eq!(positions[4], 100.);
+    /// assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    /// assert!(heights.windows(2).all(|w| w[0] <= w[1]));
+    /// assert_eq!(heights[2], running_quantile.get());
+    /// ```
+    pub fn markers(&self) -> (&[F], &[F]) {
+        (&self.position, &self.heights)
+    }
     fn find_k(&mut self, x: F) -> usize {
         let mut k: Option<usize> = None;
         if x < self.heights[0] {
@@ -87,6 +121,67 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Quantile<F> {
         q + outer * (inner_left + inner_right)
     }
 
+    /// Merges another estimator of the same quantile into this one, e.g. to aggregate estimators
+    /// fed by different shards of a stream.
+    ///
+    /// P² markers cannot be merged exactly. Once both estimators hold their five markers, the
+    /// extreme markers keep the global min and max, the inner heights are averaged with weights
+    /// proportional to the number of observations of each estimator, and the marker positions are
+    /// summed, the rank of a value in the union being the sum of its ranks in both parts. The
+    /// result is therefore an approximation which is only good if both shards come from similar
+    /// distributions. An estimator which has seen less than five values is merged exactly by
+    /// replaying its values.
+    /// # Examples
+    /// ```
+    /// use watermill::quantile::Quantile;
+    /// use watermill::stats::Univariate;
+    /// let mut left: Quantile<f64> = Quantile::new(0.5).unwrap();
+    /// let mut right: Quantile<f64> = Quantile::new(0.5).unwrap();
+    /// for i in 0..100 {
+    ///     left.update((2 * i) as f64);
+    ///     right.update((2 * i + 1) as f64);
+    /// }
+    /// left.merge(&right).unwrap();
+    /// assert!((left.get() - 99.5).abs() < 5.);
+    /// ```
+    pub fn merge(&mut self, other: &Self) -> Result<(), &'static str> {
+        if self.q != other.q {
+            return Err("Only estimators of the same quantile can be merged");
+        }
+        if other.heights.len() < 5 {
+            for x in other.heights.iter() {
+                self.update(*x);
+            }
+            return Ok(());
+        }
+        if self.heights.len() < 5 {
+            let heights = std::mem::replace(self, other.clone()).heights;
+            for x in heights {
+                self.update(x);
+            }
+            return Ok(());
+        }
+        let n_self = self.position[4];
+        let n_other = other.position[4];
+        let n = n_self + n_other;
+        self.heights[0] = self.heights[0].min(other.heights[0]);
+        self.heights[4] = self.heights[4].max(other.heights[4]);
+        for i in 1..4 {
+            self.heights[i] = (n_self * self.heights[i] + n_other * other.heights[i]) / n;
+            self.position[i] += other.position[i];
+        }
+        self.position[4] = n;
+        for (marker, desired_marker) in self
+            .marker_position
+            .iter_mut()
+            .zip(self.desired_marker_position.iter())
+        {
+            *marker = F::from_f64(1.).unwrap() + (n - F::from_f64(1.).unwrap()) * *desired_marker;
+        }
+        self.heights_sorted = true;
+        Ok(())
+    }
+
     fn adjust(&mut self) {
         for i in 1..4 {
             let n = self.position[i];
@@ -196,12 +291,145 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantil
             self.heights[index]
         }
     }
+    fn reset(&mut self) {
+        *self = Self::new(self.q).unwrap();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        if self.heights.len() < 5 {
+            Some(self.heights.len())
+        } else {
+            self.position[4].to_usize()
+        }
+    }
+}
+
+/// Running quantile estimator which feeds `Quantile` by sorted batches.
+/// Values are buffered until `buffer_size` of them have been seen, the batch is then sorted and
+/// flushed into an inner P² estimator. The first batch initialises the five P² markers with the
+/// batch quantiles they stand for `0`, `q/2`, `q`, `(1+q)/2` and `1`, instead of the first five
+/// values of the stream, which makes the estimate much less sensitive to unrepresentative early
+/// values. The other values of each batch are fed in bit-reversed order of their rank, so every
+/// prefix of the batch spans its whole range. The memory is `O(buffer_size)`, a larger buffer
+/// gives a better initialisation.
+///
+/// Until the first batch is full `get` returns the exact quantile of the buffered values, then it
+/// returns the estimate of the inner `Quantile`, which does not include the values of the
+/// partially filled buffer.
+/// # Arguments
+/// * `q` - quantile value, must be between `0` and `1`.
+/// * `buffer_size` - Number of values per batch, at least `5`.
+/// # Examples
+/// ```
+/// use watermill::quantile::BufferedQuantile;
+/// use watermill::stats::Univariate;
+/// let mut buffered_quantile: BufferedQuantile<f64> = BufferedQuantile::new(0.5, 16).unwrap();
+/// for i in 0..=100{
+///     buffered_quantile.update(((i * 37) % 101) as f64);
+/// }
+/// assert!((buffered_quantile.get() - 50.).abs() < 3.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BufferedQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    quantile: Quantile<F>,
+    buffer: Vec<F>,
+    buffer_size: usize,
+    flushed: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> BufferedQuantile<F> {
+    pub fn new(q: F, buffer_size: usize) -> Result<Self, &'static str> {
+        if buffer_size < 5 {
+            return Err("buffer_size should be at least 5");
+        }
+        Ok(Self {
+            quantile: Quantile::new(q)?,
+            buffer: Vec::with_capacity(buffer_size),
+            buffer_size,
+            flushed: false,
+        })
+    }
+    fn flush(&mut self) {
+        self.buffer.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        let last = self.buffer.len() - 1;
+        let mut fed = vec![false; self.buffer.len()];
+        if !self.flushed {
+            for p in self.quantile.desired_marker_position.clone() {
+                let rank = (p * F::from_usize(last).unwrap())
+                    .round()
+                    .to_usize()
+                    .unwrap();
+                // Two markers can only share a rank when the buffer is tiny, use the next free one.
+                let rank = (rank..=last).chain(0..rank).find(|r| !fed[*r]).unwrap();
+                fed[rank] = true;
+                self.quantile.update(self.buffer[rank]);
+            }
+            self.flushed = true;
+        }
+        let bits = usize::BITS - last.leading_zeros();
+        for i in 0..(1_usize << bits) {
+            let rank = i.reverse_bits() >> (usize::BITS - bits);
+            if rank <= last && !fed[rank] {
+                self.quantile.update(self.buffer[rank]);
+            }
+        }
+        self.buffer.clear();
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for BufferedQuantile<F> {
+    fn update(&mut self, x: F) {
+        self.buffer.push(x);
+        if self.buffer.len() == self.buffer_size {
+            self.flush();
+        }
+    }
+    fn get(&self) -> F {
+        if self.flushed {
+            return self.quantile.get();
+        }
+        if self.buffer.is_empty() {
+            return F::nan();
+        }
+        let mut sorted = self.buffer.clone();
+        sorted.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        let index = self.quantile.q * F::from_usize(sorted.len() - 1).unwrap();
+        let lower = index.floor().to_usize().unwrap();
+        let higher = (lower + 1).min(sorted.len() - 1);
+        let frac = index - F::from_usize(lower).unwrap();
+        sorted[lower] + (sorted[higher] - sorted[lower]) * frac
+    }
+    fn reset(&mut self) {
+        self.quantile.reset();
+        self.buffer.clear();
+        self.flushed = false;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.quantile.n_samples().map(|n| n + self.buffer.len())
+    }
+}
+
+/// Interpolation rule used by `RollingQuantile` when the desired quantile lies between two
+/// values `i < j` of the window. Names and semantics follow NumPy's `quantile`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterpolationMethod {
+    /// `i + (j - i) * fraction`.
+    #[default]
+    Linear,
+    /// `i`.
+    Lower,
+    /// `j`.
+    Higher,
+    /// `i` or `j` whichever is nearest, ties go to the even index.
+    Nearest,
+    /// `(i + j) / 2`.
+    Midpoint,
 }
 
 /// Rolling quantile.
 /// # Arguments
 /// * `q` - quantile value. **WARNING** Should between `0` and `1`.
 /// * `window_size` - Size of the rolling window.
+/// * `method` - Interpolation rule, see `InterpolationMethod`. Defaults to `InterpolationMethod::Linear`.
 /// # Examples
 /// ```
 /// use watermill::quantile::RollingQuantile;
@@ -214,12 +442,25 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantil
 /// }
 /// assert_eq!(rolling_quantile.get(), 50.0);
 /// ```
+/// The interpolation rule used when the desired quantile lies between two values can be chosen:
+/// ```
+/// use watermill::quantile::{InterpolationMethod, RollingQuantile};
+/// use watermill::stats::Univariate;
+/// let mut rolling_quantile: RollingQuantile<f64> =
+///     RollingQuantile::with_method(0.5_f64, 4, InterpolationMethod::Lower).unwrap();
+/// for i in 0..4{
+///     rolling_quantile.update(i as f64);
+/// }
+/// assert_eq!(rolling_quantile.get(), 1.0);
+/// ```
 ///
 
 #[derive(Serialize, Deserialize)]
 pub struct RollingQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
     sorted_window: SortedWindow<F>,
     q: F,
+    #[serde(default)]
+    method: InterpolationMethod,
     window_size: usize,
     lower: usize,
     higher: usize,
@@ -228,6 +469,13 @@ pub struct RollingQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingQuantile<F> {
     pub fn new(q: F, window_size: usize) -> Result<Self, &'static str> {
+        Self::with_method(q, window_size, InterpolationMethod::Linear)
+    }
+    pub fn with_method(
+        q: F,
+        window_size: usize,
+        method: InterpolationMethod,
+    ) -> Result<Self, &'static str> {
         if F::from_f64(0.).unwrap() > q && F::from_f64(1.).unwrap() < q {
             return Err("q should be betweek 0 and 1");
         }
@@ -242,12 +490,25 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingQuantile<F> {
         Ok(Self {
             sorted_window: SortedWindow::new(window_size),
             q,
+            method,
             window_size,
             lower,
             higher,
             frac,
         })
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
     fn prepare(&self) -> (usize, usize, F) {
         if self.sorted_window.len() < self.window_size {
             let idx = self.q
@@ -271,7 +532,33 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     }
     fn get(&self) -> F {
         let (lower, higher, frac) = self.prepare();
-        self.sorted_window[lower] + (self.sorted_window[higher] - self.sorted_window[lower]) * frac
+        let (lower_value, higher_value) = (self.sorted_window[lower], self.sorted_window[higher]);
+        if frac == F::from_f64(0.).unwrap() {
+            return lower_value;
+        }
+        match self.method {
+            InterpolationMethod::Linear => lower_value + (higher_value - lower_value) * frac,
+            InterpolationMethod::Lower => lower_value,
+            InterpolationMethod::Higher => higher_value,
+            InterpolationMethod::Nearest => {
+                let half = F::from_f64(0.5).unwrap();
+                // Ties are rounded to the even index like NumPy.
+                if frac < half || (frac == half && lower % 2 == 0) {
+                    lower_value
+                } else {
+                    higher_value
+                }
+            }
+            InterpolationMethod::Midpoint => {
+                (lower_value + higher_value) / F::from_f64(2.).unwrap()
+            }
+        }
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
     }
 }
 #[cfg(test)]
@@ -325,18 +612,117 @@ mod test {
             0.8275462962962963,
         ];
         let mut quantile = Quantile::new(0.01_f64).unwrap();
-        for (d, gt) in data
-            .clone()
-            .into_iter()
-            .zip(good_value_001_quantile.into_iter())
-        {
+        for (d, gt) in data.clone().into_iter().zip(good_value_001_quantile) {
             quantile.update(d);
             assert_eq!(quantile.get(), gt);
         }
         let mut quantile = Quantile::new(0.99_f64).unwrap();
-        for (d, gt) in data.into_iter().zip(good_value_099_quantile.into_iter()) {
+        for (d, gt) in data.into_iter().zip(good_value_099_quantile) {
             quantile.update(d);
             assert_eq!(quantile.get(), gt);
         }
     }
+
+    #[test]
+    fn rolling_quantile_interpolation_methods() {
+        use crate::quantile::{InterpolationMethod, RollingQuantile};
+        use crate::stats::Univariate;
+        // Expected values come from `np.quantile(window, q, method=...)`.
+        let window = [7., 1., 4., 10., 2.];
+        let cases = [
+            (0.4, InterpolationMethod::Linear, 3.2),
+            (0.4, InterpolationMethod::Lower, 2.),
+            (0.4, InterpolationMethod::Higher, 4.),
+            (0.4, InterpolationMethod::Nearest, 4.),
+            (0.4, InterpolationMethod::Midpoint, 3.),
+            (0.125, InterpolationMethod::Nearest, 1.),
+            (0.375, InterpolationMethod::Nearest, 4.),
+            (0.5, InterpolationMethod::Higher, 4.),
+            (1., InterpolationMethod::Higher, 10.),
+        ];
+        for (q, method, expected) in cases {
+            let mut rolling_quantile: RollingQuantile<f64> =
+                RollingQuantile::with_method(q, 5, method).unwrap();
+            for x in [100., -100.].iter().chain(window.iter()) {
+                rolling_quantile.update(*x);
+            }
+            assert!((rolling_quantile.get() - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn merged_median_is_close_to_single_pass() {
+        use crate::quantile::Quantile;
+        use crate::stats::{uniform, Univariate};
+        let mut uniform = uniform(42);
+        let data: Vec<f64> = (0..10_000).map(|_| uniform()).collect();
+        let mut single_pass: Quantile<f64> = Quantile::new(0.5).unwrap();
+        for x in data.iter() {
+            single_pass.update(*x);
+        }
+        let (first_half, second_half) = data.split_at(data.len() / 2);
+        let mut left: Quantile<f64> = Quantile::new(0.5).unwrap();
+        let mut right: Quantile<f64> = Quantile::new(0.5).unwrap();
+        for (x, y) in first_half.iter().zip(second_half.iter()) {
+            left.update(*x);
+            right.update(*y);
+        }
+        left.merge(&right).unwrap();
+        assert!((left.get() - single_pass.get()).abs() < 0.02);
+        // The merged estimator keeps updating like a regular one.
+        for x in data.iter() {
+            left.update(*x);
+        }
+        assert!((left.get() - 0.5).abs() < 0.02);
+
+        let mut short: Quantile<f64> = Quantile::new(0.5).unwrap();
+        for x in [3., 1.] {
+            short.update(x);
+        }
+        short.merge(&right).unwrap();
+        right.update(3.);
+        right.update(1.);
+        assert_eq!(short.get(), right.get());
+        assert!(short.merge(&Quantile::new(0.25).unwrap()).is_err());
+    }
+
+    #[test]
+    fn buffered_quantile_resists_skewed_warm_up() {
+        use crate::quantile::{BufferedQuantile, Quantile};
+        use crate::stats::{uniform, Univariate};
+        let mut uniform = uniform(11);
+        // The stream starts with a burst of large values before settling on uniform `[0, 1)`.
+        let data: Vec<f64> = (0..300)
+            .map(|i| if i < 6 { 100. + i as f64 } else { uniform() })
+            .collect();
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = (sorted[149] + sorted[150]) / 2.;
+        let mut raw: Quantile<f64> = Quantile::new(0.5).unwrap();
+        let mut buffered: BufferedQuantile<f64> = BufferedQuantile::new(0.5, 32).unwrap();
+        for x in data.iter() {
+            raw.update(*x);
+            buffered.update(*x);
+        }
+        let (raw_error, buffered_error) =
+            ((raw.get() - median).abs(), (buffered.get() - median).abs());
+        assert!(buffered_error < raw_error);
+    }
+
+    #[test]
+    fn buffered_quantile_feeds_every_value() {
+        use crate::quantile::BufferedQuantile;
+        use crate::stats::Univariate;
+        let mut buffered: BufferedQuantile<f64> = BufferedQuantile::new(0.5, 7).unwrap();
+        for x in [4., 1., 3.] {
+            buffered.update(x);
+        }
+        assert_eq!(buffered.get(), 3.);
+        for x in [9., 2., 6., 5., 8., 7., 0.] {
+            buffered.update(x);
+        }
+        // Position of the last marker, i.e. the number of values seen by the inner estimator.
+        assert_eq!(buffered.quantile.position[4], 7.);
+        assert!(BufferedQuantile::<f64>::new(0.5, 4).is_err());
+    }
 }
@@ -0,0 +1,112 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Rolling percentile rank of the most recent value.
+/// It is the fraction of the values of the window, the most recent one included, which are lower
+/// than or equal to the most recent value, e.g. `0.92` means the latest value is at the 92nd
+/// percentile of the window. It is `1` when the latest value is the maximum of the window and
+/// `1 / len` when it is the unique minimum. `get` returns the same value as `rank`.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::rank::RollingRank;
+/// use watermill::stats::Univariate;
+/// let mut rolling_rank: RollingRank<f64> = RollingRank::new(4).unwrap();
+/// for x in [3., 1., 4., 1.].iter(){
+///     rolling_rank.update(*x);
+/// }
+/// assert_eq!(rolling_rank.rank(), 0.5);
+/// rolling_rank.update(5.);
+/// assert_eq!(rolling_rank.get(), 1.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the percentile rank](https://en.wikipedia.org/wiki/Percentile_rank)
+#[derive(Serialize, Deserialize)]
+pub struct RollingRank<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingRank<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size),
+            last: None,
+        })
+    }
+    /// Fraction of the window values lower than or equal to the most recent value, `NaN` if no
+    /// value has been pushed yet.
+    pub fn rank(&self) -> F {
+        match self.last {
+            Some(last) => {
+                let below = self
+                    .sorted_window
+                    .sorted_window
+                    .partition_point(|x| x.into_inner() <= last);
+                F::from_usize(below).unwrap() / F::from_usize(self.sorted_window.len()).unwrap()
+            }
+            None => F::nan(),
+        }
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.sorted_window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.sorted_window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingRank<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+        self.last = Some(x);
+    }
+    fn get(&self) -> F {
+        self.rank()
+    }
+    fn reset(&mut self) {
+        self.sorted_window.clear();
+        self.last = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.sorted_window.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn newest_value_at_the_extremes() {
+        use crate::rank::RollingRank;
+        use crate::stats::Univariate;
+        let mut rolling_rank: RollingRank<f64> = RollingRank::new(100).unwrap();
+        assert!(rolling_rank.rank().is_nan());
+        for i in 0..250 {
+            rolling_rank.update(((i * 37) % 100) as f64);
+        }
+        rolling_rank.update(1000.);
+        assert_eq!(rolling_rank.rank(), 1.);
+        rolling_rank.update(-1000.);
+        assert_eq!(rolling_rank.rank(), 0.01);
+        // Ties with the most recent value count as lower or equal.
+        let mut rolling_rank: RollingRank<f64> = RollingRank::new(4).unwrap();
+        for x in [2., 2., 2., 2., 2.] {
+            rolling_rank.update(x);
+        }
+        assert_eq!(rolling_rank.rank(), 1.);
+        assert!(RollingRank::<f64>::new(0).is_err());
+    }
+}
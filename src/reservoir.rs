@@ -0,0 +1,197 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::cms::splitmix64;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Uniform random sample of `k` values of the stream, kept with Algorithm R.
+/// The first `k` values fill the sample, then the `n`-th value replaces a random value of the
+/// sample with probability `k / n`, so that every value seen so far has the same chance to be in
+/// the sample. `get` returns the mean of the sample.
+///
+/// The random numbers come from a SplitMix64 generator whose whole state is a `u64`, so the
+/// reservoir is serializable and its draws do not depend on the platform or on the version of a
+/// dependency. Two reservoirs built with the same seed and fed the same values keep the same
+/// sample and serialize to the same bytes, and `reset` replays the generator from the seed.
+/// Reservoirs are `Send` and `Sync`, they can be moved to another thread but each one should be
+/// updated by a single thread at a time.
+/// # Arguments
+/// * `k` - Size of the sample.
+/// * `seed` - Seed of the random number generator, the same seed and stream give the same sample.
+/// # Examples
+/// ```
+/// use watermill::reservoir::Reservoir;
+/// use watermill::stats::Univariate;
+/// let mut reservoir: Reservoir<f64> = Reservoir::new(3, 42).unwrap();
+/// for i in 0..100{
+///     reservoir.update(i as f64);
+/// }
+/// assert_eq!(reservoir.sample().len(), 3);
+/// let mean = reservoir.sample().iter().sum::<f64>() / 3.;
+/// assert_eq!(reservoir.get(), mean);
+/// ```
+/// # References
+/// [^1]: [Vitter, J.S., 1985. Random sampling with a reservoir. ACM Transactions on Mathematical Software, 11(1), pp.37-57.](https://dl.acm.org/doi/10.1145/3147.3165)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Reservoir<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sample: Vec<F>,
+    k: usize,
+    n: usize,
+    seed: u64,
+    /// State of the random number generator, advanced by each draw.
+    state: u64,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Reservoir<F> {
+    pub fn new(k: usize, seed: u64) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k should be greater than 0");
+        }
+        Ok(Self {
+            sample: Vec::with_capacity(k),
+            k,
+            n: 0,
+            seed,
+            state: seed,
+        })
+    }
+    /// Draws an index uniformly in `[0, n]`. The multiply-shift reduction has a bias of at most
+    /// `(n + 1) / 2^64`.
+    fn draw(&mut self, n: usize) -> usize {
+        let random = splitmix64(self.state);
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        ((random as u128 * (n as u128 + 1)) >> 64) as usize
+    }
+    /// Values currently in the sample, in no particular order.
+    pub fn sample(&self) -> &[F] {
+        &self.sample
+    }
+    /// Number of values seen so far.
+    pub fn n_seen(&self) -> usize {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Reservoir<F> {
+    fn update(&mut self, x: F) {
+        if self.sample.len() < self.k {
+            self.sample.push(x);
+        } else {
+            let i = self.draw(self.n);
+            if i < self.k {
+                self.sample[i] = x;
+            }
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        if self.sample.is_empty() {
+            return F::from_f64(0.).unwrap();
+        }
+        let sum = self
+            .sample
+            .iter()
+            .fold(F::from_f64(0.).unwrap(), |acc, x| acc + *x);
+        sum / F::from_usize(self.sample.len()).unwrap()
+    }
+    fn reset(&mut self) {
+        self.sample.clear();
+        self.n = 0;
+        self.state = self.seed;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn sample_mean_is_unbiased() {
+        use crate::reservoir::Reservoir;
+        use crate::stats::Univariate;
+        let runs = 500;
+        let mut mean_of_means = 0.;
+        for seed in 0..runs {
+            let mut reservoir: Reservoir<f64> = Reservoir::new(10, seed).unwrap();
+            for i in 0..1000 {
+                reservoir.update(i as f64);
+            }
+            assert_eq!(reservoir.sample().len(), 10);
+            mean_of_means += reservoir.get() / runs as f64;
+        }
+        // The population mean is 499.5, the standard error over the runs is about 4.
+        assert!((mean_of_means - 499.5).abs() < 20.);
+    }
+
+    #[test]
+    fn reset_replays_the_same_sample() {
+        use crate::reservoir::Reservoir;
+        use crate::stats::Univariate;
+        let mut reservoir: Reservoir<f64> = Reservoir::new(5, 7).unwrap();
+        for i in 0..3 {
+            reservoir.update(i as f64);
+        }
+        assert_eq!(reservoir.sample(), &[0., 1., 2.]);
+        for i in 3..100 {
+            reservoir.update(i as f64);
+        }
+        let first = reservoir.sample().to_vec();
+        reservoir.reset();
+        for i in 0..100 {
+            reservoir.update(i as f64);
+        }
+        assert_eq!(reservoir.sample(), &first[..]);
+        assert_eq!(reservoir.n_seen(), 100);
+        assert!(Reservoir::<f64>::new(0, 7).is_err());
+    }
+    #[test]
+    fn same_seed_same_sample() {
+        use crate::reservoir::Reservoir;
+        use crate::stats::Univariate;
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Reservoir<f64>>();
+        let mut reservoirs: Vec<Reservoir<f64>> =
+            (0..2).map(|_| Reservoir::new(8, 99).unwrap()).collect();
+        for reservoir in reservoirs.iter_mut() {
+            for i in 0..500 {
+                reservoir.update(i as f64);
+            }
+        }
+        assert_eq!(reservoirs[0].sample(), reservoirs[1].sample());
+        // Moved to another thread, the reservoir keeps drawing the same values.
+        let mut moved = reservoirs.pop().unwrap();
+        let moved = std::thread::spawn(move || {
+            moved.update(500.);
+            moved
+        })
+        .join()
+        .unwrap();
+        reservoirs[0].update(500.);
+        assert_eq!(reservoirs[0].sample(), moved.sample());
+    }
+    #[test]
+    fn serialized_state_is_reproducible() {
+        use crate::reservoir::Reservoir;
+        use crate::stats::Univariate;
+        let mut reservoir: Reservoir<f64> = Reservoir::new(3, 2023).unwrap();
+        for i in 0..10 {
+            reservoir.update(i as f64);
+        }
+        // The same bytes on every platform and with every version of the dependencies.
+        let serialized = serde_json::to_string(&reservoir).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"sample":[8.0,4.0,2.0],"k":3,"n":10,"seed":2023,"state":6018027440424184954}"#
+        );
+        let mut deserialized: Reservoir<f64> = serde_json::from_str(&serialized).unwrap();
+        for i in 10..1000 {
+            reservoir.update(i as f64);
+            deserialized.update(i as f64);
+        }
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            serde_json::to_string(&reservoir).unwrap()
+        );
+    }
+}
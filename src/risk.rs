@@ -0,0 +1,128 @@
+use num::{Float, FromPrimitive};
+use std::{
+    collections::VecDeque,
+    ops::{AddAssign, SubAssign},
+};
+
+use crate::quantile::Quantile;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Running value at risk.
+/// Values are returns, or profits and losses, so that losses are negative. The VaR is the
+/// `1 - confidence` quantile of the returns, estimated with `Quantile`, and is reported as a
+/// positive loss.
+/// # Arguments
+/// * `confidence` - Confidence level, must be between 0 and 1, e.g. `0.95`.
+/// # Examples
+/// ```
+/// use watermill::risk::VaR;
+/// use watermill::stats::Univariate;
+/// let mut running_var: VaR<f64> = VaR::new(0.9).unwrap();
+/// // Returns spread uniformly between -10 and 89.
+/// for i in 0..1000{
+///     running_var.update(((i * 37) % 100 - 10) as f64);
+/// }
+/// assert!(running_var.get().abs() < 1.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the value at risk](https://en.wikipedia.org/wiki/Value_at_risk)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VaR<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub confidence: F,
+    quantile: Quantile<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> VaR<F> {
+    pub fn new(confidence: F) -> Result<Self, &'static str> {
+        if confidence <= F::from_f64(0.).unwrap() || confidence >= F::from_f64(1.).unwrap() {
+            return Err("confidence should be between 0 and 1");
+        }
+        Ok(Self {
+            confidence,
+            quantile: Quantile::new(F::from_f64(1.).unwrap() - confidence)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for VaR<F> {
+    fn update(&mut self, x: F) {
+        self.quantile.update(x);
+    }
+    fn get(&self) -> F {
+        -self.quantile.get()
+    }
+    fn reset(&mut self) {
+        self.quantile.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.quantile.n_samples()
+    }
+}
+
+/// Conditional value at risk, also known as expected shortfall.
+/// It is the average loss of the returns of the window which lie beyond the running `VaR`, and
+/// it falls back to the VaR while no return of the window is beyond it.
+/// # Arguments
+/// * `confidence` - Confidence level, must be between 0 and 1, e.g. `0.95`.
+/// * `window_size` - Number of recent returns used to average the tail.
+/// # Examples
+/// ```
+/// use watermill::risk::ConditionalVaR;
+/// use watermill::stats::Univariate;
+/// let mut running_cvar: ConditionalVaR<f64> = ConditionalVaR::new(0.9, 100).unwrap();
+/// // Returns spread uniformly between -10 and 89.
+/// for i in 0..1000{
+///     running_cvar.update(((i * 37) % 100 - 10) as f64);
+/// }
+/// // The tail of the window holds the returns -10..=0, whose mean loss is 5.
+/// assert!((running_cvar.get() - 5.).abs() < 1.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConditionalVaR<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub var: VaR<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ConditionalVaR<F> {
+    pub fn new(confidence: F, window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equals to 0");
+        }
+        Ok(Self {
+            var: VaR::new(confidence)?,
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for ConditionalVaR<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(x);
+        self.var.update(x);
+    }
+    fn get(&self) -> F {
+        let var = self.var.get();
+        let mut tail_sum = F::from_f64(0.).unwrap();
+        let mut tail_count = 0;
+        for x in self.window.iter().filter(|x| -**x >= var) {
+            tail_sum += *x;
+            tail_count += 1;
+        }
+        if tail_count == 0 {
+            return var;
+        }
+        -tail_sum / F::from_usize(tail_count).unwrap()
+    }
+    fn reset(&mut self) {
+        self.var.reset();
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.var.n_samples()
+    }
+}
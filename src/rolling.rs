@@ -1,18 +1,39 @@
-use crate::stats::{RollableUnivariate, Univariate};
+use crate::stats::{Bivariate, RollableBivariate, RollableUnivariate, Univariate};
 use num::{Float, FromPrimitive};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
     ops::{AddAssign, SubAssign},
 };
 
+fn one() -> usize {
+    1
+}
+
+fn check_min_periods(window_size: usize, min_periods: usize) -> Result<(), &'static str> {
+    if window_size == 0 {
+        return Err("Window size should not equals to 0");
+    }
+    if min_periods > window_size {
+        return Err("min_periods should not be larger than the window size");
+    }
+    Ok(())
+}
+
 /// Generic wrapper for performing rolling computations.
 /// This can be wrapped around any struct which implements a `Univariate` and a `Revertable` and `RollableUnivariate`
 /// traits.
 /// Inputs to `update` are stored in a `VecDeque`. Elements of the queue are popped when the window is
 //  full.
+/// Like `min_periods` in pandas, `get` returns `NaN` while the window holds fewer than
+/// `min_periods` values. `new` uses `min_periods = 1`, use `with_min_periods` to change it, with
+/// `min_periods = 0` an empty window reads the initial value of the statistic.
+/// The rolling statistics with their own window, such as `RollingQuantile`, `RollingMin` or
+/// `RollingMode`, have no `min_periods`: wrap them in `warmup::WarmUp` to get the same behavior.
 /// # Arguments
 /// * `to_roll` - A running statistics which implements `Univariate` and `Revertable` and `RollableUnivariate` trait.
 /// * `window_size` - Size of sliding window.
+/// * `min_periods` - Number of values required in the window before `get` returns a value, at most `window_size`.
 /// # Examples
 /// ```
 ///
@@ -28,10 +49,10 @@ use std::{
 /// }
 /// assert_eq!(rolling_sum.get(), 9.0);
 /// ```
-
 pub struct Rolling<'a, F: Float + FromPrimitive + AddAssign + SubAssign> {
     to_roll: &'a mut dyn RollableUnivariate<F>,
     window_size: usize,
+    min_periods: usize,
     window: VecDeque<F>,
 }
 
@@ -39,19 +60,97 @@ impl<'a, F: Float + FromPrimitive + AddAssign + SubAssign> Rolling<'a, F> {
     pub fn new(
         to_roll: &'a mut dyn RollableUnivariate<F>,
         window_size: usize,
-    ) -> Result<Self, &str> {
-        if window_size == 0 {
-            return Err("Window size should not equals to 0");
-        }
+    ) -> Result<Self, &'static str> {
+        Self::with_min_periods(to_roll, window_size, 1)
+    }
+    pub fn with_min_periods(
+        to_roll: &'a mut dyn RollableUnivariate<F>,
+        window_size: usize,
+        min_periods: usize,
+    ) -> Result<Self, &'static str> {
+        check_min_periods(window_size, min_periods)?;
         Ok(Self {
             to_roll,
             window_size,
-            window: VecDeque::new(),
+            min_periods,
+            window: VecDeque::with_capacity(window_size),
         })
     }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values, i.e. when the next update
+    /// starts reverting the oldest value.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::rolling::Rolling;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// let mut rolling_mean: Rolling<f64> = Rolling::new(&mut running_mean, 3).unwrap();
+    /// rolling_mean.update(1.);
+    /// rolling_mean.update(2.);
+    /// assert!(!rolling_mean.is_full());
+    /// assert_eq!(rolling_mean.len(), 2);
+    /// rolling_mean.update(3.);
+    /// assert!(rolling_mean.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+    /// Removes the oldest value from the window and reverts it from the statistic, without
+    /// pushing a new one. Returns `None` if the window is empty. This is the building block of
+    /// windows which expire values on something else than their count, such as time.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::rolling::Rolling;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// let mut rolling_mean: Rolling<f64> = Rolling::new(&mut running_mean, 3).unwrap();
+    /// rolling_mean.update(1.);
+    /// rolling_mean.update(5.);
+    /// assert_eq!(rolling_mean.pop_oldest(), Some(1.));
+    /// assert_eq!(rolling_mean.get(), 5.);
+    /// ```
+    pub fn pop_oldest(&mut self) -> Option<F> {
+        let oldest = self.window.pop_front()?;
+        // Same reasoning as `update`.
+        if let Err(err) = self.to_roll.revert(oldest) {
+            panic!("{}", err);
+        }
+        Some(oldest)
+    }
+    /// Pushes the values of `other`'s window through `self`, oldest first, as if they had been
+    /// seen after the values of `self`. Only the last `window_size` values are kept. This costs
+    /// `O(other.len())`.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::rolling::Rolling;
+    /// use watermill::stats::Univariate;
+    /// let (mut first_mean, mut second_mean): (Mean<f64>, Mean<f64>) = (Mean::new(), Mean::new());
+    /// let mut first: Rolling<f64> = Rolling::new(&mut first_mean, 3).unwrap();
+    /// let mut second: Rolling<f64> = Rolling::new(&mut second_mean, 3).unwrap();
+    /// first.update(1.);
+    /// second.update(4.);
+    /// second.update(7.);
+    /// first.absorb(&second);
+    /// assert_eq!(first.get(), 4.);
+    /// ```
+    pub fn absorb(&mut self, other: &Rolling<'_, F>) {
+        for x in other.window.iter() {
+            self.update(*x);
+        }
+    }
 }
 
-impl<'a, F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling<'_, F> {
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling<'_, F> {
     fn update(&mut self, x: F) {
         if self.window.len() == self.window_size {
             // To handle the error, the program panics because returning the error type would change
@@ -69,10 +168,508 @@ impl<'a, F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rol
         self.to_roll.update(x);
     }
 
+    fn get(&self) -> F {
+        if self.window.len() < self.min_periods {
+            return F::nan();
+        }
+        self.to_roll.get()
+    }
+    fn reset(&mut self) {
+        self.to_roll.reset();
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+/// Owned counterpart of `Rolling`.
+/// It takes ownership of the statistic to roll, so it has no lifetime and can be stored, cloned
+/// and serialized like any other statistic. `mean::RollingMean` and `variance::RollingVariance`
+/// are aliases of it.
+/// # Arguments
+/// * `to_roll` - A running statistics which implements the `RollableUnivariate` trait.
+/// * `window_size` - Size of sliding window.
+/// * `min_periods` - Same as for `Rolling`, defaults to 1.
+/// # Examples
+/// ```
+/// use watermill::rolling::RollingOwned;
+/// use watermill::stats::Univariate;
+/// use watermill::sum::Sum;
+/// let mut rolling_sum: RollingOwned<f64, Sum<f64>> = RollingOwned::new(Sum::new(), 2).unwrap();
+/// for x in [9., 7., 3.].iter(){
+///     rolling_sum.update(*x);
+/// }
+/// assert_eq!(rolling_sum.get(), 10.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingOwned<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    pub to_roll: U,
+    window_size: usize,
+    #[serde(default = "one")]
+    min_periods: usize,
+    window: VecDeque<F>,
+}
+
+impl<F, U> RollingOwned<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    pub fn new(to_roll: U, window_size: usize) -> Result<Self, &'static str> {
+        Self::with_min_periods(to_roll, window_size, 1)
+    }
+    pub fn with_min_periods(
+        to_roll: U,
+        window_size: usize,
+        min_periods: usize,
+    ) -> Result<Self, &'static str> {
+        check_min_periods(window_size, min_periods)?;
+        Ok(Self {
+            to_roll,
+            window_size,
+            min_periods,
+            window: VecDeque::with_capacity(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+    /// Removes the oldest value from the window, see `Rolling::pop_oldest`.
+    pub fn pop_oldest(&mut self) -> Option<F> {
+        let oldest = self.window.pop_front()?;
+        if let Err(err) = self.to_roll.revert(oldest) {
+            panic!("{}", err);
+        }
+        Some(oldest)
+    }
+    /// Pushes the values of `other`'s window through `self`, see `Rolling::absorb`.
+    pub fn absorb(&mut self, other: &Self) {
+        for x in other.window.iter() {
+            self.update(*x);
+        }
+    }
+}
+
+impl<F, U> Univariate<F> for RollingOwned<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            // Same reasoning as `Rolling::update`.
+            let oldest = self.window.pop_front().unwrap();
+            if let Err(err) = self.to_roll.revert(oldest) {
+                panic!("{}", err);
+            }
+        }
+        self.window.push_back(x);
+        self.to_roll.update(x);
+    }
+
+    fn get(&self) -> F {
+        if self.window.len() < self.min_periods {
+            return F::nan();
+        }
+        self.to_roll.get()
+    }
+    fn reset(&mut self) {
+        self.to_roll.reset();
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+/// Rolling wrapper for statistics which cannot be reverted, such as `Kurtosis` or `Skew`.
+/// The window is kept in a `VecDeque` and each call to `get` feeds the whole window to a fresh
+/// clone of the statistic, so `update` is `O(1)` but `get` is `O(window_size)`. Prefer `Rolling`
+/// or `RollingOwned` for statistics which implement `RollableUnivariate`.
+/// # Arguments
+/// * `window_size` - Size of sliding window.
+/// * `min_periods` - Same as for `Rolling`, defaults to 1.
+/// # Examples
+/// ```
+/// use watermill::kurtosis::Kurtosis;
+/// use watermill::rolling::RollingRecompute;
+/// use watermill::stats::Univariate;
+/// let mut rolling_kurtosis: RollingRecompute<f64, Kurtosis<f64>> =
+///     RollingRecompute::with_stat(Kurtosis::new(true), 4).unwrap();
+/// for x in [100., 1., 2., 3., 4.].iter(){
+///     rolling_kurtosis.update(*x);
+/// }
+/// assert!((rolling_kurtosis.get() + 1.36).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingRecompute<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Clone + Default,
+{
+    /// Statistic in its initial state, cloned by each call to `get`.
+    pub stat: U,
+    window_size: usize,
+    #[serde(default = "one")]
+    min_periods: usize,
+    window: VecDeque<F>,
+}
+
+impl<F, U> RollingRecompute<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Clone + Default,
+{
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_stat(U::default(), window_size)
+    }
+    /// Same as `new` but the window is fed to clones of `stat` instead of `U::default()`.
+    pub fn with_stat(stat: U, window_size: usize) -> Result<Self, &'static str> {
+        Self::with_min_periods(stat, window_size, 1)
+    }
+    /// Same as `with_stat` with a custom `min_periods`.
+    pub fn with_min_periods(
+        stat: U,
+        window_size: usize,
+        min_periods: usize,
+    ) -> Result<Self, &'static str> {
+        check_min_periods(window_size, min_periods)?;
+        Ok(Self {
+            stat,
+            window_size,
+            min_periods,
+            window: VecDeque::with_capacity(window_size),
+        })
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+    /// Removes the oldest value from the window, see `Rolling::pop_oldest`.
+    pub fn pop_oldest(&mut self) -> Option<F> {
+        self.window.pop_front()
+    }
+    /// Pushes the values of `other`'s window through `self`, see `Rolling::absorb`.
+    pub fn absorb(&mut self, other: &Self) {
+        for x in other.window.iter() {
+            self.update(*x);
+        }
+    }
+}
+
+impl<F, U> Univariate<F> for RollingRecompute<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F> + Clone + Default,
+{
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back(x);
+    }
+
+    fn get(&self) -> F {
+        if self.window.len() < self.min_periods {
+            return F::nan();
+        }
+        let mut stat = self.stat.clone();
+        for x in self.window.iter() {
+            stat.update(*x);
+        }
+        stat.get()
+    }
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+/// Rolling wrapper for bivariate statistics.
+/// It owns a struct which implements the `RollableBivariate` trait, stores the `(x, y)` pairs in a
+/// `VecDeque` and reverts the oldest pair once the window is full.
+/// # Arguments
+/// * `to_roll` - A running statistics which implements the `RollableBivariate` trait.
+/// * `window_size` - Size of sliding window.
+/// * `min_periods` - Same as for `Rolling`, counted in pairs, defaults to 1.
+/// # Examples
+/// ```
+/// use watermill::covariance::Covariance;
+/// use watermill::rolling::RollingBivariate;
+/// use watermill::stats::Bivariate;
+/// let x = vec![1., 2., 3., 10., 20.];
+/// let y = vec![5., 4., 3., 12., 22.];
+/// let mut rolling_cov: RollingBivariate<f64, Covariance<f64>> =
+///     RollingBivariate::new(Covariance::default(), 2).unwrap();
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     rolling_cov.update(*xi, *yi);
+/// }
+/// assert_eq!(rolling_cov.get(), 50.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingBivariate<F, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    B: RollableBivariate<F>,
+{
+    pub to_roll: B,
+    window_size: usize,
+    #[serde(default = "one")]
+    min_periods: usize,
+    window: VecDeque<(F, F)>,
+}
+
+impl<F, B> RollingBivariate<F, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    B: RollableBivariate<F>,
+{
+    pub fn new(to_roll: B, window_size: usize) -> Result<Self, &'static str> {
+        Self::with_min_periods(to_roll, window_size, 1)
+    }
+    pub fn with_min_periods(
+        to_roll: B,
+        window_size: usize,
+        min_periods: usize,
+    ) -> Result<Self, &'static str> {
+        check_min_periods(window_size, min_periods)?;
+        Ok(Self {
+            to_roll,
+            window_size,
+            min_periods,
+            window: VecDeque::with_capacity(window_size),
+        })
+    }
+    /// Number of pairs currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if no pair has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` pairs.
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window_size
+    }
+    /// Removes the oldest pair from the window, see `Rolling::pop_oldest`.
+    pub fn pop_oldest(&mut self) -> Option<(F, F)> {
+        let (old_x, old_y) = self.window.pop_front()?;
+        if let Err(err) = self.to_roll.revert(old_x, old_y) {
+            panic!("{}", err);
+        }
+        Some((old_x, old_y))
+    }
+    /// Pushes the pairs of `other`'s window through `self`, see `Rolling::absorb`.
+    pub fn absorb(&mut self, other: &Self) {
+        for (x, y) in other.window.iter() {
+            self.update(*x, *y);
+        }
+    }
+}
+
+impl<F, B> Bivariate<F> for RollingBivariate<F, B>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    B: RollableBivariate<F>,
+{
+    fn update(&mut self, x: F, y: F) {
+        if self.window.len() == self.window_size {
+            // Same reasoning as `Rolling::update`: the window size is controlled by the
+            // constructor, so reverting a pair we pushed ourselves should not fail.
+            let (old_x, old_y) = self.window.pop_front().unwrap();
+            if let Err(err) = self.to_roll.revert(old_x, old_y) {
+                panic!("{}", err);
+            }
+        }
+        self.window.push_back((x, y));
+        self.to_roll.update(x, y);
+    }
+
+    fn get(&self) -> F {
+        if self.window.len() < self.min_periods {
+            return F::nan();
+        }
+        self.to_roll.get()
+    }
+
+    fn reset(&mut self) {
+        self.to_roll.reset();
+        self.window.clear();
+    }
+
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
+}
+
+/// Rolling wrapper whose window is a time horizon instead of a number of values.
+/// `update_at` stores the `(t, x)` pair and reverts every value seen before `t - horizon`, so the
+/// statistic covers the values with a timestamp in `[t - horizon, t]`. Timestamps are expected to
+/// be non-decreasing. `expire` moves the window forward without a new value, e.g. before reading
+/// a stream which has gone quiet, and `update` assumes one time unit between two values.
+/// # Arguments
+/// * `to_roll` - A running statistics which implements the `RollableUnivariate` trait.
+/// * `horizon` - Length of the window, in the unit of the timestamps.
+/// # Examples
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::rolling::TimeRolling;
+/// use watermill::stats::Univariate;
+/// let mut rolling_mean: TimeRolling<f64, Mean<f64>> = TimeRolling::new(Mean::new(), 10.).unwrap();
+/// rolling_mean.update_at(1., 0.);
+/// rolling_mean.update_at(2., 4.);
+/// rolling_mean.update_at(6., 12.);
+/// // The value seen at time 0 is older than `12 - 10`.
+/// assert_eq!(rolling_mean.get(), 4.);
+/// rolling_mean.expire(20.);
+/// assert_eq!(rolling_mean.get(), 6.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimeRolling<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    pub to_roll: U,
+    pub horizon: F,
+    /// `(t, x)` pairs, the oldest first.
+    window: VecDeque<(F, F)>,
+    last_t: Option<F>,
+}
+
+impl<F, U> TimeRolling<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    pub fn new(to_roll: U, horizon: F) -> Result<Self, &'static str> {
+        if horizon < F::from_f64(0.).unwrap() {
+            return Err("horizon should not be negative");
+        }
+        Ok(Self {
+            to_roll,
+            horizon,
+            window: VecDeque::new(),
+            last_t: None,
+        })
+    }
+    /// Updates the statistic with a value `x` observed at time `t`.
+    pub fn update_at(&mut self, x: F, t: F) {
+        self.window.push_back((t, x));
+        self.to_roll.update(x);
+        self.expire(t);
+    }
+    /// Reverts the values seen before `t - horizon`.
+    pub fn expire(&mut self, t: F) {
+        while let Some((oldest_t, oldest)) = self.window.front().copied() {
+            if oldest_t >= t - self.horizon {
+                break;
+            }
+            self.window.pop_front();
+            // Same reasoning as `Rolling::update`, only values we pushed ourselves are reverted.
+            if let Err(err) = self.to_roll.revert(oldest) {
+                panic!("{}", err);
+            }
+        }
+        self.last_t = Some(self.last_t.map_or(t, |last_t| last_t.max(t)));
+    }
+    /// Time of the last update or expiry.
+    pub fn last_time(&self) -> Option<F> {
+        self.last_t
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+    /// Returns `true` if the window holds no value.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+}
+
+impl<F, U> Univariate<F> for TimeRolling<F, U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    fn update(&mut self, x: F) {
+        let t = self
+            .last_t
+            .map_or(F::from_f64(0.).unwrap(), |t| t + F::from_f64(1.).unwrap());
+        self.update_at(x, t);
+    }
     fn get(&self) -> F {
         self.to_roll.get()
     }
+    fn reset(&mut self) {
+        self.to_roll.reset();
+        self.window.clear();
+        self.last_t = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.window.len())
+    }
 }
+
+/// Computes a statistic over every full window of size `window` of a slice.
+/// The statistic is rolled with `Rolling`, so each step costs one `update` and one `revert`
+/// instead of recomputing the whole window.
+/// # Arguments
+/// * `data` - Values to roll over.
+/// * `window` - Size of the sliding window.
+/// * `stat` - A running statistic which implements the `RollableUnivariate` trait.
+/// # Panics
+/// Panics if `window` is 0.
+/// # Examples
+/// ```
+/// use watermill::rolling::windowed;
+/// use watermill::sum::Sum;
+/// let data = vec![9., 7., 3., 2., 6.];
+/// let sums = windowed(&data, 3, Sum::new());
+/// assert_eq!(sums, vec![19., 12., 11.]);
+/// ```
+pub fn windowed<F, U>(data: &[F], window: usize, mut stat: U) -> Vec<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: RollableUnivariate<F>,
+{
+    let mut rolling = Rolling::new(&mut stat, window).unwrap();
+    let mut values = Vec::with_capacity((data.len() + 1).saturating_sub(window));
+    for (i, x) in data.iter().enumerate() {
+        rolling.update(*x);
+        if i + 1 >= window {
+            values.push(rolling.get());
+        }
+    }
+    values
+}
+
+#[cfg(test)]
 mod tests {
     #[test]
     fn it_works() {
@@ -84,8 +681,305 @@ mod tests {
         // We wrap `running_var` inside the `Rolling` struct.
         let mut rolling_var: Rolling<f64> = Rolling::new(&mut running_var, 2).unwrap();
         for x in data.iter() {
-            rolling_var.update(*x as f64);
+            rolling_var.update(*x);
         }
         assert_eq!(rolling_var.get(), 0.5);
     }
+
+    #[test]
+    fn windowed_matches_brute_force() {
+        use crate::rolling::windowed;
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        let data: Vec<f64> = (0..50).map(|i| ((i * 7) % 13) as f64 * 0.5).collect();
+        for window in [1, 2, 5, 50] {
+            let rolled = windowed(&data, window, Variance::default());
+            let brute_force: Vec<f64> = data
+                .windows(window)
+                .map(|w| {
+                    let mut var: Variance<f64> = Variance::default();
+                    for x in w {
+                        var.update(*x);
+                    }
+                    var.get()
+                })
+                .collect();
+            assert_eq!(rolled.len(), brute_force.len());
+            for (r, b) in rolled.iter().zip(brute_force.iter()) {
+                assert!((r - b).abs() < 1e-9);
+            }
+        }
+        assert!(windowed(&data, 51, Variance::default()).is_empty());
+    }
+
+    #[test]
+    fn capacity_is_stable_once_full() {
+        use crate::mean::Mean;
+        use crate::rolling::Rolling;
+        use crate::stats::Univariate;
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut rolling_mean: Rolling<f64> = Rolling::new(&mut running_mean, 10).unwrap();
+        let capacity = rolling_mean.window.capacity();
+        assert!(capacity >= 10);
+        for i in 0..1000 {
+            rolling_mean.update(i as f64);
+            assert_eq!(rolling_mean.window.capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn rolling_covariance_matches_brute_force() {
+        use crate::covariance::Covariance;
+        use crate::rolling::RollingBivariate;
+        use crate::stats::Bivariate;
+        let x: Vec<f64> = (0..60).map(|i| ((i * 7) % 13) as f64 * 0.5).collect();
+        let y: Vec<f64> = (0..60)
+            .map(|i| ((i * 5) % 11) as f64 - 0.3 * x[i])
+            .collect();
+        for window in [1, 3, 10] {
+            let mut rolling_cov: RollingBivariate<f64, Covariance<f64>> =
+                RollingBivariate::new(Covariance::new(1), window).unwrap();
+            for i in 0..x.len() {
+                rolling_cov.update(x[i], y[i]);
+                let start = (i + 1).saturating_sub(window);
+                let mut brute_force: Covariance<f64> = Covariance::new(1);
+                for j in start..=i {
+                    brute_force.update(x[j], y[j]);
+                }
+                assert!((rolling_cov.get() - brute_force.get()).abs() < 1e-9);
+            }
+            assert!(rolling_cov.is_full());
+        }
+        assert!(RollingBivariate::new(Covariance::<f64>::default(), 0).is_err());
+    }
+
+    #[test]
+    fn rolling_kurtosis_matches_brute_force() {
+        use crate::kurtosis::Kurtosis;
+        use crate::rolling::RollingRecompute;
+        use crate::stats::Univariate;
+        let data: Vec<f64> = (0..50).map(|i| ((i * 7) % 13) as f64 * 0.5).collect();
+        let window_size = 8;
+        let mut rolling_kurtosis: RollingRecompute<f64, Kurtosis<f64>> =
+            RollingRecompute::new(window_size).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_kurtosis.update(*x);
+            let mut brute_force: Kurtosis<f64> = Kurtosis::default();
+            for y in data[(i + 1).saturating_sub(window_size)..=i].iter() {
+                brute_force.update(*y);
+            }
+            let (rolled, expected) = (rolling_kurtosis.get(), brute_force.get());
+            assert!(rolled == expected || (rolled.is_nan() && expected.is_nan()));
+        }
+        assert!(RollingRecompute::<f64, Kurtosis<f64>>::new(0).is_err());
+    }
+
+    #[test]
+    fn get_and_reset_reports_per_interval() {
+        use crate::mean::{Mean, RollingMean};
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        let mut rolling_mean: RollingMean<f64> = RollingMean::new(Mean::new(), 3).unwrap();
+        let mut interval_variance: Variance<f64> = Variance::new(0);
+        for x in [1., 2., 3., 4.] {
+            rolling_mean.update(x);
+            interval_variance.update(x);
+        }
+        assert_eq!(rolling_mean.get_and_reset(), 3.);
+        assert_eq!(interval_variance.get_and_reset(), 1.25);
+        assert!(rolling_mean.is_empty());
+        for x in [10., 20., 30., 40.] {
+            rolling_mean.update(x);
+            interval_variance.update(x);
+        }
+        // Only the values seen since the flush count, and the window size is kept.
+        assert_eq!(rolling_mean.get_and_reset(), 30.);
+        assert_eq!(interval_variance.get_and_reset(), 125.);
+        assert_eq!(interval_variance.ddof, 0);
+    }
+    #[test]
+    fn min_periods_gates_the_warm_up() {
+        use crate::covariance::Covariance;
+        use crate::kurtosis::Kurtosis;
+        use crate::mean::{Mean, RollingMean};
+        use crate::rolling::{Rolling, RollingBivariate, RollingRecompute};
+        use crate::stats::{Bivariate, Univariate};
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut rolling_mean: Rolling<f64> =
+            Rolling::with_min_periods(&mut running_mean, 5, 3).unwrap();
+        let mut owned_mean: RollingMean<f64> =
+            RollingMean::with_min_periods(Mean::new(), 5, 3).unwrap();
+        let mut default_mean: RollingMean<f64> = RollingMean::new(Mean::new(), 5).unwrap();
+        let mut rolling_kurtosis: RollingRecompute<f64, Kurtosis<f64>> =
+            RollingRecompute::with_min_periods(Kurtosis::default(), 5, 3).unwrap();
+        let mut rolling_cov: RollingBivariate<f64, Covariance<f64>> =
+            RollingBivariate::with_min_periods(Covariance::default(), 5, 3).unwrap();
+        assert!(default_mean.get().is_nan());
+        for (i, x) in [4., 8., 6., 2., 10., 12.].iter().enumerate() {
+            rolling_mean.update(*x);
+            owned_mean.update(*x);
+            default_mean.update(*x);
+            rolling_kurtosis.update(*x);
+            rolling_cov.update(*x, -*x);
+            let warm = i + 1 >= 3;
+            assert_eq!(!rolling_mean.get().is_nan(), warm);
+            assert_eq!(!owned_mean.get().is_nan(), warm);
+            assert_eq!(!rolling_kurtosis.get().is_nan(), warm);
+            assert_eq!(!rolling_cov.get().is_nan(), warm);
+            assert!(!default_mean.get().is_nan());
+        }
+        assert_eq!(owned_mean.get(), 7.6);
+        owned_mean.reset();
+        owned_mean.update(1.);
+        assert!(owned_mean.get().is_nan());
+        let mut ungated: RollingMean<f64> =
+            RollingMean::with_min_periods(Mean::new(), 5, 0).unwrap();
+        assert_eq!(ungated.get(), 0.);
+        ungated.update(1.);
+        assert_eq!(ungated.get(), 1.);
+        assert!(RollingMean::with_min_periods(Mean::<f64>::new(), 5, 6).is_err());
+        // Serialized rolling statistics without `min_periods` deserialize with the default.
+        let serialized =
+            r#"{"to_roll":{"mean":0.0,"n":{"count":0.0}},"window_size":2,"window":[]}"#;
+        let mut deserialized: RollingMean<f64> = serde_json::from_str(serialized).unwrap();
+        assert!(deserialized.get().is_nan());
+        deserialized.update(3.);
+        assert_eq!(deserialized.get(), 3.);
+    }
+    #[test]
+    fn pop_oldest_reverts_the_front_value() {
+        use crate::covariance::Covariance;
+        use crate::kurtosis::Kurtosis;
+        use crate::mean::Mean;
+        use crate::rolling::{RollingBivariate, RollingOwned, RollingRecompute};
+        use crate::stats::{Bivariate, Univariate};
+        use crate::variance::{RollingVariance, Variance};
+        let data = [2., 9., 4., 6., 5.];
+        let mut rolling_var: RollingVariance<f64> =
+            RollingVariance::new(Variance::default(), 5).unwrap();
+        let mut rolling_mean: RollingOwned<f64, Mean<f64>> =
+            RollingOwned::new(Mean::new(), 5).unwrap();
+        let mut rolling_kurtosis: RollingRecompute<f64, Kurtosis<f64>> =
+            RollingRecompute::new(5).unwrap();
+        let mut rolling_cov: RollingBivariate<f64, Covariance<f64>> =
+            RollingBivariate::new(Covariance::default(), 5).unwrap();
+        for x in data {
+            rolling_var.update(x);
+            rolling_mean.update(x);
+            rolling_kurtosis.update(x);
+            rolling_cov.update(x, 2. * x);
+        }
+        assert!(rolling_var.is_full());
+        assert_eq!(rolling_var.pop_oldest(), Some(2.));
+        assert_eq!(rolling_var.pop_oldest(), Some(9.));
+        assert_eq!(rolling_mean.pop_oldest(), Some(2.));
+        assert_eq!(rolling_mean.pop_oldest(), Some(9.));
+        assert_eq!(rolling_kurtosis.pop_oldest(), Some(2.));
+        assert_eq!(rolling_kurtosis.pop_oldest(), Some(9.));
+        assert_eq!(rolling_cov.pop_oldest(), Some((2., 4.)));
+        assert_eq!(rolling_cov.pop_oldest(), Some((9., 18.)));
+        assert_eq!(rolling_var.len(), 3);
+        assert_eq!(rolling_mean.get(), 5.);
+        assert!((rolling_var.get() - 1.).abs() < 1e-12);
+        assert!((rolling_cov.get() - 2.).abs() < 1e-12);
+        let mut kurtosis: Kurtosis<f64> = Kurtosis::default();
+        for x in [4., 6., 5.] {
+            kurtosis.update(x);
+        }
+        assert_eq!(rolling_kurtosis.get(), kurtosis.get());
+        // The window keeps rolling normally after explicit evictions.
+        for x in [7., 8., 9.] {
+            rolling_mean.update(x);
+        }
+        assert_eq!(rolling_mean.get(), 7.);
+        for _ in 0..3 {
+            rolling_var.pop_oldest();
+        }
+        assert!(rolling_var.is_empty());
+        assert_eq!(rolling_var.pop_oldest(), None);
+    }
+    #[test]
+    fn time_rolling_keeps_recent_values() {
+        use crate::mean::Mean;
+        use crate::rolling::TimeRolling;
+        use crate::stats::Univariate;
+        use crate::sum::Sum;
+        let data = [
+            (0., 5.),
+            (0.5, 1.),
+            (3.2, 4.),
+            (3.3, 2.),
+            (9., 8.),
+            (11.7, 3.),
+            (30., 6.),
+        ];
+        let horizon = 5.;
+        let mut rolling_mean: TimeRolling<f64, Mean<f64>> =
+            TimeRolling::new(Mean::new(), horizon).unwrap();
+        for (i, (t, x)) in data.iter().enumerate() {
+            rolling_mean.update_at(*x, *t);
+            let recent: Vec<f64> = data[..=i]
+                .iter()
+                .filter(|(s, _)| *s >= t - horizon)
+                .map(|(_, y)| *y)
+                .collect();
+            assert_eq!(rolling_mean.len(), recent.len());
+            let expected = recent.iter().sum::<f64>() / recent.len() as f64;
+            assert!((rolling_mean.get() - expected).abs() < 1e-12);
+        }
+        // Only the value seen at time 30 is left.
+        assert_eq!(rolling_mean.get(), 6.);
+        rolling_mean.expire(40.);
+        assert!(rolling_mean.is_empty());
+        assert_eq!(rolling_mean.last_time(), Some(40.));
+        // Without timestamps the values are one time unit apart.
+        let mut rolling_sum: TimeRolling<f64, Sum<f64>> = TimeRolling::new(Sum::new(), 2.).unwrap();
+        for x in [1., 2., 3., 4., 5.] {
+            rolling_sum.update(x);
+        }
+        assert_eq!(rolling_sum.get(), 12.);
+        rolling_sum.reset();
+        assert_eq!(rolling_sum.last_time(), None);
+        assert!(TimeRolling::new(Sum::<f64>::new(), -1.).is_err());
+    }
+    #[test]
+    fn absorb_matches_sequential_updates() {
+        use crate::covariance::Covariance;
+        use crate::mean::{Mean, RollingMean};
+        use crate::rolling::RollingBivariate;
+        use crate::stats::{Bivariate, Univariate};
+        let data: Vec<f64> = (0..30).map(|i| ((i * 7) % 13) as f64 * 0.5).collect();
+        for split in [0, 3, 20, 27] {
+            let mut sequential: RollingMean<f64> = RollingMean::new(Mean::new(), 8).unwrap();
+            let mut first: RollingMean<f64> = RollingMean::new(Mean::new(), 8).unwrap();
+            let mut second: RollingMean<f64> = RollingMean::new(Mean::new(), 8).unwrap();
+            for x in data.iter() {
+                sequential.update(*x);
+            }
+            for x in data[..split].iter() {
+                first.update(*x);
+            }
+            for x in data[split..].iter() {
+                second.update(*x);
+            }
+            first.absorb(&second);
+            // `second` only keeps its last 8 values, which are also the last 8 of the stream.
+            assert!((first.get() - sequential.get()).abs() < 1e-12);
+            assert_eq!(first.len(), sequential.len());
+        }
+        let mut first: RollingBivariate<f64, Covariance<f64>> =
+            RollingBivariate::new(Covariance::default(), 4).unwrap();
+        let mut second = first.clone();
+        let mut sequential = first.clone();
+        for (i, x) in data.iter().enumerate() {
+            sequential.update(*x, -x * x);
+            if i < 2 {
+                first.update(*x, -x * x);
+            } else {
+                second.update(*x, -x * x);
+            }
+        }
+        first.absorb(&second);
+        assert!((first.get() - sequential.get()).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,209 @@
+use num::{Bounded, Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::maximum::Max;
+use crate::minimum::Min;
+use crate::stats::Univariate;
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running min-max scaler, maps `x` to `(x - min) / (max - min)` with the extremes seen so far.
+/// `transform` scales a value without fitting it, `update` fits a value and `get` returns the most
+/// recent value scaled, `NaN` before the first update. The scaled value is 0 while the range is
+/// empty. A value beyond the extremes seen during fitting is mapped outside of `[0, 1]`, unless
+/// the scaler is built with `clamped`.
+/// # Examples
+/// ```
+/// use watermill::scaler::MinMaxScaler;
+/// use watermill::stats::Univariate;
+/// let mut scaler: MinMaxScaler<f64> = MinMaxScaler::new();
+/// let mut clamped_scaler: MinMaxScaler<f64> = MinMaxScaler::clamped();
+/// for x in [2., 6., 4.].iter(){
+///     scaler.update(*x);
+///     clamped_scaler.update(*x);
+/// }
+/// assert_eq!(scaler.get(), 0.5);
+/// assert_eq!(scaler.transform(10.), 2.);
+/// assert_eq!(clamped_scaler.transform(10.), 1.);
+/// assert_eq!(clamped_scaler.transform(0.), 0.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MinMaxScaler<F: Float + FromPrimitive + AddAssign + SubAssign + Bounded> {
+    pub min: Min<F>,
+    pub max: Max<F>,
+    /// If `true`, the scaled values are clamped to `[0, 1]`.
+    pub clamp: bool,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + Bounded> MinMaxScaler<F> {
+    pub fn new() -> Self {
+        Self {
+            min: Min::new(),
+            max: Max::new(),
+            clamp: false,
+            last: None,
+        }
+    }
+    /// Scaler whose scaled values are clamped to `[0, 1]`.
+    pub fn clamped() -> Self {
+        Self {
+            clamp: true,
+            ..Self::new()
+        }
+    }
+    /// Scales `x` with the current extremes, the scaler is left untouched.
+    pub fn transform(&self, x: F) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        let range = self.max.get() - self.min.get();
+        if range <= zero {
+            return zero;
+        }
+        let scaled = (x - self.min.get()) / range;
+        if self.clamp {
+            return scaled.max(zero).min(F::from_f64(1.).unwrap());
+        }
+        scaled
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + Bounded> Default for MinMaxScaler<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign + Bounded> Univariate<F> for MinMaxScaler<F> {
+    fn update(&mut self, x: F) {
+        self.min.update(x);
+        self.max.update(x);
+        self.last = Some(x);
+    }
+    fn get(&self) -> F {
+        match self.last {
+            Some(x) => self.transform(x),
+            None => F::nan(),
+        }
+    }
+    fn reset(&mut self) {
+        self.min.reset();
+        self.max.reset();
+        self.last = None;
+    }
+}
+
+/// Running standard scaler, maps `x` to `(x - mean) / std` with the population standard deviation
+/// of the values seen so far.
+/// `transform` scales a value without fitting it, `update` fits a value and `get` returns the most
+/// recent value scaled, `NaN` before the first update. The scaled value is 0 while the standard
+/// deviation is 0. A scaler built with `clamped(bound)` clamps the scaled values to
+/// `[-bound, bound]`.
+/// # Examples
+/// ```
+/// use watermill::scaler::StandardScaler;
+/// use watermill::stats::Univariate;
+/// let mut scaler: StandardScaler<f64> = StandardScaler::clamped(3.).unwrap();
+/// for x in [1., 3., 1., 3.].iter(){
+///     scaler.update(*x);
+/// }
+/// assert_eq!(scaler.get(), 1.);
+/// assert_eq!(scaler.transform(0.), -2.);
+/// assert_eq!(scaler.transform(100.), 3.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct StandardScaler<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+    /// Bound of the scaled values, `None` leaves them unbounded.
+    pub clamp: Option<F>,
+    last: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> StandardScaler<F> {
+    pub fn new() -> Self {
+        Self {
+            variance: Variance::new(0),
+            clamp: None,
+            last: None,
+        }
+    }
+    /// Scaler whose scaled values are clamped to `[-bound, bound]`, `bound` must be greater than 0.
+    pub fn clamped(bound: F) -> Result<Self, &'static str> {
+        if bound <= F::from_f64(0.).unwrap() {
+            return Err("bound should be greater than 0");
+        }
+        Ok(Self {
+            clamp: Some(bound),
+            ..Self::new()
+        })
+    }
+    /// Scales `x` with the current mean and standard deviation, the scaler is left untouched.
+    pub fn transform(&self, x: F) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        let std = self.variance.get().max(zero).sqrt();
+        if std == zero {
+            return zero;
+        }
+        let scaled = (x - self.variance.mean.get()) / std;
+        match self.clamp {
+            Some(bound) => scaled.max(-bound).min(bound),
+            None => scaled,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for StandardScaler<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for StandardScaler<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+        self.last = Some(x);
+    }
+    fn get(&self) -> F {
+        match self.last {
+            Some(x) => self.transform(x),
+            None => F::nan(),
+        }
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+        self.last = None;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        use crate::scaler::{MinMaxScaler, StandardScaler};
+        use crate::stats::Univariate;
+        let mut min_max: MinMaxScaler<f64> = MinMaxScaler::clamped();
+        let mut standard: StandardScaler<f64> = StandardScaler::clamped(2.).unwrap();
+        let mut unbounded: StandardScaler<f64> = StandardScaler::new();
+        assert!(min_max.get().is_nan());
+        assert_eq!(min_max.transform(5.), 0.);
+        for i in 0..100 {
+            let x = (i % 10) as f64;
+            min_max.update(x);
+            standard.update(x);
+            unbounded.update(x);
+        }
+        // Serving skew: a value far beyond the fitted range.
+        assert_eq!(min_max.transform(50.), 1.);
+        assert_eq!(min_max.transform(-50.), 0.);
+        assert!((min_max.transform(4.5) - 0.5).abs() < 1e-12);
+        assert!(unbounded.transform(50.) > 2.);
+        assert_eq!(standard.transform(50.), 2.);
+        assert_eq!(standard.transform(-50.), -2.);
+        assert!((standard.transform(4.5)).abs() < 1e-12);
+        // The most recent fitted value is at the edge of the range, it is not clamped away.
+        min_max.update(-50.);
+        assert_eq!(min_max.get(), 0.);
+        assert!(StandardScaler::<f64>::clamped(0.).is_err());
+    }
+}
@@ -69,18 +69,12 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Skew<F>
         self.central_moments.update_m2();
     }
     fn get(&self) -> F {
-        let n = self.central_moments.count.get();
-
-        let mut skew: F = F::from_f64(0.).unwrap();
-        if self.central_moments.m2 != F::from_f64(0.).unwrap() {
-            skew += n.powf(F::from_f64(0.5).unwrap()) * self.central_moments.m3
-                / self.central_moments.m2.powf(F::from_f64(1.5).unwrap());
-        }
-        if (!self.bias) && n > F::from_f64(2.).unwrap() {
-            return ((n - F::from_f64(1.).unwrap()) * n).powf(F::from_f64(0.5).unwrap())
-                / (n - F::from_f64(2.).unwrap())
-                * skew;
-        }
-        skew
+        self.central_moments.skewness(self.bias)
+    }
+    fn reset(&mut self) {
+        *self = Self::new(self.bias);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.central_moments.count.n_samples()
     }
 }
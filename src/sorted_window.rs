@@ -28,6 +28,13 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> SortedWindow<F> {
     pub fn is_empty(&self) -> bool {
         self.sorted_window.len() == 0
     }
+    pub fn clear(&mut self) {
+        self.sorted_window.clear();
+        self.unsorted_window.clear();
+    }
+    pub fn is_full(&self) -> bool {
+        self.sorted_window.len() == self.window_size
+    }
 
     pub fn front(&self) -> F {
         self.sorted_window
@@ -41,6 +48,14 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> SortedWindow<F> {
             .expect("The value is NaN")
             .into_inner()
     }
+    /// Average rank, starting at 1, of `value` in the window. Tied values share the mean of
+    /// their ranks.
+    pub fn rank(&self, value: F) -> F {
+        let value = NotNan::new(value).expect("Value is NaN");
+        let first = self.sorted_window.partition_point(|x| *x < value);
+        let last = self.sorted_window.partition_point(|x| *x <= value);
+        F::from_usize(first + last + 1).unwrap() / F::from_f64(2.).unwrap()
+    }
     pub fn push_back(&mut self, value: F) {
         // Before add the newest value to the sorted window
         // we should remove the oldest value
@@ -69,3 +84,25 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Index<usize> for SortedWi
     }
     type Output = F;
 }
+#[cfg(test)]
+mod test {
+    #[test]
+    fn capacity_is_stable_once_full() {
+        use crate::sorted_window::SortedWindow;
+        let mut sorted_window: SortedWindow<f64> = SortedWindow::new(10);
+        let capacities = (
+            sorted_window.sorted_window.capacity(),
+            sorted_window.unsorted_window.capacity(),
+        );
+        for i in 0..1000 {
+            sorted_window.push_back(((i * 7) % 11) as f64);
+            assert_eq!(
+                (
+                    sorted_window.sorted_window.capacity(),
+                    sorted_window.unsorted_window.capacity()
+                ),
+                capacities
+            );
+        }
+    }
+}
@@ -1,20 +1,383 @@
-use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
-pub trait Univariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
+use num::Num;
+use serde::{Deserialize, Serialize};
+
+pub trait Univariate<F: Num + Copy> {
     fn update(&mut self, x: F);
     fn get(&self) -> F;
+    /// Brings the statistic back to its initial state, parameters such as `ddof` or the window
+    /// size are kept.
+    /// The default implementation does nothing, so that implementations written before `reset`
+    /// was added keep compiling: they should override it, otherwise the statistic keeps its state
+    /// when it is reset, e.g. by `get_and_reset` or `RollingOwned::reset`.
+    fn reset(&mut self) {}
+    /// Number of values the statistic currently accounts for, for rolling statistics it is the
+    /// number of values in the window. `None` for statistics which do not count their values,
+    /// such as `Sum`, `Max` or the exponentially weighted ones, which is the default.
+    fn n_samples(&self) -> Option<usize> {
+        None
+    }
+    /// Returns the current value and resets the statistic, e.g. to report a value per interval.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// running_mean.update(2.);
+    /// running_mean.update(4.);
+    /// assert_eq!(running_mean.get_and_reset(), 3.);
+    /// running_mean.update(10.);
+    /// assert_eq!(running_mean.get_and_reset(), 10.);
+    /// ```
+    fn get_and_reset(&mut self) -> F {
+        let value = self.get();
+        self.reset();
+        value
+    }
+    /// Updates the statistic with a reading which may be missing.
+    /// `None` is ignored by default, exponentially weighted statistics override this to keep
+    /// decaying the weight of past values across the gap.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// for x in [Some(1.), None, Some(3.)]{
+    ///     running_mean.update_opt(x);
+    /// }
+    /// assert_eq!(running_mean.get(), 2.);
+    /// ```
+    fn update_opt(&mut self, x: Option<F>) {
+        if let Some(x) = x {
+            self.update(x);
+        }
+    }
 }
 
-pub trait Bivariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
+pub trait Bivariate<F: Num + Copy> {
     fn update(&mut self, x: F, y: F);
     fn get(&self) -> F;
+    /// Brings the statistic back to its initial state, parameters such as `ddof` or the window
+    /// size are kept.
+    /// The default implementation does nothing, so that implementations written before `reset`
+    /// was added keep compiling: they should override it, otherwise the statistic keeps its state
+    /// when it is reset, e.g. by `RollingBivariate::reset`.
+    fn reset(&mut self) {}
+    /// Number of pairs the statistic currently accounts for, for rolling statistics it is the
+    /// number of pairs in the window. `None` for statistics which do not count their pairs,
+    /// which is the default.
+    fn n_samples(&self) -> Option<usize> {
+        None
+    }
 }
 
-pub trait Revertable<F: Float + FromPrimitive + AddAssign + SubAssign> {
+pub trait Revertable<F: Num + Copy> {
     fn revert(&mut self, x: F) -> Result<(), &'static str>;
 }
 
-pub trait RollableUnivariate<F: Float + FromPrimitive + AddAssign + SubAssign>:
-    Revertable<F> + Univariate<F>
-{
+pub trait RevertableBivariate<F: Num + Copy> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str>;
+}
+
+pub trait RollableBivariate<F: Num + Copy>: RevertableBivariate<F> + Bivariate<F> {}
+
+pub trait RollableUnivariate<F: Num + Copy>: Revertable<F> + Univariate<F> {
+    /// Value of the statistic as if `x` had never been seen, the statistic itself is left untouched.
+    /// The default implementation reverts `x` on a clone.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::{RollableUnivariate, Univariate};
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// for i in 1..=3{
+    ///     running_mean.update(i as f64);
+    /// }
+    /// assert_eq!(running_mean.get_without(3.), Ok(1.5));
+    /// assert_eq!(running_mean.get(), 2.);
+    /// ```
+    fn get_without(&self, x: F) -> Result<F, &'static str>
+    where
+        Self: Clone + Sized,
+    {
+        let mut without = self.clone();
+        without.revert(x)?;
+        Ok(without.get())
+    }
+}
+
+/// Feeds the values of `data` to `stat` and returns it, e.g. for a one-shot computation over a
+/// slice. `Mean`, `Variance` and `Quantile` also have a `fold` constructor built on it.
+/// # Examples
+/// ```
+/// use watermill::maximum::Max;
+/// use watermill::stats::{fold, Univariate};
+/// assert_eq!(fold(Max::new(), &[1., 4., 2.]).get(), 4.);
+/// ```
+pub fn fold<F: Num + Copy, U: Univariate<F>>(mut stat: U, data: &[F]) -> U {
+    for x in data.iter() {
+        stat.update(*x);
+    }
+    stat
+}
+
+/// Statistics which can absorb the state of another instance, e.g. partial results computed
+/// over chunks of a stream.
+pub trait Mergeable<F: Num + Copy> {
+    /// Combines `other` into `self`, as if the values seen by `other` had been seen by `self`.
+    fn merge(&mut self, other: &Self);
+    /// Statistic of the values of `data`. The slice is processed in blocks of `LANES` independent
+    /// partial accumulators which the compiler can vectorize, the lanes are merged at the end.
+    fn from_slice(data: &[F]) -> Self;
+}
+
+/// Number of partial accumulators used by `Mergeable::from_slice`.
+pub const LANES: usize = 8;
+
+/// Sum of `f(x)` over `data`, accumulated in `LANES` independent lanes.
+pub(crate) fn block_sum<F: Num + Copy, G: Fn(F) -> F>(data: &[F], f: G) -> F {
+    let mut lanes = [F::zero(); LANES];
+    let chunks = data.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        for (lane, x) in lanes.iter_mut().zip(chunk) {
+            *lane = *lane + f(*x);
+        }
+    }
+    let mut sum = lanes.iter().fold(F::zero(), |sum, lane| sum + *lane);
+    for x in remainder {
+        sum = sum + f(*x);
+    }
+    sum
+}
+
+/// Scalar summary of a statistic, its value and the number of samples behind it, for telemetry
+/// where the full internal state is not needed. `n` is `None`, written as `null`, for the
+/// statistics which do not count their samples.
+/// # Examples
+/// ```
+/// use watermill::maximum::Max;
+/// use watermill::mean::Mean;
+/// use watermill::stats::{StatSnapshot, Univariate};
+/// let mut running_mean: Mean<f64> = Mean::new();
+/// running_mean.update(1.);
+/// running_mean.update(2.);
+/// let snapshot = StatSnapshot::new(&running_mean);
+/// assert_eq!(snapshot.summary_json(), r#"{"value":1.5,"n":2}"#);
+/// let mut running_max: Max<f64> = Max::new();
+/// running_max.update(2.);
+/// assert_eq!(StatSnapshot::new(&running_max).summary_json(), r#"{"value":2.0,"n":null}"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatSnapshot<F> {
+    pub value: F,
+    pub n: Option<usize>,
+}
+
+impl<F: Num + Copy> StatSnapshot<F> {
+    /// Snapshot of a univariate statistic, `n` is its `n_samples`.
+    pub fn new<U: Univariate<F> + ?Sized>(stat: &U) -> Self {
+        Self {
+            value: stat.get(),
+            n: stat.n_samples(),
+        }
+    }
+    /// Snapshot of a bivariate statistic, `n` is its `n_samples`.
+    pub fn from_bivariate<B: Bivariate<F> + ?Sized>(stat: &B) -> Self {
+        Self {
+            value: stat.get(),
+            n: stat.n_samples(),
+        }
+    }
+}
+
+impl<F: Serialize> StatSnapshot<F> {
+    /// Compact JSON of the snapshot, `{"value":...,"n":...}`. A `NaN` value is written as `null`.
+    pub fn summary_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Field by field comparison of statistics within a tolerance, used to check serde round trips
+/// where the JSON re-parse of a float may differ from the original in the last bits.
+#[cfg(test)]
+pub(crate) trait ApproxEq<F: num::Float> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool;
+}
+
+/// `true` if `a` and `b` are within `eps`, two `NaN` or the same infinity.
+#[cfg(test)]
+pub(crate) fn close<F: num::Float>(a: F, b: F, eps: F) -> bool {
+    a == b || (a.is_nan() && b.is_nan()) || (a - b).abs() <= eps
+}
+
+/// Deterministic stream of uniform values in `[0, 1)`, from a 64-bit linear congruential
+/// generator, for tests which need noisy data.
+#[cfg(test)]
+pub(crate) fn uniform(mut seed: u64) -> impl FnMut() -> f64 {
+    move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn variance_snapshot() {
+        use crate::covariance::Covariance;
+        use crate::stats::{Bivariate, StatSnapshot, Univariate};
+        use crate::variance::Variance;
+        let mut running_variance: Variance<f64> = Variance::default();
+        for x in [1., 3., 5.] {
+            running_variance.update(x);
+        }
+        let snapshot = StatSnapshot::new(&running_variance);
+        assert_eq!(
+            snapshot,
+            StatSnapshot {
+                value: 4.,
+                n: Some(3)
+            }
+        );
+        let json = snapshot.summary_json();
+        assert_eq!(json, r#"{"value":4.0,"n":3}"#);
+        let deserialized: StatSnapshot<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, snapshot);
+        let mut running_cov: Covariance<f64> = Covariance::default();
+        running_cov.update(1., 2.);
+        running_cov.update(2., 4.);
+        assert_eq!(StatSnapshot::from_bivariate(&running_cov).n, Some(2));
+        let empty = StatSnapshot::new(&Variance::<f64>::default());
+        assert_eq!(empty.summary_json(), r#"{"value":0.0,"n":0}"#);
+    }
+    #[test]
+    fn from_slice_matches_sequential_updates() {
+        use crate::mean::Mean;
+        use crate::stats::{Mergeable, Univariate};
+        use crate::sum::Sum;
+        use crate::variance::Variance;
+        for len in [0, 1, 7, 8, 9, 100, 1003] {
+            let data: Vec<f64> = (0..len)
+                .map(|i| 1e6 + ((i * 37) % 101) as f64 / 7.)
+                .collect();
+            let (mut sum, mut mean, mut variance) = (Sum::new(), Mean::new(), Variance::default());
+            for x in data.iter() {
+                sum.update(*x);
+                mean.update(*x);
+                variance.update(*x);
+            }
+            assert!((Sum::from_slice(&data).get() - sum.get()).abs() <= 1e-9 * sum.get().abs());
+            assert!((Mean::from_slice(&data).get() - mean.get()).abs() < 1e-9);
+            assert!((Variance::from_slice(&data).get() - variance.get()).abs() < 1e-6);
+            // Chunks merged together.
+            let mut merged: Variance<f64> = Variance::default();
+            for chunk in data.chunks(64) {
+                merged.merge(&Variance::from_slice(chunk));
+            }
+            assert!((merged.get() - variance.get()).abs() < 1e-6);
+            assert_eq!(merged.mean.n.get(), len as f64);
+            let population = Variance::from_slice_with_ddof(&data, 0);
+            assert_eq!(population.ddof, 0);
+            assert!((population.get() - Variance::fold(&data, 0).get()).abs() < 1e-6);
+        }
+    }
+    #[test]
+    fn every_statistic_overrides_reset() {
+        // `reset` does nothing by default, each statistic of the crate brings its own.
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut n_impls = 0;
+        for entry in std::fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            let lines: Vec<&str> = source.lines().collect();
+            for (i, line) in lines.iter().enumerate() {
+                let is_impl = line.starts_with("impl")
+                    && (line.contains("> Univariate<") || line.contains("> Bivariate<"));
+                if !is_impl {
+                    continue;
+                }
+                n_impls += 1;
+                let overrides_reset = lines[i..]
+                    .iter()
+                    .take_while(|line| **line != "}")
+                    .any(|line| line.trim_start().starts_with("fn reset(&mut self)"));
+                assert!(
+                    overrides_reset,
+                    "{}:{} does not override reset",
+                    path.display(),
+                    i + 1
+                );
+            }
+        }
+        assert!(n_impls > 90);
+    }
+    #[test]
+    fn univariate_defaults() {
+        use crate::stats::Univariate;
+        // A statistic implementing only the methods which used to be required.
+        struct Last(f64);
+        impl Univariate<f64> for Last {
+            fn update(&mut self, x: f64) {
+                self.0 = x;
+            }
+            fn get(&self) -> f64 {
+                self.0
+            }
+        }
+        let mut last = Last(0.);
+        last.update(2.);
+        assert_eq!(last.n_samples(), None);
+        assert_eq!(last.get_and_reset(), 2.);
+        assert_eq!(last.get(), 2.);
+    }
+    #[test]
+    fn bivariate_defaults() {
+        use crate::stats::Bivariate;
+        // A statistic implementing only the methods which used to be required.
+        struct LastProduct(f64);
+        impl Bivariate<f64> for LastProduct {
+            fn update(&mut self, x: f64, y: f64) {
+                self.0 = x * y;
+            }
+            fn get(&self) -> f64 {
+                self.0
+            }
+        }
+        let mut last_product = LastProduct(0.);
+        last_product.update(2., 3.);
+        assert_eq!(last_product.n_samples(), None);
+        last_product.reset();
+        assert_eq!(last_product.get(), 6.);
+    }
+    #[test]
+    fn univariate_n_samples() {
+        use crate::count::Count;
+        use crate::mean::{Mean, RollingMean};
+        use crate::quantile::RollingQuantile;
+        use crate::stats::{StatSnapshot, Univariate};
+        use crate::sum::Sum;
+        use crate::variance::Variance;
+        let mut count: Count<f64> = Count::new();
+        let mut variance: Variance<f64> = Variance::default();
+        let mut rolling_mean: RollingMean<f64> = RollingMean::new(Mean::new(), 3).unwrap();
+        let mut rolling_median: RollingQuantile<f64> = RollingQuantile::new(0.5, 3).unwrap();
+        let mut sum: Sum<f64> = Sum::new();
+        for x in [1., 2., 3., 4., 5.] {
+            count.update(x);
+            variance.update(x);
+            rolling_mean.update(x);
+            rolling_median.update(x);
+            sum.update(x);
+        }
+        assert_eq!(count.n_samples(), Some(5));
+        assert_eq!(variance.n_samples(), Some(5));
+        assert_eq!(rolling_mean.n_samples(), Some(3));
+        assert_eq!(rolling_median.n_samples(), Some(3));
+        // `Sum` does not count its values.
+        assert_eq!(sum.n_samples(), None);
+        assert_eq!(StatSnapshot::new(&rolling_mean).n, Some(3));
+        variance.reset();
+        assert_eq!(variance.n_samples(), Some(0));
+    }
 }
@@ -0,0 +1,82 @@
+use num::Num;
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Forwards only every `k`-th update to the wrapped statistic, the first update included.
+/// Useful to downsample very high-frequency streams before an expensive statistic.
+/// After `n` updates the wrapped statistic has seen `ceil(n / k)` samples.
+/// # Arguments
+/// * `inner` - The statistic receiving the downsampled stream.
+/// * `k` - Stride, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::strided::Strided;
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let mut strided_mean = Strided::new(Mean::new(), 3).unwrap();
+/// for i in 0..10{
+///     strided_mean.update(i as f64);
+/// }
+/// // The mean only saw 0, 3, 6 and 9.
+/// assert_eq!(strided_mean.get(), 4.5);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Strided<U> {
+    pub inner: U,
+    k: usize,
+    n: usize,
+}
+
+impl<U> Strided<U> {
+    pub fn new(inner: U, k: usize) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k should not equals to 0");
+        }
+        Ok(Self { inner, k, n: 0 })
+    }
+}
+
+impl<F: Num + Copy, U: Univariate<F>> Univariate<F> for Strided<U> {
+    fn update(&mut self, x: F) {
+        if self.n.is_multiple_of(self.k) {
+            self.inner.update(x);
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.inner.get()
+    }
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.inner.n_samples()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn inner_sees_ceil_n_over_k() {
+        use crate::count::Count;
+        use crate::stats::Univariate;
+        use crate::strided::Strided;
+        for k in 1..=7 {
+            for n in 0..=30_usize {
+                let mut strided_count: Strided<Count<f64>> = Strided::new(Count::new(), k).unwrap();
+                for i in 0..n {
+                    strided_count.update(i as f64);
+                }
+                assert_eq!(strided_count.get(), n.div_ceil(k) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_stride() {
+        use crate::count::Count;
+        use crate::strided::Strided;
+        assert!(Strided::new(Count::<f64>::new(), 0).is_err());
+    }
+}
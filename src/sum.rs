@@ -1,5 +1,9 @@
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
-use num::{Float, FromPrimitive};
+use crate::rolling::RollingBivariate;
+use crate::stats::{
+    block_sum, Bivariate, Mergeable, Revertable, RevertableBivariate, RollableBivariate,
+    RollableUnivariate, Univariate,
+};
+use num::{Num, Signed};
 use serde::{Deserialize, Serialize};
 use std::ops::{AddAssign, SubAssign};
 /// Running sum.
@@ -20,33 +24,316 @@ use std::ops::{AddAssign, SubAssign};
 /// }
 /// assert_eq!(running_sum.get(), 0.);
 /// ```
+/// `Sum` does not require a floating point type:
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::sum::Sum;
+/// let mut running_sum: Sum<i64> = Sum::new();
+/// for i in -5..10{
+///     running_sum.update(i);
+/// }
+/// assert_eq!(running_sum.get(), 30);
+/// ```
 ///
 #[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
-pub struct Sum<F: Float + FromPrimitive + AddAssign + SubAssign> {
+pub struct Sum<F: Num + Copy + AddAssign + SubAssign> {
     pub sum: F,
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Sum<F> {
+impl<F: Num + Copy + AddAssign + SubAssign> Sum<F> {
     pub fn new() -> Self {
-        Self {
-            sum: F::from_f64(0.0).unwrap(),
-        }
+        Self { sum: F::zero() }
     }
 }
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Sum<F> {
+
+macro_rules! impl_const_new {
+    ($($t:ty),*) => {
+        $(
+            impl Sum<$t> {
+                /// Same as `Sum::new` but usable in `const` and `static` contexts.
+                pub const fn new_const() -> Self {
+                    Self { sum: 0.0 }
+                }
+            }
+        )*
+    };
+}
+impl_const_new!(f32, f64);
+
+impl<F: Num + Copy + AddAssign + SubAssign> Univariate<F> for Sum<F> {
     fn update(&mut self, x: F) {
         self.sum += x;
     }
     fn get(&self) -> F {
         self.sum
     }
+    fn reset(&mut self) {
+        self.sum = F::zero();
+    }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Sum<F> {
+/// # Examples
+/// ```
+/// use watermill::stats::{Mergeable, Univariate};
+/// use watermill::sum::Sum;
+/// let data: Vec<i64> = (1..=100).collect();
+/// let running_sum: Sum<i64> = Sum::from_slice(&data);
+/// assert_eq!(running_sum.get(), 5050);
+/// ```
+impl<F: Num + Copy + AddAssign + SubAssign> Mergeable<F> for Sum<F> {
+    fn merge(&mut self, other: &Self) {
+        self.sum += other.sum;
+    }
+    fn from_slice(data: &[F]) -> Self {
+        Self {
+            sum: block_sum(data, |x| x),
+        }
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Revertable<F> for Sum<F> {
     fn revert(&mut self, x: F) -> std::result::Result<(), &'static str> {
         self.sum -= x;
         Ok(())
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Sum<F> {}
+impl<F: Num + Copy + AddAssign + SubAssign> RollableUnivariate<F> for Sum<F> {
+    fn get_without(&self, x: F) -> Result<F, &'static str> {
+        Ok(self.sum - x)
+    }
+}
+/// Running sum of absolute values, i.e. the L1 norm of the values seen so far.
+/// # Examples
+/// ```
+/// use watermill::stats::{Revertable, Univariate};
+/// use watermill::sum::AbsSum;
+/// let mut running_abs_sum: AbsSum<f64> = AbsSum::new();
+/// for x in [1., -2., 3., -4.].iter(){
+///     running_abs_sum.update(*x);
+/// }
+/// assert_eq!(running_abs_sum.get(), 10.);
+/// running_abs_sum.revert(-4.).unwrap();
+/// assert_eq!(running_abs_sum.get(), 6.);
+/// ```
+/// Since it is revertable, it can be rolled:
+/// ```
+/// use watermill::rolling::windowed;
+/// use watermill::sum::AbsSum;
+/// let data = vec![1., -2., 3., -4., 5.];
+/// assert_eq!(windowed(&data, 2, AbsSum::new()), vec![3., 5., 7., 9.]);
+/// ```
+///
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct AbsSum<F: Signed + Copy + AddAssign + SubAssign> {
+    pub sum: F,
+}
+
+impl<F: Signed + Copy + AddAssign + SubAssign> AbsSum<F> {
+    pub fn new() -> Self {
+        Self { sum: F::zero() }
+    }
+}
+
+impl<F: Signed + Copy + AddAssign + SubAssign> Univariate<F> for AbsSum<F> {
+    fn update(&mut self, x: F) {
+        self.sum += x.abs();
+    }
+    fn get(&self) -> F {
+        self.sum
+    }
+    fn reset(&mut self) {
+        self.sum = F::zero();
+    }
+}
+
+impl<F: Signed + Copy + AddAssign + SubAssign> Revertable<F> for AbsSum<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.sum -= x.abs();
+        Ok(())
+    }
+}
+
+impl<F: Signed + Copy + AddAssign + SubAssign> RollableUnivariate<F> for AbsSum<F> {}
+
+/// Running sum of squares, i.e. the squared L2 norm of the values seen so far.
+/// # Examples
+/// ```
+/// use watermill::stats::{Revertable, Univariate};
+/// use watermill::sum::SquaredSum;
+/// let mut running_squared_sum: SquaredSum<i64> = SquaredSum::new();
+/// for x in [1, -2, 3, -4]{
+///     running_squared_sum.update(x);
+/// }
+/// assert_eq!(running_squared_sum.get(), 30);
+/// running_squared_sum.revert(3).unwrap();
+/// assert_eq!(running_squared_sum.get(), 21);
+/// ```
+///
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SquaredSum<F: Num + Copy + AddAssign + SubAssign> {
+    pub sum: F,
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> SquaredSum<F> {
+    pub fn new() -> Self {
+        Self { sum: F::zero() }
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Univariate<F> for SquaredSum<F> {
+    fn update(&mut self, x: F) {
+        self.sum += x * x;
+    }
+    fn get(&self) -> F {
+        self.sum
+    }
+    fn reset(&mut self) {
+        self.sum = F::zero();
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Revertable<F> for SquaredSum<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.sum -= x * x;
+        Ok(())
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> RollableUnivariate<F> for SquaredSum<F> {}
+
+/// Running sum of products `Σ x_i * y_i`, a building block of bivariate statistics.
+/// # Examples
+/// ```
+/// use watermill::stats::{Bivariate, RevertableBivariate};
+/// use watermill::sum::SumOfProducts;
+/// let mut running_sum: SumOfProducts<i64> = SumOfProducts::new();
+/// for (x, y) in [(1, 2), (-3, 4), (5, 6)]{
+///     running_sum.update(x, y);
+/// }
+/// assert_eq!(running_sum.get(), 20);
+/// running_sum.revert(-3, 4).unwrap();
+/// assert_eq!(running_sum.get(), 32);
+/// ```
+///
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SumOfProducts<F: Num + Copy + AddAssign + SubAssign> {
+    pub sum: F,
+    n: usize,
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> SumOfProducts<F> {
+    pub fn new() -> Self {
+        Self {
+            sum: F::zero(),
+            n: 0,
+        }
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> Bivariate<F> for SumOfProducts<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.sum += x * y;
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.sum
+    }
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> RevertableBivariate<F> for SumOfProducts<F> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        if self.n == 0 {
+            return Err("SumOfProducts cannot revert more pairs than it has seen");
+        }
+        self.sum -= x * y;
+        self.n -= 1;
+        Ok(())
+    }
+}
+
+impl<F: Num + Copy + AddAssign + SubAssign> RollableBivariate<F> for SumOfProducts<F> {}
+
+/// Rolling sum of products over the last `window_size` pairs, each pair is added when pushed and
+/// subtracted when evicted.
+/// # Examples
+/// ```
+/// use watermill::stats::Bivariate;
+/// use watermill::sum::{RollingSumOfProducts, SumOfProducts};
+/// let mut rolling_sum: RollingSumOfProducts<f64> =
+///     RollingSumOfProducts::new(SumOfProducts::new(), 2).unwrap();
+/// for (x, y) in [(1., 2.), (3., 4.), (5., 6.)].iter(){
+///     rolling_sum.update(*x, *y);
+/// }
+/// assert_eq!(rolling_sum.get(), 42.);
+/// ```
+pub type RollingSumOfProducts<F> = RollingBivariate<F, SumOfProducts<F>>;
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn integer_sum() {
+        use crate::stats::{Revertable, Univariate};
+        use crate::sum::Sum;
+        let mut running_sum: Sum<i64> = Sum::new();
+        for i in -100..=100_i64 {
+            running_sum.update(i * i);
+        }
+        assert_eq!(running_sum.get(), 676_700);
+        running_sum.revert(10_000).unwrap();
+        assert_eq!(running_sum.get(), 666_700);
+    }
+
+    #[test]
+    fn const_sum() {
+        use crate::stats::Univariate;
+        use crate::sum::Sum;
+        const ZERO: Sum<f64> = Sum::<f64>::new_const();
+        static TOTAL: Sum<f64> = Sum::<f64>::new_const();
+        assert_eq!(ZERO.get(), 0.);
+        let mut running_sum = TOTAL;
+        for i in 1..10 {
+            running_sum.update(i as f64);
+        }
+        assert_eq!(running_sum.get(), 45.);
+        assert_eq!(TOTAL.get(), 0.);
+    }
+
+    #[test]
+    fn get_without_last() {
+        use crate::stats::{RollableUnivariate, Univariate};
+        use crate::sum::Sum;
+        let mut running_sum: Sum<f64> = Sum::new();
+        for x in [1.5, 2., -4.] {
+            running_sum.update(x);
+        }
+        assert_eq!(running_sum.get_without(-4.), Ok(3.5));
+        assert_eq!(running_sum.get(), -0.5);
+    }
+    #[test]
+    fn rolling_sum_of_products_matches_brute_force() {
+        use crate::stats::Bivariate;
+        use crate::sum::{RollingSumOfProducts, SumOfProducts};
+        let x: Vec<f64> = (0..80).map(|i| ((i * 7) % 13) as f64 * 0.5 - 2.).collect();
+        let y: Vec<f64> = (0..80)
+            .map(|i| ((i * 5) % 11) as f64 - 0.3 * x[i])
+            .collect();
+        for window_size in [1, 4, 25] {
+            let mut rolling_sum: RollingSumOfProducts<f64> =
+                RollingSumOfProducts::new(SumOfProducts::new(), window_size).unwrap();
+            for i in 0..x.len() {
+                rolling_sum.update(x[i], y[i]);
+                let start = (i + 1).saturating_sub(window_size);
+                let expected: f64 = (start..=i).map(|j| x[j] * y[j]).sum();
+                assert!((rolling_sum.get() - expected).abs() < 1e-9);
+                assert_eq!(rolling_sum.n_samples(), Some(i + 1 - start));
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running one-sample t-statistic of the mean against a null value,
+/// `t = (mean - null_mean) / sem` with `sem` the standard error of the mean computed with the
+/// sample variance (`ddof = 1`). `get` returns the t-statistic, `NaN` until two values are seen
+/// and infinite when all the values are equal.
+/// # Arguments
+/// * `null_mean` - Mean under the null hypothesis.
+/// # Examples
+/// ```
+/// use watermill::ttest::OneSampleT;
+/// use watermill::stats::Univariate;
+/// let mut one_sample_t: OneSampleT<f64> = OneSampleT::new(5.);
+/// for x in [5., 6., 7., 6., 5., 7.].iter(){
+///     one_sample_t.update(*x);
+/// }
+/// // The mean is 6 and the sample variance is 0.8, so sem = sqrt(0.8 / 6).
+/// assert_eq!(one_sample_t.n(), 6.);
+/// assert!((one_sample_t.get() - 7.5_f64.sqrt()).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Student's t-test](https://en.wikipedia.org/wiki/Student%27s_t-test#One-sample_t-test)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct OneSampleT<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+    pub null_mean: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> OneSampleT<F> {
+    pub fn new(null_mean: F) -> Self {
+        Self {
+            variance: Variance::sample(),
+            null_mean,
+        }
+    }
+    /// `(mean - null_mean) / sem`.
+    pub fn t_statistic(&self) -> F {
+        (self.variance.mean.get() - self.null_mean) / self.variance.standard_error()
+    }
+    /// Number of values seen, the degrees of freedom of the test are `n() - 1`.
+    pub fn n(&self) -> F {
+        self.variance.mean.weight()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for OneSampleT<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.t_statistic()
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for OneSampleT<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.variance.revert(x)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for OneSampleT<F> {}
+
+/// Welch's t-test comparing the means of two streams whose variances may differ,
+/// `t = (mean_a - mean_b) / sqrt(s_a² / n_a + s_b² / n_b)` with the sample variances of the
+/// groups. The degrees of freedom follow the Welch–Satterthwaite approximation. Both are `NaN`
+/// until each group has two values.
+/// # Examples
+/// ```
+/// use watermill::ttest::WelchTest;
+/// let mut welch: WelchTest<f64> = WelchTest::new();
+/// for x in [1., 2., 3.].iter(){
+///     welch.update_a(*x);
+/// }
+/// for x in [3., 5., 7.].iter(){
+///     welch.update_b(*x);
+/// }
+/// // s_a² / n_a = 1 / 3 and s_b² / n_b = 4 / 3.
+/// assert!((welch.t_statistic() + 3. / (5_f64 / 3.).sqrt()).abs() < 1e-12);
+/// assert!((welch.degrees_of_freedom() - 25. / 8.5).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on Welch's t-test](https://en.wikipedia.org/wiki/Welch%27s_t-test)
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct WelchTest<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub a: Variance<F>,
+    pub b: Variance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WelchTest<F> {
+    pub fn new() -> Self {
+        Self {
+            a: Variance::sample(),
+            b: Variance::sample(),
+        }
+    }
+    /// Adds `x` to the first group.
+    pub fn update_a(&mut self, x: F) {
+        self.a.update(x);
+    }
+    /// Adds `x` to the second group.
+    pub fn update_b(&mut self, x: F) {
+        self.b.update(x);
+    }
+    /// `(mean_a - mean_b) / sqrt(s_a² / n_a + s_b² / n_b)`, positive when the first group has the
+    /// larger mean.
+    pub fn t_statistic(&self) -> F {
+        let se2 = self.squared_error(&self.a) + self.squared_error(&self.b);
+        (self.a.mean.get() - self.b.mean.get()) / se2.sqrt()
+    }
+    /// Welch–Satterthwaite degrees of freedom,
+    /// `(s_a² / n_a + s_b² / n_b)² / ((s_a² / n_a)² / (n_a - 1) + (s_b² / n_b)² / (n_b - 1))`.
+    pub fn degrees_of_freedom(&self) -> F {
+        let one = F::from_f64(1.).unwrap();
+        let (se2_a, se2_b) = (self.squared_error(&self.a), self.squared_error(&self.b));
+        let (n_a, n_b) = (self.a.mean.weight(), self.b.mean.weight());
+        (se2_a + se2_b).powi(2) / (se2_a.powi(2) / (n_a - one) + se2_b.powi(2) / (n_b - one))
+    }
+    /// Squared standard error of the mean of a group.
+    fn squared_error(&self, group: &Variance<F>) -> F {
+        group.standard_error().powi(2)
+    }
+    pub fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn different_means_give_a_large_t_statistic() {
+        use crate::ttest::WelchTest;
+        let mut welch: WelchTest<f64> = WelchTest::new();
+        welch.update_a(1.);
+        welch.update_b(1.);
+        assert!(welch.t_statistic().is_nan());
+        welch.reset();
+        // The groups have different sizes and spreads.
+        let a: Vec<f64> = (0..200).map(|i| 10. + ((i * 7) % 5) as f64 - 2.).collect();
+        let b: Vec<f64> = (0..120).map(|i| 13. + ((i * 4) % 9) as f64 - 4.).collect();
+        for x in a.iter() {
+            welch.update_a(*x);
+        }
+        for x in b.iter() {
+            welch.update_b(*x);
+        }
+        let moments = |data: &[f64]| {
+            let n = data.len() as f64;
+            let mean = data.iter().sum::<f64>() / n;
+            let var = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.);
+            (mean, var / n, n)
+        };
+        let ((mean_a, se2_a, n_a), (mean_b, se2_b, n_b)) = (moments(&a), moments(&b));
+        let t = (mean_a - mean_b) / (se2_a + se2_b).sqrt();
+        let df =
+            (se2_a + se2_b).powi(2) / (se2_a.powi(2) / (n_a - 1.) + se2_b.powi(2) / (n_b - 1.));
+        assert!((welch.t_statistic() - t).abs() < 1e-9);
+        assert!((welch.degrees_of_freedom() - df).abs() < 1e-9);
+        assert!(welch.t_statistic() < -10.);
+        assert!(
+            welch.degrees_of_freedom() > n_b - 1. && welch.degrees_of_freedom() < n_a + n_b - 2.
+        );
+    }
+}
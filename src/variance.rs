@@ -2,7 +2,8 @@ use num::{Float, FromPrimitive};
 use std::ops::{AddAssign, SubAssign};
 
 use crate::mean::Mean;
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::rolling::RollingOwned;
+use crate::stats::{block_sum, fold, Mergeable, Revertable, RollableUnivariate, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running variance using Belford Algorithm.
 /// # Arguments
@@ -43,6 +44,116 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Variance<F> {
             state: F::from_f64(0.).unwrap(),
         }
     }
+    /// Variance of the values of `data`, with the divisor `n - ddof`.
+    /// # Examples
+    /// ```
+    /// use watermill::variance::Variance;
+    /// use watermill::stats::Univariate;
+    /// assert_eq!(Variance::fold(&[1., 2., 3.], 1).get(), 1.);
+    /// ```
+    pub fn fold(data: &[F], ddof: u32) -> Self {
+        fold(Self::new(ddof), data)
+    }
+    /// Variance of the values of `data` with the textbook two-pass algorithm: the first pass
+    /// computes the mean, the second one sums the squared deviations from it, with the divisor
+    /// `n - ddof`. It needs the whole slice but is more accurate than the running update when the
+    /// mean is large compared to the spread. Returns 0 when `n <= ddof`, as `get` does.
+    /// # Examples
+    /// ```
+    /// use watermill::variance::Variance;
+    /// assert_eq!(Variance::two_pass(&[1e9 + 4., 1e9 + 7., 1e9 + 13., 1e9 + 16.], 1), 30.);
+    /// ```
+    pub fn two_pass(data: &[F], ddof: u32) -> F {
+        let n = F::from_usize(data.len()).unwrap();
+        let ddof = F::from_u32(ddof).unwrap();
+        if n <= ddof {
+            return F::from_f64(0.).unwrap();
+        }
+        let mean = data
+            .iter()
+            .fold(F::from_f64(0.).unwrap(), |sum, x| sum + *x)
+            / n;
+        let m2 = data.iter().fold(F::from_f64(0.).unwrap(), |m2, x| {
+            m2 + (*x - mean) * (*x - mean)
+        });
+        m2 / (n - ddof)
+    }
+    /// Same as `Mergeable::from_slice` with the divisor `n - ddof` instead of `n - 1`.
+    /// # Examples
+    /// ```
+    /// use watermill::variance::Variance;
+    /// use watermill::stats::Univariate;
+    /// assert_eq!(Variance::from_slice_with_ddof(&[1., 2., 3., 6.], 0).get(), 3.5);
+    /// ```
+    pub fn from_slice_with_ddof(data: &[F], ddof: u32) -> Self {
+        let mean = Mean::from_slice(data);
+        let m = mean.get();
+        Self {
+            mean,
+            ddof,
+            state: block_sum(data, |x| (x - m) * (x - m)),
+        }
+    }
+    /// Population variance, `ddof = 0`: the divisor is `n`.
+    pub fn population() -> Self {
+        Self::new(0)
+    }
+    /// Sample variance, `ddof = 1`: the divisor is `n - 1`. Same as `Variance::default`.
+    pub fn sample() -> Self {
+        Self::new(1)
+    }
+    /// Standard error of the mean, `sqrt(variance / n)` with the `ddof` of the variance, usually
+    /// `1`. `NaN` until two values are seen.
+    /// # Examples
+    /// ```
+    /// use watermill::variance::Variance;
+    /// use watermill::stats::Univariate;
+    /// let mut running_variance: Variance<f64> = Variance::sample();
+    /// for x in [2., 4., 4., 4., 5., 5., 7., 9.].iter(){
+    ///     running_variance.update(*x);
+    /// }
+    /// // The sample variance is 32 / 7, over 8 values.
+    /// let sem = (4_f64 / 7.).sqrt();
+    /// assert!((running_variance.standard_error() - sem).abs() < 1e-12);
+    /// let (lower, upper) = running_variance.confidence_interval(1.96);
+    /// assert!((lower - (5. - 1.96 * sem)).abs() < 1e-12);
+    /// assert!((upper - (5. + 1.96 * sem)).abs() < 1e-12);
+    /// ```
+    pub fn standard_error(&self) -> F {
+        let n = self.mean.weight();
+        if n < F::from_f64(2.).unwrap() {
+            return F::nan();
+        }
+        (self.get().max(F::from_f64(0.).unwrap()) / n).sqrt()
+    }
+    /// Confidence interval of the mean, `(mean - z * sem, mean + z * sem)`, e.g. `z = 1.96` for
+    /// 95% under the normal approximation. Both bounds are `NaN` until two values are seen.
+    pub fn confidence_interval(&self, z: F) -> (F, F) {
+        let mean = self.mean.get();
+        let margin = z * self.standard_error();
+        (mean - margin, mean + margin)
+    }
+    /// Variance of `n` values whose mean is `mean` and whose sum of squared deviations from the
+    /// mean is `m2`, e.g. to resume from aggregates computed elsewhere.
+    /// # Examples
+    /// ```
+    /// use watermill::variance::Variance;
+    /// use watermill::stats::Univariate;
+    /// // 1, 2 and 3 have a mean of 2 and a M2 of 2.
+    /// let mut running_variance: Variance<f64> = Variance::from_parts(2., 2., 3, 1).unwrap();
+    /// running_variance.update(4.);
+    /// assert!((running_variance.get() - 5. / 3.).abs() < 1e-12);
+    /// ```
+    pub fn from_parts(mean: F, m2: F, n: usize, ddof: u32) -> Result<Self, &'static str> {
+        if m2 < F::from_f64(0.).unwrap() {
+            return Err("m2 should not be negative");
+        }
+        Ok(Self {
+            mean: Mean::from_parts(mean, n)?,
+            ddof,
+            state: m2,
+        })
+    }
 }
 
 impl<F> Default for Variance<F>
@@ -66,12 +177,46 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Varianc
         self.state += (x - mean_old) * (x - mean_new);
     }
     fn get(&self) -> F {
-        let mean_n = self.mean.n.get();
+        let mean_n = self.mean.weight();
         if mean_n > F::from_u32(self.ddof).unwrap() {
             return self.state / (mean_n - F::from_u32(self.ddof).unwrap());
         }
         F::from_f64(0.).unwrap()
     }
+    fn reset(&mut self) {
+        *self = Self::new(self.ddof);
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.mean.n_samples()
+    }
+}
+
+/// `merge` keeps the `ddof` of `self`, `from_slice` returns a variance with `ddof = 1`, see
+/// `Variance::from_slice_with_ddof` for another `ddof`. It uses two passes over the slice, one for
+/// the mean and one for the squared deviations.
+/// # Examples
+/// ```
+/// use watermill::stats::{Mergeable, Univariate};
+/// use watermill::variance::Variance;
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let mut running_variance: Variance<f64> = Variance::from_slice(&data[..2]);
+/// running_variance.merge(&Variance::from_slice(&data[2..]));
+/// assert!((running_variance.get() - 12.566666666666668).abs() < 1e-12);
+/// ```
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable<F> for Variance<F> {
+    fn merge(&mut self, other: &Self) {
+        let (n_self, n_other) = (self.mean.weight(), other.mean.weight());
+        let n = n_self + n_other;
+        if n == F::from_f64(0.).unwrap() {
+            return;
+        }
+        let delta = other.mean.get() - self.mean.get();
+        self.state += other.state + delta * delta * n_self * n_other / n;
+        self.mean.merge(&other.mean);
+    }
+    fn from_slice(data: &[F]) -> Self {
+        Self::from_slice_with_ddof(data, 1)
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Variance<F> {
@@ -84,4 +229,265 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Varianc
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Variance<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Variance<F> {
+    fn get_without(&self, x: F) -> Result<F, &'static str> {
+        let mean_old = self.mean.get();
+        let mean_new = self.mean.get_without(x)?;
+        let state = self.state - (x - mean_old) * (x - mean_new);
+        let mean_n = self.mean.weight() - F::from_f64(1.).unwrap();
+        if mean_n > F::from_u32(self.ddof).unwrap() {
+            return Ok(state / (mean_n - F::from_u32(self.ddof).unwrap()));
+        }
+        Ok(F::from_f64(0.).unwrap())
+    }
+}
+
+/// Rolling variance, see `rolling::RollingOwned`.
+/// # Examples
+/// ```
+/// use watermill::variance::{RollingVariance, Variance};
+/// use watermill::stats::Univariate;
+/// let mut rolling_variance: RollingVariance<f64> = RollingVariance::new(Variance::default(), 2).unwrap();
+/// for x in [9., 7., 3.].iter(){
+///     rolling_variance.update(*x);
+/// }
+/// assert_eq!(rolling_variance.get(), 8.);
+/// ```
+pub type RollingVariance<F> = RollingOwned<F, Variance<F>>;
+
+/// Rolling standard error of the mean, `sqrt(variance / n)` over the last `window_size` values
+/// with the sample variance (`ddof = 1`). `get` returns the standard error, `NaN` until the window
+/// holds two values.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::variance::RollingStandardError;
+/// use watermill::stats::Univariate;
+/// let mut rolling_sem: RollingStandardError<f64> = RollingStandardError::new(2).unwrap();
+/// for x in [100., 1., 3.].iter(){
+///     rolling_sem.update(*x);
+/// }
+/// // The window holds 1 and 3, their sample variance is 2.
+/// assert!((rolling_sem.get() - 1.).abs() < 1e-12);
+/// let (lower, upper) = rolling_sem.confidence_band(2.);
+/// assert!((lower - 0.).abs() < 1e-12 && (upper - 4.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingStandardError<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Variance of the window, its mean is the rolling mean.
+    pub variance: RollingVariance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingStandardError<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            variance: RollingVariance::new(Variance::sample(), window_size)?,
+        })
+    }
+    /// Rolling mean.
+    pub fn mean(&self) -> F {
+        self.variance.to_roll.mean.get()
+    }
+    /// Confidence band around the rolling mean, `(mean - z * sem, mean + z * sem)`.
+    pub fn confidence_band(&self, z: F) -> (F, F) {
+        self.variance.to_roll.confidence_interval(z)
+    }
+    /// Number of values currently in the window.
+    pub fn len(&self) -> usize {
+        self.variance.len()
+    }
+    /// Returns `true` if no value has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.variance.is_empty()
+    }
+    /// Returns `true` once the window holds `window_size` values.
+    pub fn is_full(&self) -> bool {
+        self.variance.is_full()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingStandardError<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.variance.to_roll.standard_error()
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+#[cfg(test)]
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> crate::stats::ApproxEq<F> for Variance<F> {
+    fn approx_eq(&self, other: &Self, eps: F) -> bool {
+        self.ddof == other.ddof
+            && crate::stats::close(self.state, other.state, eps)
+            && self.mean.approx_eq(&other.mean, eps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn get_without_last() {
+        use crate::stats::{RollableUnivariate, Univariate};
+        use crate::variance::Variance;
+        let data = [3., 5., 4., 7., 10., 12.];
+        let mut running_variance: Variance<f64> = Variance::default();
+        let mut without_last: Variance<f64> = Variance::default();
+        for (i, x) in data.iter().enumerate() {
+            running_variance.update(*x);
+            let without = running_variance.get_without(*x).unwrap();
+            assert!((without - without_last.get()).abs() < 1e-12);
+            if i + 1 < data.len() {
+                without_last.update(*x);
+            }
+        }
+    }
+
+    #[test]
+    fn resume_from_parts() {
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        let data: Vec<f64> = (0..101)
+            .map(|i| ((i * 37) % 17) as f64 * 0.25 - 1.)
+            .collect();
+        let mut single_pass: Variance<f64> = Variance::default();
+        for x in data.iter() {
+            single_pass.update(*x);
+        }
+        let (first_half, second_half) = data.split_at(50);
+        let n = first_half.len();
+        let mean = first_half.iter().sum::<f64>() / n as f64;
+        let m2 = first_half
+            .iter()
+            .map(|x| (x - mean) * (x - mean))
+            .sum::<f64>();
+        let mut resumed: Variance<f64> = Variance::from_parts(mean, m2, n, 1).unwrap();
+        for x in second_half.iter() {
+            resumed.update(*x);
+        }
+        assert!((resumed.get() - single_pass.get()).abs() < 1e-12);
+        assert!((resumed.mean.get() - single_pass.mean.get()).abs() < 1e-12);
+        assert!(Variance::<f64>::from_parts(0., 1., 0, 1).is_err());
+        assert!(Variance::<f64>::from_parts(0., -1., 3, 1).is_err());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        use crate::stats::{ApproxEq, Univariate};
+        use crate::variance::Variance;
+        let mut running_variance: Variance<f64> = Variance::new(0);
+        for i in 1..=10 {
+            running_variance.update(1. / i as f64);
+        }
+        let serialized = serde_json::to_string(&running_variance).unwrap();
+        let mut deserialized: Variance<f64> = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.approx_eq(&running_variance, 1e-12));
+        deserialized.update(0.);
+        running_variance.update(0.);
+        assert!(deserialized.approx_eq(&running_variance, 1e-12));
+        assert!(!deserialized.approx_eq(&Variance::new(1), 1e-12));
+    }
+    #[test]
+    fn population_and_sample_divisors() {
+        use crate::geometric::GeometricStdDev;
+        use crate::moments::MomentSummary;
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        // The sum of squared deviations of 1, 2, 3 and 4 is 5.
+        let data = [1., 2., 3., 4.];
+        let mut population: Variance<f64> = Variance::population();
+        let mut sample: Variance<f64> = Variance::sample();
+        let mut summary_population: MomentSummary<f64> = MomentSummary::population();
+        let mut summary_sample: MomentSummary<f64> = MomentSummary::sample();
+        let mut gstd_population: GeometricStdDev<f64> = GeometricStdDev::population();
+        let mut gstd_sample: GeometricStdDev<f64> = GeometricStdDev::sample();
+        for x in data {
+            population.update(x);
+            sample.update(x);
+            summary_population.update(x);
+            summary_sample.update(x);
+            gstd_population.update(x.exp());
+            gstd_sample.update(x.exp());
+        }
+        assert_eq!(population.get(), 5. / 4.);
+        assert_eq!(sample.get(), 5. / 3.);
+        assert!((summary_population.variance() - 5. / 4.).abs() < 1e-12);
+        assert!((summary_sample.variance() - 5. / 3.).abs() < 1e-12);
+        assert!((gstd_population.get().ln() - (5. / 4_f64).sqrt()).abs() < 1e-12);
+        assert!((gstd_sample.get().ln() - (5. / 3_f64).sqrt()).abs() < 1e-12);
+        assert_eq!(
+            Variance::<f64>::sample().ddof,
+            Variance::<f64>::default().ddof
+        );
+    }
+    #[test]
+    fn standard_error_needs_two_values() {
+        use crate::stats::{Revertable, Univariate};
+        use crate::variance::Variance;
+        let mut running_variance: Variance<f64> = Variance::default();
+        assert!(running_variance.standard_error().is_nan());
+        running_variance.update(1.);
+        assert!(running_variance.standard_error().is_nan());
+        assert!(running_variance.confidence_interval(2.).0.is_nan());
+        running_variance.update(3.);
+        // The sample variance of 1 and 3 is 2.
+        assert!((running_variance.standard_error() - 1.).abs() < 1e-12);
+        assert_eq!(running_variance.confidence_interval(2.), (0., 4.));
+        running_variance.revert(3.).unwrap();
+        assert!(running_variance.standard_error().is_nan());
+    }
+    #[test]
+    fn rolling_band_shrinks_as_the_window_fills() {
+        use crate::stats::Univariate;
+        use crate::variance::RollingStandardError;
+        let window_size = 64;
+        let mut rolling_sem: RollingStandardError<f64> =
+            RollingStandardError::new(window_size).unwrap();
+        let data: Vec<f64> = (0..200).map(|i| ((i * 29) % 11) as f64).collect();
+        let width = |rolling_sem: &RollingStandardError<f64>| {
+            let (lower, upper) = rolling_sem.confidence_band(1.96);
+            upper - lower
+        };
+        rolling_sem.update(data[0]);
+        assert!(width(&rolling_sem).is_nan());
+        for x in data[1..11].iter() {
+            rolling_sem.update(*x);
+        }
+        let early = width(&rolling_sem);
+        for x in data[11..window_size].iter() {
+            rolling_sem.update(*x);
+        }
+        let full = width(&rolling_sem);
+        assert!(rolling_sem.is_full());
+        assert!(full < early / 2.);
+        // Once full, the width no longer shrinks with the number of values.
+        for x in data[window_size..].iter() {
+            rolling_sem.update(*x);
+        }
+        assert!((width(&rolling_sem) / full - 1.).abs() < 0.2);
+    }
+    #[test]
+    fn two_pass_on_an_ill_conditioned_dataset() {
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        // A large mean and a small spread, the deviations are exact integers.
+        let offset = 1e9;
+        let deviations: Vec<f64> = (0..1000).map(|i| ((i * 7) % 13) as f64).collect();
+        let data: Vec<f64> = deviations.iter().map(|x| offset + x).collect();
+        let mean = deviations.iter().sum::<f64>() / 1000.;
+        let expected = deviations.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 999.;
+        let two_pass_error = (Variance::two_pass(&data, 1) - expected).abs();
+        let running_error = (Variance::fold(&data, 1).get() - expected).abs();
+        assert!(two_pass_error < 1e-9 * expected);
+        assert!(two_pass_error < running_error);
+        assert_eq!(Variance::two_pass(&[1.], 1), 0.);
+        assert_eq!(Variance::<f64>::two_pass(&[], 0), 0.);
+    }
+}
@@ -0,0 +1,102 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+/// Hides the value of a statistic until it has seen enough samples.
+/// `get` returns `NaN` until `min_samples` updates have occurred, then delegates to the wrapped statistic.
+/// # Arguments
+/// * `inner` - The statistic to gate.
+/// * `min_samples` - Number of updates required before `get` returns the wrapped value.
+/// # Examples
+/// ```
+/// use watermill::warmup::WarmUp;
+/// use watermill::variance::Variance;
+/// use watermill::stats::Univariate;
+/// let mut running_variance: WarmUp<Variance<f64>> = WarmUp::new(Variance::default(), 2);
+/// running_variance.update(1.);
+/// assert!(running_variance.get().is_nan());
+/// running_variance.update(3.);
+/// assert_eq!(running_variance.get(), 2.);
+/// ```
+/// As a window only grows until it is full, `WarmUp` acts as `min_periods` for the rolling
+/// statistics which do not take one:
+/// ```
+/// use watermill::warmup::WarmUp;
+/// use watermill::quantile::RollingQuantile;
+/// use watermill::stats::Univariate;
+/// let rolling_median: RollingQuantile<f64> = RollingQuantile::new(0.5, 5).unwrap();
+/// let mut rolling_median = WarmUp::new(rolling_median, 3);
+/// for x in [4., 1.].iter(){
+///     rolling_median.update(*x);
+///     assert!(rolling_median.get().is_nan());
+/// }
+/// rolling_median.update(2.);
+/// assert_eq!(rolling_median.get(), 2.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WarmUp<U> {
+    pub inner: U,
+    min_samples: usize,
+    n: usize,
+}
+
+impl<U> WarmUp<U> {
+    pub fn new(inner: U, min_samples: usize) -> Self {
+        Self {
+            inner,
+            min_samples,
+            n: 0,
+        }
+    }
+    /// Returns `true` once `min_samples` updates have occurred.
+    pub fn is_warm(&self) -> bool {
+        self.n >= self.min_samples
+    }
+}
+
+impl<F, U> Univariate<F> for WarmUp<U>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    U: Univariate<F>,
+{
+    fn update(&mut self, x: F) {
+        self.inner.update(x);
+        self.n = self.n.saturating_add(1);
+    }
+    fn get(&self) -> F {
+        if self.is_warm() {
+            return self.inner.get();
+        }
+        F::nan()
+    }
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.n = 0;
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn gated_until_min_samples() {
+        use crate::minimum::Min;
+        use crate::stats::Univariate;
+        use crate::warmup::WarmUp;
+        let mut running_min: WarmUp<Min<f64>> = WarmUp::new(Min::new(), 3);
+        assert!(running_min.get().is_nan());
+        for x in [5., 4.] {
+            running_min.update(x);
+            assert!(running_min.get().is_nan());
+            assert!(!running_min.is_warm());
+        }
+        running_min.update(6.);
+        assert!(running_min.is_warm());
+        assert_eq!(running_min.get(), 4.);
+        running_min.update(1.);
+        assert_eq!(running_min.get(), 1.);
+    }
+}
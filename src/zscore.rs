@@ -0,0 +1,251 @@
+use num::{Float, FromPrimitive};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::ewmean::Adjust;
+use crate::ewvariance::EWVariance;
+use crate::stats::Univariate;
+use crate::variance::{RollingVariance, Variance};
+use serde::{Deserialize, Serialize};
+/// Rolling z-score anomaly detector.
+/// Each new value is scored against the mean and the standard deviation of the `window_size`
+/// values which precede it, the value itself is only added to the window after being scored so an
+/// outlier cannot mask itself. `get` returns the z-score of the most recent value, which is `NaN`
+/// until two values have been seen. A value is an anomaly when the absolute value of its z-score
+/// is greater than `k`.
+/// # Arguments
+/// * `window_size` - Number of past values used as the reference.
+/// * `k` - Threshold, in standard deviations.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::zscore::RollingZScoreDetector;
+/// let mut detector: RollingZScoreDetector<f64> = RollingZScoreDetector::new(4, 3.).unwrap();
+/// for x in [10., 11., 9., 10., 11.].iter(){
+///     detector.update(*x);
+///     assert!(!detector.is_anomaly());
+/// }
+/// detector.update(30.);
+/// assert!(detector.is_anomaly());
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingZScoreDetector<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Variance of the reference window, its mean is the reference mean.
+    pub variance: RollingVariance<F>,
+    pub k: F,
+    z_score: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingZScoreDetector<F> {
+    pub fn new(window_size: usize, k: F) -> Result<Self, &'static str> {
+        Ok(Self {
+            variance: RollingVariance::new(Variance::default(), window_size)?,
+            k,
+            z_score: F::nan(),
+        })
+    }
+    /// Z-score of the most recent value.
+    pub fn z_score(&self) -> F {
+        self.z_score
+    }
+    /// Returns `true` if the most recent value is more than `k` standard deviations away from the
+    /// mean of the previous values.
+    pub fn is_anomaly(&self) -> bool {
+        self.z_score.abs() > self.k
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingZScoreDetector<F> {
+    fn update(&mut self, x: F) {
+        self.z_score = if self.variance.len() < 2 {
+            F::nan()
+        } else {
+            let deviation = x - self.variance.to_roll.mean.get();
+            let std = self.variance.get().sqrt();
+            if std > F::from_f64(0.).unwrap() {
+                deviation / std
+            } else if deviation == F::from_f64(0.).unwrap() {
+                F::from_f64(0.).unwrap()
+            } else {
+                F::infinity().copysign(deviation)
+            }
+        };
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.z_score
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+        self.z_score = F::nan();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        self.variance.n_samples()
+    }
+}
+
+/// Exponentially weighted z-score, an anomaly detector adapting to the stream without a window.
+/// `z_score(x)` scores `x` against the current exponentially weighted mean and standard deviation
+/// without adding it to them. `update` scores the value the same way before adding it, so an
+/// outlier cannot mask itself, and `get` returns the z-score of the most recent value. The
+/// z-scores are `NaN` until two values have been seen.
+/// # Arguments
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::zscore::EWZScore;
+/// let mut ew_z_score: EWZScore<f64> = EWZScore::new(0.5);
+/// // The mean is 11 and the variance 0.5 * (10 - 11)² + 0.5 * (12 - 11)² = 1.
+/// for x in [10., 12.].iter(){
+///     ew_z_score.update(*x);
+/// }
+/// assert_eq!(ew_z_score.z_score(14.), 3.);
+/// assert!(ew_z_score.is_anomaly(14., 2.));
+/// assert!(!ew_z_score.is_anomaly(10., 2.));
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EWZScore<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    /// Exponentially weighted variance, its mean is the exponentially weighted mean.
+    pub variance: EWVariance<F>,
+    pub alpha: F,
+    n: u64,
+    z_score: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWZScore<F> {
+    pub fn new(alpha: F) -> Self {
+        Self {
+            // `Adjust::Legacy` would restart from any value seen while the mean is exactly 0.
+            variance: EWVariance::with_adjust(alpha, Adjust::Recursive),
+            alpha,
+            n: 0,
+            z_score: F::nan(),
+        }
+    }
+    /// Z-score of `x` against the current mean and standard deviation, `x` is not added to them.
+    pub fn z_score(&self, x: F) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        if self.n < 2 {
+            return F::nan();
+        }
+        let deviation = x - self.variance.mean.get();
+        // The difference of the two means can be slightly negative.
+        let std = self.variance.get().max(zero).sqrt();
+        if std > zero {
+            deviation / std
+        } else if deviation == zero {
+            zero
+        } else {
+            F::infinity().copysign(deviation)
+        }
+    }
+    /// Returns `true` if `x` is more than `k` standard deviations away from the current mean.
+    pub fn is_anomaly(&self, x: F, k: F) -> bool {
+        self.z_score(x).abs() > k
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWZScore<F> {
+    fn update(&mut self, x: F) {
+        self.z_score = self.z_score(x);
+        self.variance.update(x);
+        self.n += 1;
+    }
+    /// A missing reading keeps the z-score of the last value and is passed on to the variance,
+    /// which decays the weight of the past values.
+    fn update_opt(&mut self, x: Option<F>) {
+        match x {
+            Some(x) => self.update(x),
+            None => self.variance.update_opt(None),
+        }
+    }
+    fn get(&self) -> F {
+        self.z_score
+    }
+    fn reset(&mut self) {
+        self.variance.reset();
+        self.n = 0;
+        self.z_score = F::nan();
+    }
+    fn n_samples(&self) -> Option<usize> {
+        Some(self.n as usize)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn outlier_in_stationary_data() {
+        use crate::stats::Univariate;
+        use crate::zscore::RollingZScoreDetector;
+        let mut detector: RollingZScoreDetector<f64> = RollingZScoreDetector::new(20, 4.).unwrap();
+        detector.update(1.);
+        assert!(detector.z_score().is_nan());
+        assert!(!detector.is_anomaly());
+        for i in 1..200 {
+            let x = if i == 150 { 25. } else { ((i * 7) % 5) as f64 };
+            detector.update(x);
+            assert_eq!(detector.is_anomaly(), i == 150);
+        }
+        // Constant data has no spread, any change is an anomaly.
+        let mut detector: RollingZScoreDetector<f64> = RollingZScoreDetector::new(3, 4.).unwrap();
+        for _ in 0..4 {
+            detector.update(2.);
+        }
+        assert_eq!(detector.get(), 0.);
+        detector.update(2.5);
+        assert_eq!(detector.get(), f64::INFINITY);
+    }
+    #[test]
+    fn spike_after_a_stable_baseline() {
+        use crate::stats::Univariate;
+        use crate::zscore::EWZScore;
+        let mut ew_z_score: EWZScore<f64> = EWZScore::new(0.1);
+        ew_z_score.update(5.);
+        assert!(ew_z_score.get().is_nan());
+        assert!(ew_z_score.z_score(5.).is_nan());
+        for i in 1..300 {
+            ew_z_score.update(5. + ((i * 7) % 5) as f64 / 10.);
+            if i > 50 {
+                assert!(ew_z_score.get().abs() < 3.);
+            }
+        }
+        let before = ew_z_score.variance.mean.get();
+        assert!(ew_z_score.z_score(20.) > 10.);
+        // Scoring does not move the baseline, updating does.
+        assert_eq!(ew_z_score.variance.mean.get(), before);
+        ew_z_score.update(20.);
+        assert!(ew_z_score.get() > 10.);
+        assert!(ew_z_score.variance.mean.get() > before);
+        // The baseline adapts, the same spike repeated stops being an anomaly.
+        for _ in 0..100 {
+            ew_z_score.update(20.);
+        }
+        assert!(!ew_z_score.is_anomaly(20., 3.));
+        ew_z_score.reset();
+        assert!(ew_z_score.get().is_nan());
+    }
+    #[test]
+    fn missing_values_reach_the_variance() {
+        use crate::ewmean::Adjust;
+        use crate::ewvariance::EWVariance;
+        use crate::stats::Univariate;
+        use crate::zscore::EWZScore;
+        let mut ew_z_score: EWZScore<f64> = EWZScore::new(0.3);
+        let mut variance: EWVariance<f64> = EWVariance::with_adjust(0.3, Adjust::Recursive);
+        for x in [Some(1.), Some(3.), None, None, Some(2.), None] {
+            ew_z_score.update_opt(x);
+            variance.update_opt(x);
+            assert_eq!(ew_z_score.variance.get(), variance.get());
+        }
+        // Skipping the gaps would weight the past values differently.
+        let mut skipped: EWVariance<f64> = EWVariance::with_adjust(0.3, Adjust::Recursive);
+        for x in [1., 3., 2.] {
+            skipped.update(x);
+        }
+        assert!((ew_z_score.variance.get() - skipped.get()).abs() > 1e-3);
+        // The last value is 2, the gap keeps its z-score.
+        assert_eq!(ew_z_score.n_samples(), Some(3));
+        assert!(ew_z_score.get().is_finite());
+    }
+}